@@ -1,2 +1,104 @@
+use interpreter::markdown::MarkdownInterpreter;
+use rongta::{RongtaPrinter, elements::Justify};
+
 pub mod interpreter;
 pub mod template;
+
+/// Render markdown into a builder wrapping at `cpl` without printing it, so the lines can
+/// be inspected or cached before they are sent to a printer
+pub fn markdown_to_builder(markdown: &str, cpl: u8) -> anyhow::Result<RongtaPrinter> {
+    MarkdownInterpreter::new(RongtaPrinter::default().with_cpl(cpl)).render(markdown)
+}
+
+/// Head a document with `title`, bold, centered and underlined above a full width divider,
+/// so printouts of several files can be told apart. The next content starts plain and left
+/// aligned below it. Meant for a builder that has nothing in it yet.
+pub fn add_title_header(builder: &mut RongtaPrinter, title: &str) -> anyhow::Result<()> {
+    builder.reset_styles();
+    builder.set_justify_content(Justify::Center);
+    builder.set_is_bold(true);
+    builder.set_is_underlined(true);
+    builder.add_content(title)?;
+    builder.new_line();
+    builder.reset_styles();
+    builder.add_content(&"-".repeat(builder.cpl() as usize))?;
+    builder.new_line();
+    builder.set_justify_content(Justify::Left);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rongta::elements::TextSize;
+
+    mod add_title_header {
+        use super::*;
+
+        #[test]
+        fn header_comes_before_the_content() {
+            let mut builder = RongtaPrinter::default().with_cpl(16);
+            add_title_header(&mut builder, "notes.md").unwrap();
+            builder.add_content("first line").unwrap();
+
+            let lines = builder.lines();
+            let texts: Vec<String> = lines
+                .iter()
+                .map(|line| line.chars.iter().map(|sc| sc.ch).collect())
+                .collect();
+            assert_eq!(texts, ["notes.md", "-".repeat(16).as_str(), "first line"]);
+            assert_eq!(lines[0].justify_content, Justify::Center);
+            assert!(
+                lines[0]
+                    .chars
+                    .iter()
+                    .all(|sc| sc.state.is_bold && sc.state.is_underlined)
+            );
+            assert_eq!(lines[2].justify_content, Justify::Left);
+            assert!(!lines[2].chars[0].state.is_bold);
+        }
+    }
+
+    mod markdown_to_builder {
+        use super::*;
+
+        #[test]
+        fn renders_heading_without_printing() {
+            let builder = markdown_to_builder("# Hi", 32).unwrap();
+            assert_eq!(builder.cpl(), 32);
+            let line = &builder.lines()[0];
+            let text: String = line.chars.iter().map(|sc| sc.ch).collect();
+            assert_eq!(text, "Hi");
+            assert_eq!(line.justify_content, Justify::Center);
+            assert!(
+                line.chars
+                    .iter()
+                    .all(|sc| { sc.state.text_size == TextSize::ExtraLarge && sc.state.is_bold })
+            );
+        }
+
+        #[test]
+        fn only_h1_is_centered() {
+            let builder = markdown_to_builder("# One\n\n## Two\n\nBody", 32).unwrap();
+            let justify: Vec<(String, Justify)> = builder
+                .lines()
+                .iter()
+                .map(|line| {
+                    (
+                        line.chars.iter().map(|sc| sc.ch).collect(),
+                        line.justify_content,
+                    )
+                })
+                .filter(|(text, _): &(String, _)| !text.is_empty())
+                .collect();
+            assert_eq!(
+                justify,
+                [
+                    ("One".to_string(), Justify::Center),
+                    ("Two".to_string(), Justify::Left),
+                    ("Body".to_string(), Justify::Left),
+                ]
+            );
+        }
+    }
+}
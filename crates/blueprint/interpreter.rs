@@ -1,3 +1,6 @@
 mod block_adornment;
+mod code_keywords;
+pub use block_adornment::{CHECKED_BOX, HeadingStyle, HeadingStyles, UNCHECKED_BOX};
+pub mod front_matter;
 pub mod markdown;
 pub mod text;
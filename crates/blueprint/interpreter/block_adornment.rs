@@ -4,6 +4,11 @@ use rongta::{
     elements::{FormatState, Justify, TextSize},
 };
 use serde::{Deserialize, Serialize};
+use std::ops::RangeInclusive;
+
+/// Task list and checklist boxes, followed by the space before the item
+pub const UNCHECKED_BOX: &str = "[ ] ";
+pub const CHECKED_BOX: &str = "[■] ";
 
 pub trait ToBuilderCommand {
     fn to_builder_command(&self, builder: &mut RongtaPrinter) -> Result<()>;
@@ -36,15 +41,29 @@ pub struct ListItemBefore {
     ordinal: Option<OrderedListType>,
     content: String,
     format: FormatState,
+    /// Columns every ordered prefix is right-aligned to
+    width: usize,
 }
 impl ListItemBefore {
-    pub fn new_ordered(ordinal: Option<OrderedListType>) -> Self {
+    /// Prefixes for the items numbered `indices`, right-aligned to the widest one so
+    /// "9. " and "10. " start their text in the same column
+    pub fn new_ordered(ordinal: Option<OrderedListType>, indices: RangeInclusive<u64>) -> Self {
+        let width = indices
+            .map(|index| {
+                Self::ordered_before_content(index, &ordinal)
+                    .chars()
+                    .count()
+            })
+            .max()
+            .unwrap_or_default();
         Self {
             content: "".to_string(),
             ordinal,
+            width,
             format: FormatState {
                 text_size: TextSize::Medium,
                 is_bold: true,
+                ..Default::default()
             },
         }
     }
@@ -54,22 +73,25 @@ impl ListItemBefore {
             format: FormatState {
                 is_bold: true,
                 text_size: TextSize::Medium,
+                ..Default::default()
             },
             ordinal: None,
+            width: 0,
         }
     }
     fn ordered_before_content(index: u64, ordinal: &Option<OrderedListType>) -> String {
         let value = match ordinal.clone().unwrap_or_default() {
             OrderedListType::LowerCaseLetter => Self::letter_for_index(index, false),
             OrderedListType::UpperCaseLetter => Self::letter_for_index(index, true),
-            OrderedListType::LowerCaseRoman => Self::roman_numeral(index, false),
-            OrderedListType::UpperCaseRoman => Self::roman_numeral(index, true),
+            OrderedListType::LowerCaseRoman => Self::roman_or_decimal(index, false),
+            OrderedListType::UpperCaseRoman => Self::roman_or_decimal(index, true),
             OrderedListType::Number => index.to_string(),
         };
         format!("{}. ", value)
     }
     pub fn next_index(&mut self, index: u64) {
-        self.content = Self::ordered_before_content(index, &self.ordinal);
+        let content = Self::ordered_before_content(index, &self.ordinal);
+        self.content = format!("{content:>width$}", width = self.width);
     }
     /// Returns the alphabetic label for a 1-based index.
     /// Examples: 1 -> "a"/"A", 26 -> "z"/"Z", 27 -> "aa"/"AA".
@@ -87,11 +109,18 @@ impl ListItemBefore {
         }
         s
     }
-    /// Returns the Roman numeral for a positive integer (1..=3999).
+    /// Roman numeral for `index`, or the decimal number when it has no Roman numeral
+    fn roman_or_decimal(index: u64, uppercase: bool) -> String {
+        Self::roman_numeral(index, uppercase).unwrap_or_else(|| {
+            log::warn!("List index {index} has no Roman numeral, numbering it as {index}");
+            index.to_string()
+        })
+    }
+    /// Returns the Roman numeral for a positive integer (1..=3999), `None` outside that range.
     /// Set `uppercase` to control casing (e.g., 4 -> "iv" or "IV").
-    fn roman_numeral(value: u64, uppercase: bool) -> String {
+    fn roman_numeral(value: u64, uppercase: bool) -> Option<String> {
         if value == 0 || value > 3999 {
-            return String::new();
+            return None;
         }
         let mut n = value;
         let vals: [u64; 13] = [1000, 900, 500, 400, 100, 90, 50, 40, 10, 9, 5, 4, 1];
@@ -105,7 +134,7 @@ impl ListItemBefore {
                 n -= v;
             }
         }
-        if uppercase { out } else { out.to_lowercase() }
+        Some(if uppercase { out } else { out.to_lowercase() })
     }
 }
 impl ToBuilderCommand for ListItemBefore {
@@ -126,16 +155,13 @@ pub struct TaskListBefore {
 }
 impl TaskListBefore {
     pub fn new(checked: bool) -> Self {
-        let content = if checked {
-            "[■] ".to_string()
-        } else {
-            "[ ] ".to_string()
-        };
+        let content = if checked { CHECKED_BOX } else { UNCHECKED_BOX }.to_string();
         Self {
             content,
             format: FormatState {
                 text_size: TextSize::Medium,
                 is_bold: true,
+                ..Default::default()
             },
         }
     }
@@ -161,6 +187,7 @@ impl HorizontalRule {
             format: FormatState {
                 text_size: TextSize::Large,
                 is_bold: true,
+                ..Default::default()
             },
         }
     }
@@ -178,24 +205,120 @@ impl ToBuilderCommand for HorizontalRule {
     }
 }
 
-pub fn set_heading_style(level: u8, builder: &mut RongtaPrinter) -> Result<()> {
-    match level {
-        1 => {
-            builder.set_text_size(TextSize::ExtraLarge);
-            builder.set_is_bold(true);
+/// Text size, decoration and alignment of one heading level
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HeadingStyle {
+    pub text_size: TextSize,
+    pub is_bold: bool,
+    pub is_underlined: bool,
+    pub justify: Justify,
+}
+impl HeadingStyle {
+    const fn new(text_size: TextSize, is_bold: bool) -> Self {
+        Self {
+            text_size,
+            is_bold,
+            is_underlined: false,
+            justify: Justify::Left,
         }
-        2 => {
-            builder.set_text_size(TextSize::Large);
-            builder.set_is_bold(true);
+    }
+}
+
+/// How headings print, one style per level from H1 to H4. H5 and H6 share the H4 style.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HeadingStyles {
+    pub levels: [HeadingStyle; 4],
+}
+impl Default for HeadingStyles {
+    fn default() -> Self {
+        Self {
+            levels: [
+                HeadingStyle {
+                    justify: Justify::Center,
+                    ..HeadingStyle::new(TextSize::ExtraLarge, true)
+                },
+                HeadingStyle::new(TextSize::Large, true),
+                HeadingStyle::new(TextSize::Large, false),
+                HeadingStyle::new(TextSize::Medium, true),
+            ],
         }
-        3 => {
-            builder.set_text_size(TextSize::Large);
-            builder.set_is_bold(false);
+    }
+}
+impl HeadingStyles {
+    /// The style of heading `level`, counting H1 as 1
+    pub fn style(&self, level: u8) -> HeadingStyle {
+        let index = (level.max(1) as usize - 1).min(self.levels.len() - 1);
+        self.levels[index]
+    }
+
+    /// Style the next characters as a heading of `level`, aligning the current line
+    pub fn apply(&self, level: u8, builder: &mut RongtaPrinter) {
+        let style = self.style(level);
+        builder.set_justify_content(style.justify);
+        builder.set_text_size(style.text_size);
+        builder.set_is_bold(style.is_bold);
+        builder.set_is_underlined(style.is_underlined);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod next_index {
+        use super::*;
+
+        #[test]
+        fn aligns_numbers_to_the_widest() {
+            let mut before = ListItemBefore::new_ordered(None, 1..=12);
+            let prefixes: Vec<String> = [1, 9, 10, 12]
+                .into_iter()
+                .map(|index| {
+                    before.next_index(index);
+                    before.content.clone()
+                })
+                .collect();
+            assert_eq!(prefixes, [" 1. ", " 9. ", "10. ", "12. "]);
+        }
+
+        #[test]
+        fn aligns_roman_numerals() {
+            let mut before =
+                ListItemBefore::new_ordered(Some(OrderedListType::UpperCaseRoman), 1..=8);
+            before.next_index(5);
+            assert_eq!(before.content, "   V. ");
+            before.next_index(8);
+            assert_eq!(before.content, "VIII. ");
         }
-        _ => {
-            builder.set_text_size(TextSize::Medium);
-            builder.set_is_bold(true);
+    }
+
+    mod ordered_before_content {
+        use super::*;
+
+        #[test]
+        fn roman_numerals_within_range() {
+            let upper = Some(OrderedListType::UpperCaseRoman);
+            let lower = Some(OrderedListType::LowerCaseRoman);
+            assert_eq!(ListItemBefore::ordered_before_content(4, &upper), "IV. ");
+            assert_eq!(
+                ListItemBefore::ordered_before_content(3999, &lower),
+                "mmmcmxcix. "
+            );
         }
-    };
-    Ok(())
+
+        #[test]
+        fn roman_overflow_falls_back_to_decimal() {
+            for ordinal in [
+                OrderedListType::UpperCaseRoman,
+                OrderedListType::LowerCaseRoman,
+            ] {
+                let ordinal = Some(ordinal);
+                assert_eq!(
+                    ListItemBefore::ordered_before_content(4000, &ordinal),
+                    "4000. "
+                );
+                assert_eq!(ListItemBefore::ordered_before_content(0, &ordinal), "0. ");
+            }
+        }
+    }
 }
@@ -0,0 +1,122 @@
+const RUST: &[&str] = &[
+    "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum", "false",
+    "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub", "ref",
+    "return", "self", "Self", "static", "struct", "super", "trait", "true", "type", "unsafe",
+    "use", "where", "while",
+];
+
+const JAVASCRIPT: &[&str] = &[
+    "async",
+    "await",
+    "break",
+    "case",
+    "catch",
+    "class",
+    "const",
+    "continue",
+    "default",
+    "delete",
+    "do",
+    "else",
+    "export",
+    "extends",
+    "false",
+    "finally",
+    "for",
+    "function",
+    "if",
+    "import",
+    "in",
+    "instanceof",
+    "let",
+    "new",
+    "null",
+    "of",
+    "return",
+    "static",
+    "super",
+    "switch",
+    "this",
+    "throw",
+    "true",
+    "try",
+    "typeof",
+    "undefined",
+    "var",
+    "void",
+    "while",
+    "yield",
+];
+
+const PYTHON: &[&str] = &[
+    "and", "as", "assert", "async", "await", "break", "class", "continue", "def", "del", "elif",
+    "else", "except", "False", "finally", "for", "from", "global", "if", "import", "in", "is",
+    "lambda", "None", "nonlocal", "not", "or", "pass", "raise", "return", "True", "try", "while",
+    "with", "yield",
+];
+
+/// Keywords of a fenced code block's `language`, `None` for languages without a set
+pub fn keywords(language: &str) -> Option<&'static [&'static str]> {
+    match language.to_ascii_lowercase().as_str() {
+        "rust" | "rs" => Some(RUST),
+        "javascript" | "js" | "typescript" | "ts" | "jsx" | "tsx" => Some(JAVASCRIPT),
+        "python" | "py" => Some(PYTHON),
+        _ => None,
+    }
+}
+
+/// Split `line` into runs, each flagged when it is one of `keywords`. Keywords only match
+/// whole identifiers, so `fn` in `fn_name` is not one.
+pub fn split_keywords<'a>(line: &'a str, keywords: &[&str]) -> Vec<(&'a str, bool)> {
+    let is_ident = |ch: char| ch.is_alphanumeric() || ch == '_';
+    let mut runs: Vec<(&str, bool)> = Vec::new();
+    let mut start = 0;
+    let mut chars = line.char_indices().peekable();
+    while let Some((index, ch)) = chars.next() {
+        let ends_run = chars
+            .peek()
+            .is_none_or(|(_, next)| is_ident(*next) != is_ident(ch));
+        if !ends_run {
+            continue;
+        }
+        let end = index + ch.len_utf8();
+        let run = &line[start..end];
+        let keyword = is_ident(ch) && keywords.contains(&run);
+        // Keep plain text in one run so styles change only around keywords
+        match runs.last_mut() {
+            Some((text, false)) if !keyword => *text = &line[start - text.len()..end],
+            _ => runs.push((run, keyword)),
+        }
+        start = end;
+    }
+    runs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod split_keywords {
+        use super::*;
+
+        #[test]
+        fn flags_whole_keywords_only() {
+            let runs = split_keywords("pub fn fn_name(x: u8) {", RUST);
+            assert_eq!(
+                runs,
+                [
+                    ("pub", true),
+                    (" ", false),
+                    ("fn", true),
+                    (" fn_name(x: u8) {", false)
+                ]
+            );
+        }
+
+        #[test]
+        fn unknown_language_has_no_keywords() {
+            assert_eq!(keywords("Python"), Some(PYTHON));
+            assert_eq!(keywords("cobol"), None);
+        }
+    }
+}
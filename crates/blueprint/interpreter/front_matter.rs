@@ -0,0 +1,145 @@
+use anyhow::{Context, Result};
+
+/// Print settings read from a `---` delimited block at the top of a markdown file.
+/// Only flat `key: value` pairs are understood; other keys are ignored. A block with any
+/// other line is a thematic break rather than front matter.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FrontMatter {
+    /// Printed as a centered H1 above the body
+    pub title: Option<String>,
+    pub cut: Option<bool>,
+    /// Rows per page
+    pub rows: Option<u32>,
+    /// Characters per line
+    pub cpl: Option<u8>,
+}
+
+impl FrontMatter {
+    fn set(&mut self, key: &str, value: &str) -> Result<()> {
+        let invalid = || format!("Invalid front matter value for '{key}': '{value}'");
+        match key {
+            "title" => self.title = Some(value.to_string()),
+            "cut" => {
+                self.cut = Some(match value {
+                    "true" | "yes" => true,
+                    "false" | "no" => false,
+                    _ => anyhow::bail!(invalid()),
+                })
+            }
+            "rows" => self.rows = Some(value.parse().with_context(invalid)?),
+            "cpl" => self.cpl = Some(value.parse().with_context(invalid)?),
+            _ => log::debug!("Ignoring front matter key '{key}'"),
+        }
+        Ok(())
+    }
+}
+
+/// The key and unquoted value of a `key: value` line, `None` for any other line
+fn parse_entry(line: &str) -> Option<(&str, &str)> {
+    let (key, value) = line.split_once(':')?;
+    let key = key.trim();
+    let is_key = !key.is_empty()
+        && key
+            .chars()
+            .all(|ch| ch.is_ascii_alphanumeric() || ch == '_' || ch == '-');
+    if !is_key {
+        return None;
+    }
+    let value = value.trim();
+    let value = value
+        .strip_prefix('"')
+        .and_then(|v| v.strip_suffix('"'))
+        .or_else(|| value.strip_prefix('\'').and_then(|v| v.strip_suffix('\'')))
+        .unwrap_or(value);
+    Some((key, value))
+}
+
+/// Split leading front matter from `markdown`, returning the settings and the remaining body.
+/// Markdown without a closed `---` block at its very start, or whose block holds anything
+/// but `key: value` lines, blanks and `#` comments, is returned whole.
+pub fn split_front_matter(markdown: &str) -> Result<(FrontMatter, &str)> {
+    let mut front_matter = FrontMatter::default();
+    let Some(rest) = markdown
+        .strip_prefix("---\n")
+        .or_else(|| markdown.strip_prefix("---\r\n"))
+    else {
+        return Ok((front_matter, markdown));
+    };
+
+    let mut start = 0;
+    for line in rest.split_inclusive('\n') {
+        let end = start + line.len();
+        if matches!(line.trim_end(), "---" | "...") {
+            let entries: Option<Vec<(&str, &str)>> = rest[..start]
+                .lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty() && !line.starts_with('#'))
+                .map(parse_entry)
+                .collect();
+            let Some(entries) = entries else {
+                log::debug!("Leading --- block isn't front matter, printing it as markdown");
+                return Ok((front_matter, markdown));
+            };
+            for (key, value) in entries {
+                front_matter.set(key, value)?;
+            }
+            return Ok((front_matter, &rest[end..]));
+        }
+        start = end;
+    }
+    Ok((front_matter, markdown))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod split_front_matter {
+        use super::*;
+
+        #[test]
+        fn reads_settings_and_strips_the_block() {
+            let markdown = "---\ntitle: \"Groceries\"\ncut: no\nrows: 20\ncpl: 32\n---\n# Hi";
+            let (front_matter, body) = split_front_matter(markdown).unwrap();
+            assert_eq!(
+                front_matter,
+                FrontMatter {
+                    title: Some("Groceries".to_string()),
+                    cut: Some(false),
+                    rows: Some(20),
+                    cpl: Some(32),
+                }
+            );
+            assert_eq!(body, "# Hi");
+        }
+
+        #[test]
+        fn leaves_markdown_without_front_matter_alone() {
+            for markdown in ["# Hi\n---\nrows: 20", "---\nrows: many\nnever closed"] {
+                let (front_matter, body) = split_front_matter(markdown).unwrap();
+                assert_eq!(front_matter, FrontMatter::default());
+                assert_eq!(body, markdown);
+            }
+        }
+
+        #[test]
+        fn leaves_text_between_thematic_breaks_alone() {
+            let markdown = "---\nA paragraph between rules.\n\nNote: keep this\n---\nMore";
+            let (front_matter, body) = split_front_matter(markdown).unwrap();
+            assert_eq!(front_matter, FrontMatter::default());
+            assert_eq!(body, markdown);
+        }
+
+        #[test]
+        fn rejects_invalid_values() {
+            assert!(split_front_matter("---\nrows: many\n---\n").is_err());
+            assert!(split_front_matter("---\ncut: maybe\n---\n").is_err());
+        }
+
+        #[test]
+        fn ignores_unknown_keys() {
+            let (front_matter, _) = split_front_matter("---\nauthor: me\n---\n").unwrap();
+            assert_eq!(front_matter, FrontMatter::default());
+        }
+    }
+}
@@ -1,35 +1,171 @@
 use crate::interpreter::block_adornment::{
-    HorizontalRule, ListItemBefore, TaskListBefore, ToBuilderCommand,
+    HeadingStyles, HorizontalRule, ListItemBefore, TaskListBefore, ToBuilderCommand,
 };
+use crate::interpreter::code_keywords;
+use crate::interpreter::front_matter::{FrontMatter, split_front_matter};
 use anyhow::Result;
-use pulldown_cmark::{Options, Parser, Tag};
-use rongta::{RongtaPrinter, SupportedDriver};
+use pulldown_cmark::{CodeBlockKind, Event, LinkType, Options, Parser, Tag, TagEnd};
+use rongta::{PrintReport, RongtaPrinter, SupportedDriver, elements::Justify};
+
+/// Paper has no strikethrough, so struck text is wrapped in this marker instead
+const STRIKETHROUGH_MARKER: &str = "--";
+
+/// Items in the list whose events are `rest`, up to its end and not counting nested lists
+fn count_list_items(rest: &[Event]) -> u64 {
+    let mut depth = 0;
+    let mut items = 0;
+    for event in rest {
+        match event {
+            Event::Start(Tag::List(_)) => depth += 1,
+            Event::End(TagEnd::List(_)) if depth == 0 => break,
+            Event::End(TagEnd::List(_)) => depth -= 1,
+            Event::Start(Tag::Item) if depth == 0 => items += 1,
+            _ => {}
+        }
+    }
+    items
+}
+
+/// How fenced and indented code blocks are printed
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum CodeBlockStyle {
+    /// Bold text
+    #[default]
+    Plain,
+    /// Bold text in reverse video (white on black) so code stands out on paper
+    Reverse,
+}
 
 pub struct MarkdownInterpreter {
     builder: RongtaPrinter,
-    list_index: Option<u64>,
+    /// One entry per open list: the ordered prefix and the next item's number, `None` for
+    /// bullet lists
+    lists: Vec<Option<(ListItemBefore, u64)>>,
+    code_block_style: CodeBlockStyle,
+    /// Bold the keywords of code blocks in a known language instead of the whole block
+    highlight_keywords: bool,
+    in_code_block: bool,
+    /// Keywords of the open code block's language, when highlighting
+    code_block_keywords: Option<&'static [&'static str]>,
+    split_on_rule: bool,
+    smart_punctuation: bool,
+    /// Bold state before each open `Strong`, restored when it closes so a bold run
+    /// inside a heading doesn't clobber the heading's own style
+    bold_before_strong: Vec<bool>,
+    /// A paragraph just ended, so the next block is preceded by a blank line
+    paragraph_ended: bool,
+    /// Rows per page from the content's front matter
+    front_matter_rows: Option<u32>,
+    /// Destination of each open link, printed after its text. `None` for autolinks, whose
+    /// text already is the destination.
+    link_urls: Vec<Option<String>>,
+    heading_styles: HeadingStyles,
 }
 impl MarkdownInterpreter {
     pub fn new(builder: RongtaPrinter) -> Self {
         Self {
             builder,
-            list_index: None,
+            lists: Vec::new(),
+            code_block_style: CodeBlockStyle::default(),
+            highlight_keywords: false,
+            in_code_block: false,
+            code_block_keywords: None,
+            split_on_rule: false,
+            smart_punctuation: false,
+            bold_before_strong: Vec::new(),
+            paragraph_ended: false,
+            front_matter_rows: None,
+            link_urls: Vec::new(),
+            heading_styles: HeadingStyles::default(),
         }
     }
 
+    /// Print headings, and the front matter title, in `styles` instead of the defaults
+    pub fn set_heading_styles(&mut self, styles: HeadingStyles) -> &mut Self {
+        self.heading_styles = styles;
+        self
+    }
+
+    pub fn set_code_block_style(&mut self, style: CodeBlockStyle) -> &mut Self {
+        self.code_block_style = style;
+        self
+    }
+
+    /// Bold only the keywords of fenced code blocks in Rust, JavaScript or Python, leaving
+    /// the rest of the code plain. Other code blocks print as before.
+    pub fn set_highlight_keywords(&mut self, highlight: bool) -> &mut Self {
+        self.highlight_keywords = highlight;
+        self
+    }
+
+    /// Parse quotes, `--` and `...` as typographic punctuation. The printer can't show it,
+    /// so it prints as the ASCII it stands for, see `RongtaPrinter::add_content`.
+    pub fn set_smart_punctuation(&mut self, smart: bool) -> &mut Self {
+        self.smart_punctuation = smart;
+        self
+    }
+
+    /// Cut the paper at each horizontal rule instead of printing a dashed line, so one
+    /// document prints as a stack of separate notes
+    pub fn set_split_on_rule(&mut self, split: bool) -> &mut Self {
+        self.split_on_rule = split;
+        self
+    }
+
+    /// Render `content` and hand back the builder without printing it
+    pub fn render(mut self, content: &str) -> Result<RongtaPrinter> {
+        self.render_content(content)?;
+        Ok(self.builder)
+    }
+
+    /// Render and print `content`. Its front matter `cut` and `cpl` replace the builder's,
+    /// within the printer profile, and its `rows` paginates the job unless `rows` is given.
     pub fn print(
         &mut self,
         content: &str,
         rows: Option<u32>,
         driver: SupportedDriver,
-    ) -> Result<()> {
+    ) -> Result<PrintReport> {
         self.render_content(content)?;
-        self.builder.print(rows, driver)?;
-        log::info!("Markdown content printed");
+        let rows = rows.or(self.front_matter_rows);
+        let report = self.builder.print_detailed(rows, driver)?;
+        log::info!("Markdown content printed: {report}");
+        Ok(report)
+    }
+
+    /// Configure the job from front matter, printing its `title` as a centered H1
+    fn apply_front_matter(&mut self, front_matter: FrontMatter) -> Result<()> {
+        let mut builder = std::mem::take(&mut self.builder);
+        if let Some(cut) = front_matter.cut {
+            builder = builder.with_cut(cut);
+        }
+        if let Some(cpl) = front_matter.cpl {
+            builder = builder.with_cpl(cpl);
+        }
+        self.builder = builder;
+        self.front_matter_rows = front_matter.rows;
+        if let Some(title) = front_matter.title {
+            self.heading_styles.apply(1, &mut self.builder);
+            // The title is centered whatever the H1 alignment
+            self.builder.set_justify_content(Justify::Center);
+            self.builder.add_content(&title)?;
+            self.builder.new_line();
+            self.builder.reset_styles();
+            self.builder.set_justify_content(Justify::Left);
+        }
         Ok(())
     }
 
-    fn handle_tag_start(&mut self, tag: &Tag) -> Result<()> {
+    /// Leave one blank line between a paragraph and the block after it
+    fn separate_from_paragraph(&mut self) {
+        if std::mem::take(&mut self.paragraph_ended) {
+            self.builder.new_line();
+        }
+    }
+
+    /// `rest` is every event after this tag, used to size ordered list numbers
+    fn handle_tag_start(&mut self, tag: &Tag, rest: &[Event]) -> Result<()> {
+        self.separate_from_paragraph();
         match tag {
             Tag::Paragraph => {
                 log::debug!("Tag start: Paragraph");
@@ -51,34 +187,74 @@ impl MarkdownInterpreter {
                     pulldown_cmark::HeadingLevel::H5 => 5,
                     pulldown_cmark::HeadingLevel::H6 => 6,
                 };
-                super::block_adornment::set_heading_style(level, &mut self.builder)
+                self.heading_styles.apply(level, &mut self.builder);
+                Ok(())
             }
-            Tag::BlockQuote(_) | Tag::CodeBlock(_) => {
-                log::debug!("Tag start: BlockQuote or CodeBlock");
+            Tag::BlockQuote(_) => {
+                log::debug!("Tag start: BlockQuote");
                 self.builder.new_line();
                 self.builder.reset_styles();
                 self.builder.set_is_bold(true);
                 Ok(())
             }
+            Tag::CodeBlock(kind) => {
+                log::debug!("Tag start: CodeBlock");
+                self.code_block_keywords = match kind {
+                    CodeBlockKind::Fenced(info) if self.highlight_keywords => info
+                        .split_whitespace()
+                        .next()
+                        .and_then(code_keywords::keywords),
+                    _ => None,
+                };
+                self.builder.new_line();
+                self.builder.reset_styles();
+                self.builder.set_is_bold(self.code_block_keywords.is_none());
+                self.builder
+                    .set_is_reversed(self.code_block_style == CodeBlockStyle::Reverse);
+                self.in_code_block = true;
+                Ok(())
+            }
             Tag::List(ordered_start) => {
                 log::debug!("Tag start: List (ordered_start={:?})", ordered_start);
-                self.list_index = *ordered_start;
+                let list = ordered_start.map(|start| {
+                    let last = start + count_list_items(rest).saturating_sub(1);
+                    (ListItemBefore::new_ordered(None, start..=last), start)
+                });
+                self.lists.push(list);
                 Ok(())
             }
             Tag::Item => {
-                log::debug!("Tag start: Item (list_index={:?})", self.list_index);
-                let before = match self.list_index {
-                    Some(i) => {
-                        let mut b = ListItemBefore::new_ordered(None);
-                        b.next_index(i);
-                        b
+                log::debug!("Tag start: Item");
+                match self.lists.last_mut() {
+                    Some(Some((before, index))) => {
+                        before.next_index(*index);
+                        *index += 1;
+                        before.to_builder_command(&mut self.builder)
                     }
-                    None => ListItemBefore::new_unordered(),
+                    _ => ListItemBefore::new_unordered().to_builder_command(&mut self.builder),
+                }
+            }
+            Tag::Strikethrough => {
+                log::debug!("Tag start: Strikethrough");
+                self.builder.add_content(STRIKETHROUGH_MARKER)
+            }
+            Tag::Link {
+                link_type,
+                dest_url,
+                ..
+            } => {
+                log::debug!("Tag start: Link ({:?} to {})", link_type, dest_url);
+                let printed_url = match link_type {
+                    LinkType::Autolink | LinkType::Email => None,
+                    _ => Some(dest_url.to_string()),
                 };
-                before.to_builder_command(&mut self.builder)
+                self.link_urls.push(printed_url);
+                Ok(())
             }
             Tag::Strong => {
                 log::debug!("Tag start: Strong");
+                self.bold_before_strong
+                    .push(self.builder.format_state().is_bold);
                 self.builder.set_is_bold(true);
                 Ok(())
             }
@@ -89,14 +265,90 @@ impl MarkdownInterpreter {
         }
     }
 
+    fn handle_tag_end(&mut self, tag: &TagEnd) -> Result<()> {
+        log::debug!("Event: End({:?})", tag);
+        match tag {
+            TagEnd::Strong => {
+                let was_bold = self.bold_before_strong.pop().unwrap_or(false);
+                self.builder.set_is_bold(was_bold);
+            }
+            // Inline spans continue on the surrounding line
+            TagEnd::Emphasis => {}
+            TagEnd::Strikethrough => self.builder.add_content(STRIKETHROUGH_MARKER)?,
+            TagEnd::Link => {
+                if let Some(url) = self.link_urls.pop().flatten() {
+                    self.builder.add_content(&format!(" ({url})"))?;
+                }
+            }
+            TagEnd::Paragraph => {
+                self.paragraph_ended = true;
+                self.builder.new_line();
+            }
+            TagEnd::List(_) => {
+                self.lists.pop();
+                self.builder.new_line();
+            }
+            TagEnd::CodeBlock => {
+                self.in_code_block = false;
+                self.builder.set_is_reversed(false);
+                self.builder.new_line();
+            }
+            _ => self.builder.new_line(),
+        }
+        Ok(())
+    }
+
+    /// Code block text arrives with embedded newlines; print each code line as its own line
+    fn render_code_block_text(&mut self, code: &str) -> Result<()> {
+        for line in code.lines() {
+            match self.code_block_keywords {
+                Some(keywords) => {
+                    for (text, keyword) in code_keywords::split_keywords(line, keywords) {
+                        self.builder.set_is_bold(keyword);
+                        self.builder.add_content(text)?;
+                    }
+                }
+                None => self.builder.add_content(line)?,
+            }
+            self.builder.new_line();
+        }
+        Ok(())
+    }
+
+    /// Inline code is printed bold and underlined, then the surrounding style is restored
+    fn render_inline_code(&mut self, code: &str) -> Result<()> {
+        let previous = self.builder.format_state();
+        self.builder.set_is_bold(true);
+        self.builder.set_is_underlined(true);
+        self.builder.add_content(code)?;
+        self.builder.set_format_state(previous);
+        Ok(())
+    }
+
+    /// The printer can't typeset LaTeX, so display math is printed verbatim on its own line
+    fn render_display_math(&mut self, math: &str) -> Result<()> {
+        self.builder.new_line();
+        self.builder.add_content(&format!("$${}$$", math.trim()))?;
+        self.builder.new_line();
+        Ok(())
+    }
+
     fn render_content(&mut self, markdown: &str) -> Result<()> {
-        for event in Parser::new_ext(markdown, Options::ENABLE_TASKLISTS) {
-            match &event {
-                pulldown_cmark::Event::Start(tag) => self.handle_tag_start(tag),
-                pulldown_cmark::Event::End(tag) => {
-                    log::debug!("Event: End({:?})", tag);
-                    self.builder.new_line();
-                    continue;
+        let (front_matter, markdown) = split_front_matter(markdown)?;
+        self.apply_front_matter(front_matter)?;
+        let mut options =
+            Options::ENABLE_TASKLISTS | Options::ENABLE_MATH | Options::ENABLE_STRIKETHROUGH;
+        options.set(Options::ENABLE_SMART_PUNCTUATION, self.smart_punctuation);
+        let events: Vec<Event> = Parser::new_ext(markdown, options).collect();
+        for (index, event) in events.iter().enumerate() {
+            match event {
+                pulldown_cmark::Event::Start(tag) => {
+                    self.handle_tag_start(tag, &events[index + 1..])
+                }
+                pulldown_cmark::Event::End(tag) => self.handle_tag_end(tag),
+                pulldown_cmark::Event::Text(cow_str) if self.in_code_block => {
+                    log::debug!("Event: Text(\"{}\") in code block", cow_str);
+                    self.render_code_block_text(cow_str)
                 }
                 pulldown_cmark::Event::Text(cow_str) => {
                     log::debug!("Event: Text(\"{}\")", cow_str);
@@ -104,15 +356,15 @@ impl MarkdownInterpreter {
                 }
                 pulldown_cmark::Event::Code(code) => {
                     log::debug!("Event: Code(\"{}\")", code);
-                    continue;
+                    self.render_inline_code(code)
                 }
                 pulldown_cmark::Event::InlineMath(math) => {
                     log::debug!("Event: InlineMath(\"{}\")", math);
-                    continue;
+                    self.builder.add_content(&format!("${math}$"))
                 }
                 pulldown_cmark::Event::DisplayMath(math) => {
                     log::debug!("Event: DisplayMath(\"{}\")", math);
-                    continue;
+                    self.render_display_math(math)
                 }
                 pulldown_cmark::Event::Html(html) => {
                     log::debug!("Event: Html(\"{}\")", html);
@@ -137,8 +389,15 @@ impl MarkdownInterpreter {
                     self.builder.new_line();
                     continue;
                 }
+                pulldown_cmark::Event::Rule if self.split_on_rule => {
+                    log::debug!("Event: Rule, splitting");
+                    self.paragraph_ended = false;
+                    self.builder.page_break();
+                    continue;
+                }
                 pulldown_cmark::Event::Rule => {
                     log::debug!("Event: Rule");
+                    self.separate_from_paragraph();
                     let r = HorizontalRule::new();
                     r.to_builder_command(&mut self.builder)
                 }
@@ -152,3 +411,478 @@ impl MarkdownInterpreter {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rongta::elements::StyledChar;
+
+    fn render(markdown: &str) -> RongtaPrinter {
+        let mut interpreter = MarkdownInterpreter::new(RongtaPrinter::new(false));
+        interpreter.render_content(markdown).unwrap();
+        interpreter.builder
+    }
+
+    fn styled_chars(builder: &RongtaPrinter) -> Vec<StyledChar> {
+        builder
+            .lines()
+            .iter()
+            .flat_map(|line| line.chars.iter().cloned())
+            .collect()
+    }
+
+    fn rendered_lines(markdown: &str) -> Vec<String> {
+        render(markdown)
+            .lines()
+            .iter()
+            .map(|line| line.chars.iter().map(|sc| sc.ch).collect())
+            .collect()
+    }
+
+    mod set_heading_styles {
+        use super::*;
+        use crate::interpreter::HeadingStyle;
+        use rongta::elements::{FormatState, TextSize};
+
+        fn heading_state(interpreter: MarkdownInterpreter, markdown: &str) -> FormatState {
+            let builder = interpreter.render(markdown).unwrap();
+            styled_chars(&builder)[0].state
+        }
+
+        #[test]
+        fn default_styles_match_the_config() {
+            let h2 = HeadingStyles::default().style(2);
+            let state = heading_state(
+                MarkdownInterpreter::new(RongtaPrinter::new(false)),
+                "## Two",
+            );
+            assert_eq!(state.text_size, h2.text_size);
+            assert_eq!(state.is_bold, h2.is_bold);
+            assert_eq!(state.is_underlined, h2.is_underlined);
+        }
+
+        #[test]
+        fn custom_styles_change_headings_and_title() {
+            let mut styles = HeadingStyles::default();
+            styles.levels[0] = HeadingStyle {
+                text_size: TextSize::Medium,
+                is_bold: false,
+                is_underlined: true,
+                justify: Justify::Left,
+            };
+            styles.levels[1].is_underlined = true;
+
+            let mut interpreter = MarkdownInterpreter::new(RongtaPrinter::new(false));
+            interpreter.set_heading_styles(styles.clone());
+            let state = heading_state(interpreter, "## Two");
+            assert!(state.is_underlined);
+            assert_eq!(state.text_size, TextSize::Large);
+
+            let mut interpreter = MarkdownInterpreter::new(RongtaPrinter::new(false));
+            interpreter.set_heading_styles(styles);
+            let state = heading_state(interpreter, "---\ntitle: Notes\n---\nbody");
+            assert_eq!(state.text_size, TextSize::Medium);
+            assert!(!state.is_bold && state.is_underlined);
+        }
+
+        #[test]
+        fn deep_levels_share_the_last_style() {
+            let styles = HeadingStyles::default();
+            assert_eq!(styles.style(6), styles.style(4));
+            assert_eq!(styles.style(0), styles.style(1));
+        }
+    }
+
+    mod ordered_list {
+        use super::*;
+
+        #[test]
+        fn numbers_are_aligned_to_the_widest() {
+            let markdown: String = (1..=12).map(|i| format!("{i}. item\n")).collect();
+            let lines: Vec<String> = rendered_lines(&markdown)
+                .into_iter()
+                .filter(|line| !line.is_empty())
+                .collect();
+            assert_eq!(lines.len(), 12);
+            assert_eq!(lines[0], " 1. item");
+            assert_eq!(lines[8], " 9. item");
+            assert_eq!(lines[9], "10. item");
+            assert_eq!(lines[11], "12. item");
+        }
+
+        #[test]
+        fn nested_lists_keep_their_own_numbering() {
+            let lines: Vec<String> = rendered_lines("1. a\n   1. x\n   2. y\n2. b\n")
+                .into_iter()
+                .filter(|line| !line.is_empty())
+                .collect();
+            assert_eq!(lines, ["1. a", "1. x", "2. y", "2. b"]);
+        }
+    }
+
+    mod math {
+        use super::*;
+
+        #[test]
+        fn inline_math_is_printed_between_dollars() {
+            let lines = rendered_lines("Area is $\\pi r^2$ exactly");
+            assert!(lines.iter().any(|l| l == "Area is $\\pi r^2$ exactly"));
+        }
+
+        #[test]
+        fn display_math_is_printed_on_its_own_line() {
+            let lines = rendered_lines("Euler:\n\n$$e^{i\\pi} + 1 = 0$$\n");
+            assert!(lines.iter().any(|l| l == "$$e^{i\\pi} + 1 = 0$$"));
+        }
+    }
+
+    mod code_block_style {
+        use super::*;
+        use rongta::printer::DumpDriver;
+
+        const REVERSE_ON: [u8; 3] = [0x1D, b'B', 1];
+        const REVERSE_OFF: [u8; 3] = [0x1D, b'B', 0];
+
+        fn position(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+            haystack.windows(needle.len()).position(|w| w == needle)
+        }
+
+        fn printed(style: CodeBlockStyle) -> Vec<u8> {
+            let driver = DumpDriver::new();
+            let mut interpreter = MarkdownInterpreter::new(RongtaPrinter::new(false));
+            interpreter.set_code_block_style(style);
+            interpreter
+                .print(
+                    "Before\n\n```\nlet x = 1;\n```\n\nAfter",
+                    None,
+                    SupportedDriver::Dump(driver.clone()),
+                )
+                .unwrap();
+            driver.bytes()
+        }
+
+        #[test]
+        fn reverse_wraps_code_in_reverse_commands() {
+            let bytes = printed(CodeBlockStyle::Reverse);
+            let before = position(&bytes, b"Before").unwrap();
+            let on = position(&bytes, &REVERSE_ON).unwrap();
+            let code = position(&bytes, b"let x = 1;").unwrap();
+            let off = on + position(&bytes[on..], &REVERSE_OFF).unwrap();
+            let after = position(&bytes, b"After").unwrap();
+            assert!(before < on && on < code && code < off && off < after);
+        }
+
+        #[test]
+        fn highlighting_bolds_only_keywords() {
+            let mut interpreter = MarkdownInterpreter::new(RongtaPrinter::new(false));
+            interpreter.set_highlight_keywords(true);
+            let builder = interpreter
+                .render("```rust\nfn fn_name() {}\n```\n\n```\nfn plain() {}\n```")
+                .unwrap();
+            let lines: Vec<&rongta::line::Line> = builder
+                .lines()
+                .iter()
+                .filter(|line| !line.chars.is_empty())
+                .collect();
+            let bold: Vec<bool> = lines[0].chars.iter().map(|sc| sc.state.is_bold).collect();
+            // `fn` is bold, the space and `fn_name() {}` are not
+            assert_eq!(bold[..4], [true, true, false, false]);
+            assert!(bold[3..].iter().all(|bold| !bold));
+            // Without a language the whole block stays bold
+            assert!(lines[1].chars.iter().all(|sc| sc.state.is_bold));
+        }
+
+        #[test]
+        fn plain_never_enables_reverse() {
+            assert!(position(&printed(CodeBlockStyle::Plain), &REVERSE_ON).is_none());
+        }
+
+        #[test]
+        fn code_lines_are_split() {
+            let lines = rendered_lines("```\nfn a() {}\nfn b() {}\n```");
+            assert!(lines.iter().any(|l| l == "fn a() {}"));
+            assert!(lines.iter().any(|l| l == "fn b() {}"));
+        }
+    }
+
+    mod inline_code {
+        use super::*;
+
+        #[test]
+        fn renders_bold_and_underlined() {
+            let chars = styled_chars(&render("run `ls` now"));
+            let code: Vec<_> = chars.iter().filter(|sc| sc.ch == 'l').collect();
+            assert_eq!(code.len(), 1);
+            assert!(code[0].state.is_bold && code[0].state.is_underlined);
+
+            let after = chars.iter().find(|sc| sc.ch == 'w').unwrap();
+            assert!(!after.state.is_bold && !after.state.is_underlined);
+        }
+    }
+
+    mod strong {
+        use super::*;
+        use rongta::elements::TextSize;
+
+        #[test]
+        fn bold_run_in_heading_keeps_heading_style() {
+            let builder = render("### Due **today** at noon");
+            assert_eq!(
+                rendered_lines("### Due **today** at noon")[0],
+                "Due today at noon"
+            );
+            let chars = &builder.lines()[0].chars;
+            assert!(chars.iter().all(|sc| sc.state.text_size == TextSize::Large));
+            let bold: String = chars
+                .iter()
+                .filter(|sc| sc.state.is_bold)
+                .map(|sc| sc.ch)
+                .collect();
+            assert_eq!(bold, "today");
+        }
+
+        #[test]
+        fn bold_heading_stays_bold_after_run() {
+            let builder = render("# Hi **there** you");
+            let chars = &builder.lines()[0].chars;
+            assert!(chars.iter().all(|sc| sc.state.is_bold));
+            assert!(
+                chars
+                    .iter()
+                    .all(|sc| sc.state.text_size == TextSize::ExtraLarge)
+            );
+        }
+
+        #[test]
+        fn paragraph_bold_ends_with_run() {
+            let builder = render("plain **bold** plain");
+            let chars = styled_chars(&builder);
+            let bold: String = chars
+                .iter()
+                .filter(|sc| sc.state.is_bold)
+                .map(|sc| sc.ch)
+                .collect();
+            assert_eq!(bold, "bold");
+            assert_eq!(
+                rendered_lines("plain **bold** plain")[0],
+                "plain bold plain"
+            );
+        }
+    }
+
+    mod paragraph {
+        use super::*;
+
+        #[test]
+        fn consecutive_paragraphs_have_one_blank_line_between() {
+            assert_eq!(
+                rendered_lines("first\n\nsecond\n\nthird"),
+                ["first", "", "second", "", "third", ""]
+            );
+        }
+
+        #[test]
+        fn blank_line_before_following_heading() {
+            assert_eq!(rendered_lines("text\n\n## Next"), ["text", "", "Next", ""]);
+        }
+
+        #[test]
+        fn soft_breaks_stay_within_paragraph() {
+            assert_eq!(rendered_lines("one\ntwo"), ["one", "two", ""]);
+        }
+    }
+
+    mod front_matter {
+        use super::*;
+        use rongta::{elements::TextSize, printer::DumpDriver, profile::PrinterModel};
+
+        fn print(markdown: &str) -> PrintReport {
+            let mut interpreter = MarkdownInterpreter::new(RongtaPrinter::new(false));
+            interpreter
+                .print(markdown, None, SupportedDriver::Dump(DumpDriver::new()))
+                .unwrap()
+        }
+
+        #[test]
+        fn rows_paginate_and_body_renders_as_heading() {
+            let markdown = "---\nrows: 20\ncut: true\n---\n# Hi";
+            let builder = render(markdown);
+            let line = &builder.lines()[0];
+            let text: String = line.chars.iter().map(|sc| sc.ch).collect();
+            assert_eq!(text, "Hi");
+            assert!(
+                line.chars
+                    .iter()
+                    .all(|sc| sc.state.text_size == TextSize::ExtraLarge)
+            );
+
+            let report = print(markdown);
+            assert_eq!(report.pages, 1);
+            assert!(report.cut);
+            let long = format!("{markdown}\n\n{}", "line\n\n".repeat(25));
+            assert_eq!(print(&long).pages, 3);
+        }
+
+        #[test]
+        fn title_is_a_centered_heading() {
+            let builder = render("---\ntitle: Groceries\n---\nMilk");
+            let lines = builder.lines();
+            let title: String = lines[0].chars.iter().map(|sc| sc.ch).collect();
+            assert_eq!(title, "Groceries");
+            assert_eq!(lines[0].justify_content, Justify::Center);
+            assert!(lines[0].chars.iter().all(|sc| sc.state.is_bold));
+            let body = lines
+                .iter()
+                .find(|line| line.chars.iter().map(|sc| sc.ch).collect::<String>() == "Milk")
+                .unwrap();
+            assert_eq!(body.justify_content, Justify::Left);
+            assert!(body.chars.iter().all(|sc| !sc.state.is_bold));
+        }
+
+        #[test]
+        fn cpl_and_cut_configure_the_builder() {
+            let builder = render("---\ncpl: 32\n---\nbody");
+            assert_eq!(builder.cpl(), 32);
+            let report = print("---\ncut: true\n---\nbody");
+            assert!(report.cut);
+        }
+
+        #[test]
+        fn cpl_and_cut_stay_within_the_printer_profile() {
+            let rp58 = PrinterModel::RongtaRp58.profile().unwrap();
+            let interpreter =
+                || MarkdownInterpreter::new(RongtaPrinter::new(false).with_profile(&rp58));
+            let builder = interpreter().render("---\ncpl: 48\n---\nbody").unwrap();
+            assert_eq!(builder.cpl(), 32);
+            let report = interpreter()
+                .print(
+                    "---\ncut: true\n---\nbody",
+                    None,
+                    SupportedDriver::Dump(DumpDriver::new()),
+                )
+                .unwrap();
+            assert!(!report.cut);
+        }
+
+        #[test]
+        fn block_is_not_printed() {
+            let lines = rendered_lines("---\nrows: 20\n---\nbody");
+            assert!(lines.iter().all(|line| !line.contains("rows")));
+        }
+    }
+
+    mod set_smart_punctuation {
+        use super::*;
+
+        #[test]
+        fn smart_quotes_print_straight() {
+            let mut interpreter = MarkdownInterpreter::new(RongtaPrinter::new(false));
+            interpreter.set_smart_punctuation(true);
+            let builder = interpreter.render("\"Don't\" -- she said...").unwrap();
+            let text: String = builder.lines()[0].chars.iter().map(|sc| sc.ch).collect();
+            assert_eq!(text, "\"Don't\" - she said...");
+        }
+
+        #[test]
+        fn escaped_asterisk_is_literal() {
+            let builder = render("2 \\* 3 \\*not emphasis\\*");
+            let chars = styled_chars(&builder);
+            let text: String = chars.iter().map(|sc| sc.ch).collect();
+            assert_eq!(text, "2 * 3 *not emphasis*");
+            assert!(chars.iter().all(|sc| !sc.state.is_bold));
+        }
+    }
+
+    mod strikethrough {
+        use super::*;
+
+        #[test]
+        fn wraps_struck_text_in_markers() {
+            let lines = rendered_lines("Buy ~~milk~~ eggs");
+            assert!(lines.iter().any(|l| l == "Buy --milk-- eggs"));
+        }
+    }
+
+    mod link {
+        use super::*;
+
+        #[test]
+        fn prints_url_after_text() {
+            let lines = rendered_lines("See [the docs](https://example.com/docs) first");
+            assert!(
+                lines
+                    .iter()
+                    .any(|l| l == "See the docs (https://example.com/docs) first")
+            );
+        }
+
+        #[test]
+        fn autolink_is_printed_once() {
+            let lines = rendered_lines("<https://example.com>");
+            assert!(lines.iter().any(|l| l == "https://example.com"));
+        }
+
+        #[test]
+        fn reference_links_print_the_resolved_url() {
+            let markdown = "Read [the guide][guide] and [FAQ][].\n\n\
+                            [guide]: https://example.com/guide\n\
+                            [faq]: https://example.com/faq \"Questions\"\n";
+            // Word wrap drops the space at each break
+            let lines = rendered_lines(markdown);
+            let text: Vec<&str> = lines
+                .iter()
+                .map(String::as_str)
+                .filter(|l| !l.is_empty())
+                .collect();
+            assert_eq!(
+                text.join(" "),
+                "Read the guide (https://example.com/guide) and FAQ (https://example.com/faq)."
+            );
+        }
+    }
+
+    mod split_on_rule {
+        use super::*;
+        use rongta::printer::DumpDriver;
+
+        const CUT: [u8; 4] = [0x1D, b'V', b'A', 0];
+
+        fn printed(split: bool) -> (PrintReport, Vec<u8>) {
+            let driver = DumpDriver::new();
+            let mut interpreter = MarkdownInterpreter::new(RongtaPrinter::new(true));
+            interpreter.set_split_on_rule(split);
+            let report = interpreter
+                .print(
+                    "First note\n\n---\n\nSecond note",
+                    None,
+                    SupportedDriver::Dump(driver.clone()),
+                )
+                .unwrap();
+            (report, driver.bytes())
+        }
+
+        fn cuts(bytes: &[u8]) -> usize {
+            bytes.windows(CUT.len()).filter(|w| *w == CUT).count()
+        }
+
+        #[test]
+        fn cuts_between_notes() {
+            let (report, bytes) = printed(true);
+            assert_eq!(report.pages, 2);
+            assert_eq!(cuts(&bytes), 2);
+            let first = bytes.windows(10).position(|w| w == b"First note").unwrap();
+            let cut = bytes.windows(CUT.len()).position(|w| w == CUT).unwrap();
+            let second = bytes.windows(11).position(|w| w == b"Second note").unwrap();
+            assert!(first < cut && cut < second);
+            assert!(!bytes.windows(12).any(|w| w == b"------------"));
+        }
+
+        #[test]
+        fn prints_a_rule_by_default() {
+            let (report, bytes) = printed(false);
+            assert_eq!(report.pages, 1);
+            assert_eq!(cuts(&bytes), 1);
+            assert!(bytes.windows(12).any(|w| w == b"------------"));
+        }
+    }
+}
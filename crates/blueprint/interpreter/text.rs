@@ -1,17 +1,95 @@
 use anyhow::Result;
-use rongta::SupportedDriver;
+use rongta::{PrintReport, RongtaPrinter, SupportedDriver};
 
 pub struct TextInterpreter;
 
 impl TextInterpreter {
-    pub fn print(content: &str, cut: bool, driver: SupportedDriver) -> Result<()> {
-        let mut printer = rongta::build_any_printer(driver)?;
-        printer.write(content)?;
-        match cut {
-            true => printer.print_cut()?,
-            false => printer.print()?,
+    /// Print `content` as is, with the job settings of `builder`, see
+    /// `RongtaPrinter::print_raw`
+    pub fn print(
+        content: &str,
+        builder: &RongtaPrinter,
+        driver: SupportedDriver,
+    ) -> Result<PrintReport> {
+        let report = builder.print_raw(content, driver)?;
+        log::info!("Text content printed: {report}");
+        Ok(report)
+    }
+
+    /// Print `content` with a line number gutter, see `line_numbered`
+    pub fn print_with_line_numbers(
+        content: &str,
+        mut builder: RongtaPrinter,
+        rows: Option<u32>,
+        driver: SupportedDriver,
+    ) -> Result<PrintReport> {
+        for line in line_numbered(content, builder.cpl())? {
+            builder.add_content(&line)?;
+            builder.new_line();
+        }
+        let report = builder.print_detailed(rows, driver)?;
+        log::info!("Line numbered text printed: {report}");
+        Ok(report)
+    }
+}
+
+/// Prefix each source line with a right-aligned line number gutter (`" 12 | "`). Source lines
+/// wrap within what's left of `cpl` and their continuation lines get a blank gutter.
+pub fn line_numbered(content: &str, cpl: u8) -> Result<Vec<String>> {
+    let number_width = content.lines().count().max(1).to_string().len();
+    let gutter_width = number_width + " | ".len();
+    let text_cpl = (cpl as usize).saturating_sub(gutter_width).max(1) as u8;
+
+    let mut numbered = Vec::new();
+    for (index, source_line) in content.lines().enumerate() {
+        let mut wrapped = RongtaPrinter::default().with_cpl(text_cpl);
+        wrapped.add_content(source_line)?;
+        for (wrap_index, line) in wrapped.lines().iter().enumerate() {
+            let text: String = line.chars.iter().map(|sc| sc.ch).collect();
+            let number = match wrap_index {
+                0 => (index + 1).to_string(),
+                _ => String::new(),
+            };
+            numbered.push(format!("{number:>number_width$} | {text}"));
+        }
+    }
+    Ok(numbered)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod line_numbered {
+        use super::*;
+
+        #[test]
+        fn numbers_each_line() {
+            let lines = line_numbered("fn main() {\n    run();\n}", 48).unwrap();
+            assert_eq!(lines, ["1 | fn main() {", "2 |     run();", "3 | }"]);
+        }
+
+        #[test]
+        fn wrapped_continuation_gets_blank_gutter() {
+            let lines = line_numbered("short\nthis line is too long to fit", 16).unwrap();
+            assert_eq!(
+                lines,
+                [
+                    "1 | short",
+                    "2 | this line is",
+                    "  | too long to",
+                    "  | fit"
+                ]
+            );
+            assert!(lines.iter().all(|line| line.chars().count() <= 16));
+        }
+
+        #[test]
+        fn pads_numbers_to_the_widest() {
+            let content = ["x"; 10].join("\n");
+            let lines = line_numbered(&content, 48).unwrap();
+            assert_eq!(lines[0], " 1 | x");
+            assert_eq!(lines[9], "10 | x");
         }
-        log::info!("Text content printed");
-        Ok(())
     }
 }
@@ -1,19 +1,56 @@
 pub mod box_outline;
+pub mod calendar;
+pub mod checklist;
 pub mod habit_tracker;
-use anyhow::Context;
+pub mod table;
+mod timezone;
+use anyhow::{Context, bail};
 use rand::seq::IndexedRandom;
-use std::path::PathBuf;
+use std::{
+    path::{Path, PathBuf},
+    sync::LazyLock,
+};
+pub use timezone::BannerTimezone;
 
 fn konan_templates() -> PathBuf {
     PathBuf::from(concat!(env!("CARGO_MANIFEST_DIR"), "/template"))
 }
 
-fn get_box_patterns() -> anyhow::Result<Vec<BoxPattern>> {
-    let patterns_path = konan_templates().join("box_patterns.txt");
-    let content = std::fs::read_to_string(patterns_path)?;
+/// Box patterns parsed once from `box_patterns.txt` on first use, or the fallback pattern
+/// when the file can't provide any
+static BOX_PATTERNS: LazyLock<Vec<BoxPattern>> = LazyLock::new(|| {
+    load_box_patterns(&konan_templates().join("box_patterns.txt")).unwrap_or_else(|e| {
+        log::warn!("{e:#}, using the default box pattern");
+        vec![BoxPattern::fallback()]
+    })
+});
+
+/// Read the patterns at `patterns_path`, telling a missing file, an empty file and a file
+/// without a complete pattern apart
+fn load_box_patterns(patterns_path: &Path) -> anyhow::Result<Vec<BoxPattern>> {
+    let content = std::fs::read_to_string(patterns_path).with_context(|| {
+        format!(
+            "Failed to read box patterns at '{}'",
+            patterns_path.display()
+        )
+    })?;
+    if content.trim().is_empty() {
+        bail!("Box patterns file '{}' is empty", patterns_path.display());
+    }
+    let patterns = parse_box_patterns(&content);
+    if patterns.is_empty() {
+        bail!(
+            "Box patterns file '{}' has no valid patterns, each needs a top, row and bottom line",
+            patterns_path.display()
+        );
+    }
+    Ok(patterns)
+}
+
+fn parse_box_patterns(content: &str) -> Vec<BoxPattern> {
     let lines: Vec<&str> = content.lines().collect();
 
-    let templates = lines
+    lines
         .chunks(4) // Each pattern is 3 lines + 1 empty separator
         .filter_map(|chunk| {
             if chunk.len() >= 3 {
@@ -26,14 +63,16 @@ fn get_box_patterns() -> anyhow::Result<Vec<BoxPattern>> {
                 None
             }
         })
-        .collect();
+        .collect()
+}
 
-    Ok(templates)
+fn get_box_patterns() -> &'static [BoxPattern] {
+    &BOX_PATTERNS
 }
 
 pub fn get_random_box_pattern() -> anyhow::Result<BoxPattern> {
     let mut random = rand::rng();
-    let templates = get_box_patterns()?;
+    let templates = get_box_patterns();
     let random_template = templates
         .choose(&mut random)
         .with_context(|| "Failed to choose a random template")?;
@@ -44,9 +83,345 @@ pub fn get_random_box_pattern() -> anyhow::Result<BoxPattern> {
     Ok(random_template.to_owned())
 }
 
-#[derive(Clone)]
+/// Lay `labels` out in equal columns across `width`, each label at the start of its column.
+/// Fails when a label doesn't fit its column with a space to spare.
+pub fn column_row(labels: &[&str], width: usize) -> anyhow::Result<String> {
+    anyhow::ensure!(!labels.is_empty(), "At least one column label is required");
+    let column_width = width / labels.len();
+    let mut row = String::with_capacity(width);
+    for label in labels {
+        let label_width = label.chars().count();
+        anyhow::ensure!(
+            label_width < column_width,
+            "Column label '{label}' is {label_width} columns but only {} fit",
+            column_width.saturating_sub(1)
+        );
+        row.push_str(label);
+        row.push_str(&" ".repeat(column_width - label_width));
+    }
+    Ok(row.trim_end().to_string())
+}
+
+/// Put `left` at the start and `right` at the end of a `width` wide row, keeping at least
+/// one space between them. Rows that can't fit both are left for the printer to wrap.
+pub fn spread_row(left: &str, right: &str, width: usize) -> String {
+    let used = left.chars().count() + right.chars().count();
+    let gap = width.saturating_sub(used).max(1);
+    format!("{left}{}{right}", " ".repeat(gap))
+}
+
+#[derive(Clone, Debug)]
 pub struct BoxPattern {
     pub top: String,
     pub row: String,
     pub bottom: String,
 }
+impl BoxPattern {
+    /// The plain 48 column box used when `box_patterns.txt` can't be read
+    fn fallback() -> BoxPattern {
+        BoxPattern {
+            top: format!(".{}.", "-".repeat(46)),
+            row: format!("|{}|", " ".repeat(46)),
+            bottom: format!("'{}'", "-".repeat(46)),
+        }
+    }
+
+    /// Shrink the pattern to `width` columns, trimming from the interior so each line keeps
+    /// as many closing characters as the row's right edge, e.g. both of `">>"`.
+    /// Patterns that already fit are returned unchanged.
+    pub fn fit_to(&self, width: usize) -> BoxPattern {
+        let edge = self.row_edges().2.chars().count().max(1);
+        let fit = |line: &str| {
+            let chars: Vec<char> = line.chars().collect();
+            if chars.len() <= width || width <= edge {
+                return line.to_string();
+            }
+            chars[..width - edge]
+                .iter()
+                .chain(&chars[chars.len() - edge..])
+                .collect()
+        };
+        BoxPattern {
+            top: fit(&self.top),
+            row: fit(&self.row),
+            bottom: fit(&self.bottom),
+        }
+    }
+
+    /// Check that every line of the pattern has content
+    pub fn validate(&self) -> anyhow::Result<()> {
+        for (name, line) in [
+            ("top", &self.top),
+            ("row", &self.row),
+            ("bottom", &self.bottom),
+        ] {
+            anyhow::ensure!(!line.is_empty(), "Box pattern {name} line is empty");
+        }
+        Ok(())
+    }
+
+    /// Split the row into its left edge, inner width and right edge, the edges being the
+    /// runs either side of its interior spaces. e.g. `"<<    >>"` is `("<<", 4, ">>")`.
+    /// A row without interior spaces falls back to its first and last characters.
+    fn row_edges(&self) -> (&str, usize, &str) {
+        let interior = self
+            .row
+            .find(char::is_whitespace)
+            .zip(self.row.rfind(char::is_whitespace));
+        let (left_end, right_start) = match interior {
+            Some((first, last)) => {
+                let last_len = self.row[last..].chars().next().map_or(0, char::len_utf8);
+                (first, last + last_len)
+            }
+            None => {
+                let left_end = self
+                    .row
+                    .char_indices()
+                    .nth(1)
+                    .map_or(self.row.len(), |(index, _)| index);
+                let right_start = self
+                    .row
+                    .char_indices()
+                    .last()
+                    .map_or(0, |(index, _)| index)
+                    .max(left_end);
+                (left_end, right_start)
+            }
+        };
+        let inner = &self.row[left_end..right_start];
+        (
+            &self.row[..left_end],
+            inner.chars().count(),
+            &self.row[right_start..],
+        )
+    }
+
+    /// Columns available between the row's edges
+    pub fn inner_width(&self) -> usize {
+        self.row_edges().1
+    }
+
+    /// Pad `line` between the row's edges, or `None` when it is wider than the inner width
+    pub fn frame_row(&self, line: &str) -> Option<String> {
+        let (left, inner_width, right) = self.row_edges();
+        let width = line.chars().count();
+        (width <= inner_width)
+            .then(|| format!("{left}{line}{}{right}", " ".repeat(inner_width - width)))
+    }
+
+    /// Wrap `lines` in this pattern: the top border, each line padded between the row's
+    /// edges, then the bottom border. Lines wider than the inner width are left unframed.
+    pub fn frame_lines(&self, lines: &[&str]) -> Vec<String> {
+        let mut framed = Vec::with_capacity(lines.len() + 2);
+        framed.push(self.top.clone());
+        for line in lines {
+            framed.push(self.frame_row(line).unwrap_or_else(|| {
+                log::warn!(
+                    "Line is wider than the frame ({} > {}), printing unframed",
+                    line.chars().count(),
+                    self.inner_width()
+                );
+                line.to_string()
+            }));
+        }
+        framed.push(self.bottom.clone());
+        framed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod get_box_patterns {
+        use super::*;
+
+        #[test]
+        fn parses_the_file_once() {
+            let first = get_box_patterns();
+            let second = get_box_patterns();
+            assert!(!first.is_empty());
+            assert_eq!(first.len(), second.len());
+            assert!(std::ptr::eq(first, second));
+        }
+
+        #[test]
+        fn every_pattern_is_cp437() {
+            for pattern in get_box_patterns() {
+                for line in [&pattern.top, &pattern.row, &pattern.bottom] {
+                    let mut builder = rongta::RongtaPrinter::default();
+                    builder.add_content(line).unwrap();
+                    assert_eq!(builder.validate(), [], "{line}");
+                }
+            }
+        }
+
+        #[test]
+        fn missing_file_names_the_path() {
+            let err = load_box_patterns(Path::new("/nonexistent/box_patterns.txt")).unwrap_err();
+            assert!(err.to_string().contains("/nonexistent/box_patterns.txt"));
+        }
+
+        fn patterns_file(name: &str, content: &str) -> PathBuf {
+            let path = std::env::temp_dir().join(format!(
+                "konan-{name}-{}-box_patterns.txt",
+                std::process::id()
+            ));
+            std::fs::write(&path, content).unwrap();
+            path
+        }
+
+        #[test]
+        fn empty_file_is_its_own_error() {
+            let path = patterns_file("empty", "\n  \n");
+            let err = load_box_patterns(&path).unwrap_err();
+            std::fs::remove_file(&path).unwrap();
+            assert!(err.to_string().ends_with("is empty"));
+        }
+
+        #[test]
+        fn file_without_a_whole_pattern_is_its_own_error() {
+            let path = patterns_file("malformed", ".--.\n|  |\n");
+            let err = load_box_patterns(&path).unwrap_err();
+            std::fs::remove_file(&path).unwrap();
+            assert!(err.to_string().contains("has no valid patterns"));
+        }
+
+        #[test]
+        fn fallback_fits_the_default_width() {
+            let fallback = BoxPattern::fallback();
+            assert_eq!(fallback.top.chars().count(), 48);
+            assert_eq!(fallback.fit_to(48).row, fallback.row);
+        }
+    }
+
+    mod fit_to {
+        use super::*;
+
+        #[test]
+        fn shrinks_lines_and_keeps_closing_edge() {
+            let pattern = BoxPattern {
+                top: format!(".{}.", "-".repeat(46)),
+                row: format!("|{}|", " ".repeat(46)),
+                bottom: format!("'{}'", "-".repeat(46)),
+            }
+            .fit_to(32);
+            assert_eq!(pattern.top, format!(".{}.", "-".repeat(30)));
+            assert_eq!(pattern.row, format!("|{}|", " ".repeat(30)));
+            assert_eq!(pattern.bottom.chars().count(), 32);
+        }
+
+        #[test]
+        fn keeps_the_whole_right_edge() {
+            let pattern = BoxPattern {
+                top: "<".repeat(48),
+                row: format!("<<{}>>", " ".repeat(44)),
+                bottom: "<".repeat(48),
+            }
+            .fit_to(32);
+            assert_eq!(pattern.row, format!("<<{}>>", " ".repeat(28)));
+            assert_eq!(pattern.inner_width(), 28);
+            assert_eq!(pattern.top.chars().count(), 32);
+        }
+    }
+
+    mod frame_lines {
+        use super::*;
+
+        fn pattern() -> BoxPattern {
+            BoxPattern {
+                top: "<<<<<<<<<<".to_string(),
+                row: "<<      >>".to_string(),
+                bottom: "<<<<<<<<<<".to_string(),
+            }
+        }
+
+        #[test]
+        fn wraps_lines_between_borders_and_edges() {
+            let framed = pattern().frame_lines(&["(^.^)", "(o.o)"]);
+            assert_eq!(framed.first(), Some(&pattern().top));
+            assert_eq!(framed.last(), Some(&pattern().bottom));
+            assert_eq!(framed[1], "<<(^.^) >>");
+            assert_eq!(framed[2], "<<(o.o) >>");
+            assert!(framed.iter().all(|line| line.chars().count() == 10));
+        }
+
+        #[test]
+        fn leaves_overwide_lines_unframed() {
+            let framed = pattern().frame_lines(&["too wide!"]);
+            assert_eq!(framed[1], "too wide!");
+        }
+    }
+
+    mod row_edges {
+        use super::*;
+
+        fn row(row: &str) -> BoxPattern {
+            BoxPattern {
+                top: String::new(),
+                row: row.to_string(),
+                bottom: String::new(),
+            }
+        }
+
+        #[test]
+        fn edges_are_the_runs_around_interior_spaces() {
+            assert_eq!(row("|    |").row_edges(), ("|", 4, "|"));
+            assert_eq!(row("<<    >>").row_edges(), ("<<", 4, ">>"));
+            assert_eq!(
+                row("\u{2551}  \u{2551}").row_edges(),
+                ("\u{2551}", 2, "\u{2551}")
+            );
+        }
+
+        #[test]
+        fn framing_nothing_gives_back_each_shipped_row() {
+            for pattern in get_box_patterns() {
+                assert_eq!(pattern.frame_row(""), Some(pattern.row.clone()));
+            }
+        }
+
+        #[test]
+        fn row_without_spaces_keeps_its_inner_width() {
+            assert_eq!(row("####").row_edges(), ("#", 2, "#"));
+            assert_eq!(row("####").frame_row("ab"), Some("#ab#".to_string()));
+        }
+
+        #[test]
+        fn short_rows_have_no_inner_width() {
+            assert_eq!(row("||").row_edges(), ("|", 0, "|"));
+            assert_eq!(row("|").row_edges(), ("|", 0, ""));
+            assert_eq!(row("").row_edges(), ("", 0, ""));
+        }
+    }
+
+    mod parse_box_patterns {
+        use super::*;
+
+        #[test]
+        fn splits_patterns_on_blank_lines() {
+            let patterns = parse_box_patterns(".--.\n|  |\n'--'\n\n####\n#  #\n####\n");
+            assert_eq!(patterns.len(), 2);
+            assert_eq!(patterns[1].row, "#  #");
+        }
+    }
+
+    mod column_row {
+        use super::*;
+
+        #[test]
+        fn distributes_labels_evenly() {
+            let row = column_row(&["Time", "Task", "Done"], 30).unwrap();
+            assert_eq!(row, "Time      Task      Done");
+        }
+
+        #[test]
+        fn rejects_labels_that_fill_their_column() {
+            let err = column_row(&["Time", "Description"], 20).unwrap_err();
+            assert_eq!(
+                err.to_string(),
+                "Column label 'Description' is 11 columns but only 9 fit"
+            );
+        }
+    }
+}
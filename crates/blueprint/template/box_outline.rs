@@ -1,30 +1,135 @@
-use super::BoxPattern;
-use anyhow::Result;
+use super::{BannerTimezone, BoxPattern, column_row};
+use crate::interpreter::UNCHECKED_BOX;
+use anyhow::{Result, ensure};
 use chrono::{DateTime, Utc};
 use rongta::{
-    RongtaPrinter, SupportedDriver,
-    elements::{Justify, TextSize},
+    PrintReport, RongtaPrinter, SupportedDriver,
+    elements::{FormatState, Justify, StyledChar},
+    line::Line,
 };
 
+/// Drawn down the box between ledger columns
+const LEDGER_SEPARATOR: char = '|';
+
 pub struct BoxTemplateBuilder {
     builder: RongtaPrinter,
     date: Option<DateTime<Utc>>,
     banner: Option<String>,
-    rows: u32,
+    /// Body rows between the borders. Pagination is left to the printer.
+    height: u32,
     lined: bool,
+    checkboxes: bool,
     pattern: BoxPattern,
+    /// Draws the top and bottom borders when set, leaving the rows to `pattern`
+    header_pattern: Option<BoxPattern>,
+    timezone: BannerTimezone,
+    /// Header row printed bold under the top border
+    column_header: Option<String>,
+    /// Inner columns where a `LEDGER_SEPARATOR` runs down the header and body rows
+    ledger_separators: Vec<usize>,
+    /// Rendered lines printed inside the box in place of the first body rows
+    body: Vec<Line>,
 }
 
 impl BoxTemplateBuilder {
     pub fn new(builder: RongtaPrinter, pattern: BoxPattern) -> Self {
+        let pattern = pattern.fit_to(builder.cpl() as usize);
         Self {
             builder,
             date: None,
             banner: None,
-            rows: 30,
+            height: 30,
             lined: false,
+            checkboxes: false,
             pattern,
+            header_pattern: None,
+            timezone: BannerTimezone::default(),
+            column_header: None,
+            ledger_separators: Vec::new(),
+            body: Vec::new(),
+        }
+    }
+
+    /// Columns between the box edges, the `cpl` to render a body builder at
+    pub fn inner_width(&self) -> usize {
+        self.pattern.inner_width()
+    }
+
+    /// Fill the box with the lines of an already rendered `body`, e.g. interpreted
+    /// markdown, instead of blank rows. Each line keeps its styles and justification
+    /// within the box. Bodies taller than the height are cut short with a last row
+    /// counting the lines left out.
+    pub fn set_body_builder(&mut self, mut body: RongtaPrinter) -> &mut Self {
+        body.trim_trailing_blank_lines();
+        self.body = body.into_lines();
+        self
+    }
+
+    /// Print `labels` as a bold header row under the top border, spread across the box
+    pub fn set_columns(&mut self, labels: &[&str]) -> Result<&mut Self> {
+        let row = column_row(labels, self.pattern.inner_width())?;
+        self.column_header = self.pattern.frame_row(&row);
+        Ok(self)
+    }
+
+    /// Rule the box into equal columns headed by `labels`, with a separator running down
+    /// every row between them. Replaces the header row of `set_columns`.
+    pub fn set_ledger(&mut self, labels: &[&str]) -> Result<&mut Self> {
+        ensure!(!labels.is_empty(), "At least one ledger column is required");
+        let column_width = self.pattern.inner_width() / labels.len();
+        let mut header = String::new();
+        for label in labels {
+            let label_width = label.chars().count();
+            // A space and the separator follow each label
+            ensure!(
+                label_width + 2 <= column_width,
+                "Ledger column '{label}' is {label_width} columns but only {} fit",
+                column_width.saturating_sub(2)
+            );
+            header.push_str(label);
+            header.push_str(&" ".repeat(column_width - label_width));
+        }
+        self.ledger_separators = (1..labels.len())
+            .map(|column| column * column_width - 1)
+            .collect();
+        self.column_header = self
+            .pattern
+            .frame_row(&header)
+            .map(|row| self.rule_columns(row));
+        Ok(self)
+    }
+
+    /// Overwrite the ledger separator columns of a framed `row`
+    fn rule_columns(&self, row: String) -> String {
+        if self.ledger_separators.is_empty() {
+            return row;
+        }
+        let offset = self.pattern.row_edges().0.chars().count();
+        let mut chars: Vec<char> = row.chars().collect();
+        for separator in &self.ledger_separators {
+            if let Some(ch) = chars.get_mut(offset + separator) {
+                *ch = LEDGER_SEPARATOR;
+            }
         }
+        chars.into_iter().collect()
+    }
+
+    /// Draw the top and bottom borders from `pattern` instead of the body pattern.
+    /// Both patterns must be non-empty and the header must be as wide as the body rows.
+    pub fn set_header_pattern(&mut self, pattern: BoxPattern) -> Result<&mut Self> {
+        let pattern = pattern.fit_to(self.builder.cpl() as usize);
+        self.pattern.validate()?;
+        pattern.validate()?;
+        let row_width = self.pattern.row.chars().count();
+        for (name, line) in [("top", &pattern.top), ("bottom", &pattern.bottom)] {
+            let width = line.chars().count();
+            ensure!(
+                width == row_width,
+                "Header pattern {name} is {width} columns but the body rows are {row_width}"
+            );
+        }
+        self.header_pattern = Some(pattern);
+        Ok(self)
     }
 
     pub fn set_date_banner(&mut self, date: DateTime<Utc>) -> &mut Self {
@@ -32,6 +137,12 @@ impl BoxTemplateBuilder {
         self
     }
 
+    /// Show the date banner in `timezone` instead of the system local zone
+    pub fn set_timezone(&mut self, timezone: BannerTimezone) -> &mut Self {
+        self.timezone = timezone;
+        self
+    }
+
     // Add a centered banner with the date
     fn with_date_banner(&mut self) -> Result<()> {
         self.builder.reset_styles();
@@ -40,7 +151,11 @@ impl BoxTemplateBuilder {
 
         match self.date {
             Some(d) => {
-                let str_date = d.format("%A, %B %d, %Y").to_string();
+                let str_date = self
+                    .timezone
+                    .localize(d)
+                    .format("%A, %B %d, %Y")
+                    .to_string();
                 self.builder.add_content(&str_date)?;
                 self.builder.new_line();
                 Ok(())
@@ -57,10 +172,8 @@ impl BoxTemplateBuilder {
         self.builder.reset_styles();
         match &self.banner {
             Some(b) => {
-                self.builder.set_justify_content(Justify::Center);
                 self.builder.set_is_bold(true);
-                self.builder.set_text_size(TextSize::Large);
-                self.builder.add_content(b)?;
+                self.builder.add_autosized_text(b)?;
                 self.builder.new_line();
                 self.builder.new_line();
                 Ok(())
@@ -74,28 +187,120 @@ impl BoxTemplateBuilder {
         self
     }
 
-    pub fn set_rows(&mut self, rows: u32) -> &mut Self {
-        self.rows = rows;
+    /// Start each body row with an unchecked box, turning the box into a to-do page
+    pub fn set_checkboxes(&mut self, checkboxes: bool) -> &mut Self {
+        self.checkboxes = checkboxes;
+        self
+    }
+
+    pub fn set_height(&mut self, height: u32) -> &mut Self {
+        self.height = height;
         self
     }
 
+    fn with_column_header(&mut self) -> Result<()> {
+        if let Some(header) = &self.column_header {
+            self.builder.reset_styles();
+            self.builder.set_is_bold(true);
+            self.builder.add_content(header)?;
+            self.builder.new_line();
+        }
+        Ok(())
+    }
+
+    /// The `index`th body row, ruled into ledger columns when set
+    fn body_row(&self, index: u32) -> String {
+        self.rule_columns(self.plain_body_row(index))
+    }
+
+    /// Lined boxes fill every other row with dots
+    fn plain_body_row(&self, index: u32) -> String {
+        let dotted = self.lined && index.is_multiple_of(2);
+        if self.checkboxes {
+            let fill = if dotted { "." } else { " " };
+            let fill_width = self
+                .pattern
+                .inner_width()
+                .saturating_sub(UNCHECKED_BOX.chars().count());
+            let inner = format!("{UNCHECKED_BOX}{}", fill.repeat(fill_width));
+            // Rows too narrow for a checkbox stay plain
+            if let Some(row) = self.pattern.frame_row(&inner) {
+                return row;
+            }
+        }
+        match dotted {
+            true => self.pattern.row.replace(" ", "."),
+            false => self.pattern.row.clone(),
+        }
+    }
+
+    /// Frame a body line between the row's edges, placing it by its justification.
+    /// Characters beyond the inner width are dropped.
+    fn framed_body_line(&self, line: &Line) -> Line {
+        let (left, inner_width, right) = self.pattern.row_edges();
+        let edge_state = FormatState {
+            is_bold: true,
+            ..Default::default()
+        };
+        let styled = |ch| StyledChar {
+            ch,
+            state: edge_state,
+        };
+
+        let mut content = Vec::new();
+        let mut width = 0;
+        for sc in line.trimmed_chars() {
+            if width + sc.columns() > inner_width {
+                log::warn!("Body line is wider than the box ({inner_width}), clipping it");
+                break;
+            }
+            width += sc.columns();
+            content.push(sc.clone());
+        }
+        let free = inner_width - width;
+        let lead = match line.justify_content {
+            Justify::Left => 0,
+            Justify::Center => free / 2,
+            Justify::Right => free,
+        };
+
+        let mut chars: Vec<StyledChar> = left.chars().map(styled).collect();
+        chars.extend(std::iter::repeat_n(styled(' '), lead));
+        chars.extend(content);
+        chars.extend(std::iter::repeat_n(styled(' '), free - lead));
+        chars.extend(right.chars().map(styled));
+        Line::new(chars, Justify::Left)
+    }
+
     fn with_rows(&mut self) -> Result<()> {
         self.builder.reset_styles();
         self.builder.set_is_bold(true);
-        for i in 0..self.rows {
-            if self.lined {
-                if i % 2 == 0 {
-                    self.builder
-                        .add_content(&self.pattern.row.clone().replace(" ", "."))?;
-                    self.builder.new_line();
-                } else {
-                    self.builder.add_content(&self.pattern.row.clone())?;
-                    self.builder.new_line();
-                }
-            } else {
-                self.builder.add_content(&self.pattern.row.clone())?;
-                self.builder.new_line();
-            }
+        let height = self.height as usize;
+        let body = std::mem::take(&mut self.body);
+        let overflow = body.len() > height;
+        // The last row counts what didn't fit
+        let shown = if overflow {
+            height.saturating_sub(1)
+        } else {
+            body.len()
+        };
+        for line in &body[..shown] {
+            let framed = self.framed_body_line(line);
+            self.builder.push_line(framed);
+        }
+        if overflow && height > 0 {
+            let more = format!("... {} more lines", body.len() - shown);
+            let row = self
+                .pattern
+                .frame_row(&more)
+                .unwrap_or_else(|| self.pattern.row.clone());
+            self.builder.add_content(&row)?;
+            self.builder.new_line();
+        }
+        for i in body.len().min(height)..height {
+            let row = self.body_row(i as u32);
+            self.builder.add_content(&row)?;
+            self.builder.new_line();
         }
         Ok(())
     }
@@ -103,7 +308,8 @@ impl BoxTemplateBuilder {
     fn with_top(&mut self) -> Result<()> {
         self.builder.reset_styles();
         self.builder.set_is_bold(true);
-        self.builder.add_content(&self.pattern.top)?;
+        let top = &self.header_pattern.as_ref().unwrap_or(&self.pattern).top;
+        self.builder.add_content(top)?;
         self.builder.new_line();
         Ok(())
     }
@@ -111,20 +317,289 @@ impl BoxTemplateBuilder {
     fn with_bottom(&mut self) -> Result<()> {
         self.builder.reset_styles();
         self.builder.set_is_bold(true);
-        self.builder.add_content(&self.pattern.bottom)?;
+        let bottom = &self.header_pattern.as_ref().unwrap_or(&self.pattern).bottom;
+        self.builder.add_content(bottom)?;
         self.builder.new_line();
         Ok(())
     }
 
-    /// AKA build
-    pub fn print(&mut self, driver: SupportedDriver) -> Result<()> {
+    fn build(&mut self) -> Result<()> {
         self.with_text_banner()?;
         self.with_date_banner()?;
         self.with_top()?;
+        self.with_column_header()?;
         self.with_rows()?;
-        self.with_bottom()?;
-        self.builder.print(None, driver)?;
-        log::info!("Printed box template");
-        Ok(())
+        self.with_bottom()
+    }
+
+    /// The builder with the box added, to compose it into a larger document
+    pub fn into_builder(mut self) -> Result<RongtaPrinter> {
+        self.build()?;
+        Ok(self.builder)
+    }
+
+    pub fn print(&mut self, driver: SupportedDriver) -> Result<PrintReport> {
+        self.build()?;
+        let report = self.builder.print_detailed(None, driver)?;
+        log::info!("Printed box template: {report}");
+        Ok(report)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pattern(top: char, row_edge: char, bottom: char) -> BoxPattern {
+        BoxPattern {
+            top: top.to_string().repeat(10),
+            row: format!("{row_edge}{}{row_edge}", " ".repeat(8)),
+            bottom: bottom.to_string().repeat(10),
+        }
+    }
+
+    fn rendered_lines(template: &BoxTemplateBuilder) -> Vec<String> {
+        template
+            .builder
+            .lines()
+            .iter()
+            .map(|line| line.chars.iter().map(|sc| sc.ch).collect())
+            .collect()
+    }
+
+    mod set_header_pattern {
+        use super::*;
+
+        #[test]
+        fn borders_come_from_header_and_rows_from_body() {
+            let mut template =
+                BoxTemplateBuilder::new(RongtaPrinter::default(), pattern('-', '|', '_'));
+            template
+                .set_height(2)
+                .set_header_pattern(pattern('=', '!', '~'))
+                .unwrap();
+            template.build().unwrap();
+            let lines = rendered_lines(&template);
+            assert_eq!(lines[0], "==========");
+            assert_eq!(lines[1], "|        |");
+            assert_eq!(lines[2], "|        |");
+            assert_eq!(lines[3], "~~~~~~~~~~");
+        }
+
+        #[test]
+        fn rejects_header_of_different_width() {
+            let mut template =
+                BoxTemplateBuilder::new(RongtaPrinter::default(), pattern('-', '|', '_'));
+            let mut header = pattern('=', '!', '~');
+            header.top.push('=');
+            let err = template.set_header_pattern(header).err().unwrap();
+            assert_eq!(
+                err.to_string(),
+                "Header pattern top is 11 columns but the body rows are 10"
+            );
+        }
+
+        #[test]
+        fn rejects_empty_lines() {
+            let mut template =
+                BoxTemplateBuilder::new(RongtaPrinter::default(), pattern('-', '|', '_'));
+            let mut header = pattern('=', '!', '~');
+            header.bottom.clear();
+            assert!(template.set_header_pattern(header).is_err());
+        }
+    }
+
+    mod set_columns {
+        use super::*;
+
+        #[test]
+        fn header_row_sits_under_the_top_border() {
+            let body = BoxPattern {
+                top: "-".repeat(32),
+                row: format!("|{}|", " ".repeat(30)),
+                bottom: "_".repeat(32),
+            };
+            let mut template = BoxTemplateBuilder::new(RongtaPrinter::default(), body);
+            template
+                .set_height(1)
+                .set_columns(&["Time", "Task", "Done"])
+                .unwrap();
+            template.build().unwrap();
+            let lines = rendered_lines(&template);
+            assert_eq!(lines[1], "|Time      Task      Done      |");
+            assert!(lines[1].chars().count() <= 48);
+            assert!(
+                template.builder.lines()[1]
+                    .chars
+                    .iter()
+                    .all(|sc| sc.state.is_bold)
+            );
+            assert_eq!(lines[2], format!("|{}|", " ".repeat(30)));
+        }
+
+        #[test]
+        fn rejects_labels_that_do_not_fit() {
+            let mut template =
+                BoxTemplateBuilder::new(RongtaPrinter::default(), pattern('-', '|', '_'));
+            assert!(template.set_columns(&["Morning", "Evening"]).is_err());
+        }
+    }
+
+    mod set_height {
+        use super::*;
+        use rongta::printer::{AnyPrinter, PrinterCommand, RecordingPrinter};
+
+        #[test]
+        fn height_is_independent_of_pagination() {
+            let mut template =
+                BoxTemplateBuilder::new(RongtaPrinter::new(true), pattern('-', '|', '_'));
+            template.set_height(40);
+            template.build().unwrap();
+            let lines = rendered_lines(&template);
+            // Top and bottom borders around the body
+            assert_eq!(lines.iter().filter(|line| !line.is_empty()).count(), 42);
+
+            let recorder = RecordingPrinter::new();
+            let report = template
+                .builder
+                .print_to_detailed(&mut AnyPrinter::from(recorder.clone()), Some(20))
+                .unwrap();
+            assert_eq!(report.pages, 3);
+            let cuts = recorder
+                .commands()
+                .iter()
+                .filter(|command| **command == PrinterCommand::Cut)
+                .count();
+            assert_eq!(cuts, 3);
+        }
+    }
+
+    mod set_checkboxes {
+        use super::*;
+
+        #[test]
+        fn body_rows_start_with_a_checkbox() {
+            let mut template =
+                BoxTemplateBuilder::new(RongtaPrinter::default(), pattern('-', '|', '_'));
+            template.set_height(3).set_lined(true).set_checkboxes(true);
+            template.build().unwrap();
+            let lines = rendered_lines(&template);
+            for row in &lines[1..4] {
+                assert!(row.starts_with(&format!("|{UNCHECKED_BOX}")), "{row}");
+                assert_eq!(row.chars().count(), 10);
+            }
+            assert_eq!(lines[1], "|[ ] ....|");
+            assert_eq!(lines[2], "|[ ]     |");
+        }
+    }
+
+    mod set_body_builder {
+        use super::*;
+
+        fn body(lines: &[&str]) -> RongtaPrinter {
+            let mut body = RongtaPrinter::default();
+            for line in lines {
+                body.add_content(line).unwrap();
+                body.new_line();
+            }
+            body
+        }
+
+        #[test]
+        fn body_lines_sit_between_the_borders() {
+            let mut template =
+                BoxTemplateBuilder::new(RongtaPrinter::default(), pattern('-', '|', '_'));
+            let mut rendered = body(&["# Todo"]);
+            rendered.set_justify_content(Justify::Right);
+            rendered.set_is_underlined(true);
+            rendered.add_content("milk").unwrap();
+            rendered.new_line();
+            template.set_height(3).set_body_builder(rendered);
+            template.build().unwrap();
+            let lines = rendered_lines(&template);
+            assert_eq!(
+                lines,
+                [
+                    "----------",
+                    "|# Todo  |",
+                    "|    milk|",
+                    "|        |",
+                    "__________",
+                    ""
+                ]
+            );
+            let milk = &template.builder.lines()[2].chars[5];
+            assert_eq!(milk.ch, 'm');
+            assert!(milk.state.is_underlined);
+        }
+
+        #[test]
+        fn overflow_is_counted_in_the_last_row() {
+            let wide = BoxPattern {
+                top: "-".repeat(20),
+                row: format!("|{}|", " ".repeat(18)),
+                bottom: "_".repeat(20),
+            };
+            let mut template = BoxTemplateBuilder::new(RongtaPrinter::default(), wide);
+            template
+                .set_height(2)
+                .set_body_builder(body(&["one", "two", "three", "four"]));
+            template.build().unwrap();
+            let lines = rendered_lines(&template);
+            assert_eq!(lines[1], "|one               |");
+            assert_eq!(lines[2], "|... 3 more lines  |");
+            assert_eq!(lines[3], "_".repeat(20));
+        }
+    }
+
+    mod set_ledger {
+        use super::*;
+
+        fn ledger() -> BoxTemplateBuilder {
+            let body = BoxPattern {
+                top: "-".repeat(32),
+                row: format!("|{}|", " ".repeat(30)),
+                bottom: "_".repeat(32),
+            };
+            let mut template = BoxTemplateBuilder::new(RongtaPrinter::default(), body);
+            template
+                .set_height(4)
+                .set_lined(true)
+                .set_ledger(&["Date", "Desc", "Amount"])
+                .unwrap();
+            template.build().unwrap();
+            template
+        }
+
+        fn separator_positions(row: &str) -> Vec<usize> {
+            row.char_indices()
+                .filter(|(_, ch)| *ch == LEDGER_SEPARATOR)
+                .map(|(index, _)| index)
+                .collect()
+        }
+
+        #[test]
+        fn separators_line_up_on_every_row() {
+            let template = ledger();
+            let lines = rendered_lines(&template);
+            assert_eq!(lines[1], "|Date     |Desc     |Amount    |");
+            // Box edges at 0 and 31, separators every 10 columns between them
+            for row in &lines[1..6] {
+                assert_eq!(separator_positions(row), [0, 10, 20, 31], "{row}");
+                assert_eq!(row.chars().count(), 32);
+            }
+            assert_eq!(lines[2], "|.........|.........|..........|");
+        }
+
+        #[test]
+        fn rejects_labels_that_do_not_fit() {
+            let mut template =
+                BoxTemplateBuilder::new(RongtaPrinter::default(), pattern('-', '|', '_'));
+            let err = template.set_ledger(&["Date", "Amount"]).err().unwrap();
+            assert_eq!(
+                err.to_string(),
+                "Ledger column 'Date' is 4 columns but only 2 fit"
+            );
+        }
     }
 }
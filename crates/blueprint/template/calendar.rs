@@ -0,0 +1,417 @@
+use super::{BoxPattern, box_outline::BoxTemplateBuilder};
+use anyhow::{Context, Result, ensure};
+use chrono::{Datelike, NaiveDate, Weekday};
+use rongta::{PrintReport, RongtaPrinter, SupportedDriver, elements::Justify};
+
+/// Columns taken by the 8 vertical borders of a 7 day row
+const BORDERS: usize = 8;
+/// Narrowest cell, enough for a two digit day
+const MIN_CELL_WIDTH: usize = 2;
+
+/// What a calendar covers
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CalendarSpan {
+    /// The 7 days from a start date, in one row
+    Week(NaiveDate),
+    /// A whole month, Monday to Sunday rows
+    Month { year: i32, month: u32 },
+}
+
+/// Rows of a Monday first grid needed to show every day of `month` in `year`
+pub fn week_rows(year: i32, month: u32) -> Result<usize> {
+    let first = NaiveDate::from_ymd_opt(year, month, 1)
+        .with_context(|| format!("{year}-{month:02} is not a valid month"))?;
+    let days = first
+        .checked_add_months(chrono::Months::new(1))
+        .map(|next| next.signed_duration_since(first).num_days() as usize)
+        .context("Month is out of range")?;
+    let offset = first.weekday().num_days_from_monday() as usize;
+    Ok((offset + days).div_ceil(7))
+}
+
+/// Two letter weekday label, "Mo" through "Su"
+fn weekday_label(weekday: Weekday) -> &'static str {
+    match weekday {
+        Weekday::Mon => "Mo",
+        Weekday::Tue => "Tu",
+        Weekday::Wed => "We",
+        Weekday::Thu => "Th",
+        Weekday::Fri => "Fr",
+        Weekday::Sat => "Sa",
+        Weekday::Sun => "Su",
+    }
+}
+
+/// A grid of days, each page of it framed in a `BoxTemplateBuilder` box
+pub struct CalendarTemplateBuilder {
+    builder: RongtaPrinter,
+    pattern: BoxPattern,
+    span: CalendarSpan,
+    /// Writing rows under the day number of each cell
+    cell_height: usize,
+    lined: bool,
+    /// Week rows printed before a cut in the month view, all of them when unset
+    weeks_per_page: Option<usize>,
+}
+
+impl CalendarTemplateBuilder {
+    pub fn new(builder: RongtaPrinter, pattern: BoxPattern, span: CalendarSpan) -> Self {
+        Self {
+            builder,
+            pattern,
+            span,
+            cell_height: 2,
+            lined: false,
+            weeks_per_page: None,
+        }
+    }
+
+    /// Rule the writing rows of each cell
+    pub fn set_lined(&mut self, lined: bool) -> &mut Self {
+        self.lined = lined;
+        self
+    }
+
+    /// Writing rows under the day number of each cell
+    pub fn set_cell_height(&mut self, cell_height: usize) -> &mut Self {
+        self.cell_height = cell_height;
+        self
+    }
+
+    /// Cut after every `weeks` rows of the month view, repeating the weekday header on
+    /// each page so a cut never splits a week
+    pub fn set_weeks_per_page(&mut self, weeks: usize) -> &mut Self {
+        self.weeks_per_page = Some(weeks.max(1));
+        self
+    }
+
+    /// An empty box of the builder's width, to frame a page of the grid in
+    fn outline(&self) -> BoxTemplateBuilder {
+        let page = RongtaPrinter::default().with_cpl(self.builder.cpl());
+        BoxTemplateBuilder::new(page, self.pattern.clone())
+    }
+
+    /// Width of a day cell so the 7 columns and their borders fit `inner_width`, the
+    /// columns inside the box
+    fn cell_width(inner_width: usize) -> Result<usize> {
+        let width = inner_width.saturating_sub(BORDERS) / 7;
+        ensure!(
+            width >= MIN_CELL_WIDTH,
+            "A calendar needs at least {} columns inside its box, not {inner_width}",
+            BORDERS + 7 * MIN_CELL_WIDTH
+        );
+        Ok(width)
+    }
+
+    /// The dates of each week row, `None` for days of a month view outside the month
+    fn weeks(&self) -> Result<Vec<[Option<NaiveDate>; 7]>> {
+        let (first, rows, month) = match self.span {
+            CalendarSpan::Week(start) => (start, 1, None),
+            CalendarSpan::Month { year, month } => {
+                let rows = week_rows(year, month)?;
+                let first = NaiveDate::from_ymd_opt(year, month, 1)
+                    .context("Month is out of range")?
+                    .week(Weekday::Mon)
+                    .first_day();
+                (first, rows, Some(month))
+            }
+        };
+        let mut days = first.iter_days();
+        Ok((0..rows)
+            .map(|_| {
+                std::array::from_fn(|_| {
+                    days.next()
+                        .filter(|day| month.is_none_or(|month| day.month() == month))
+                })
+            })
+            .collect())
+    }
+
+    /// Weekday labels of the columns, from the first day of the grid
+    fn column_weekdays(&self) -> [Weekday; 7] {
+        let first = match self.span {
+            CalendarSpan::Week(start) => start.weekday(),
+            CalendarSpan::Month { .. } => Weekday::Mon,
+        };
+        std::array::from_fn(|column| (0..column).fold(first, |weekday, _| weekday.succ()))
+    }
+
+    fn title(&self) -> Option<String> {
+        match self.span {
+            CalendarSpan::Week(start) => Some(format!("Week of {}", start.format("%b %d, %Y"))),
+            CalendarSpan::Month { year, month } => NaiveDate::from_ymd_opt(year, month, 1)
+                .map(|first| first.format("%B %Y").to_string()),
+        }
+    }
+
+    /// A border line, e.g. `┌──┬──┐`
+    fn border(width: usize, left: char, middle: char, right: char) -> String {
+        let cell = "─".repeat(width);
+        format!("{left}{}{right}", vec![cell; 7].join(&middle.to_string()))
+    }
+
+    /// A row of 7 cells between vertical borders, each padded or cut to `width`. Cells
+    /// wider than a day number keep a space off the left border.
+    fn cell_row(width: usize, cells: impl Iterator<Item = String>) -> String {
+        let margin = match width > MIN_CELL_WIDTH {
+            true => " ",
+            false => "",
+        };
+        let cells: Vec<String> = cells
+            .map(|cell| {
+                format!("{margin}{cell:<width$}")
+                    .chars()
+                    .take(width)
+                    .collect()
+            })
+            .collect();
+        format!("│{}│", cells.join("│"))
+    }
+
+    fn with_title(&mut self) -> Result<()> {
+        if let Some(title) = self.title() {
+            self.builder.reset_styles();
+            self.builder.set_justify_content(Justify::Center);
+            self.builder.set_is_bold(true);
+            self.builder.add_content(&title)?;
+            self.builder.new_line();
+        }
+        Ok(())
+    }
+
+    /// Add a line of the grid, centered in the box
+    fn grid_line(grid: &mut RongtaPrinter, line: &str) -> Result<()> {
+        grid.set_justify_content(Justify::Center);
+        grid.add_content(line)?;
+        grid.new_line();
+        Ok(())
+    }
+
+    /// The top border and weekday header of a page
+    fn with_header(&self, grid: &mut RongtaPrinter, width: usize) -> Result<()> {
+        grid.reset_styles();
+        grid.set_is_bold(true);
+        let labels = self
+            .column_weekdays()
+            .map(|weekday| weekday_label(weekday).to_string());
+        Self::grid_line(grid, &Self::border(width, '┌', '┬', '┐'))?;
+        Self::grid_line(grid, &Self::cell_row(width, labels.into_iter()))
+    }
+
+    fn with_week(
+        &self,
+        grid: &mut RongtaPrinter,
+        width: usize,
+        week: &[Option<NaiveDate>; 7],
+    ) -> Result<()> {
+        let numbers = week.iter().map(|day| match day {
+            Some(day) => format!("{:>2}", day.day()),
+            None => String::new(),
+        });
+        Self::grid_line(grid, &Self::border(width, '├', '┼', '┤'))?;
+        Self::grid_line(grid, &Self::cell_row(width, numbers))?;
+        let fill = match self.lined {
+            true => "_",
+            false => " ",
+        };
+        for _ in 0..self.cell_height {
+            let row = format!("│{}│", vec![fill.repeat(width); 7].join("│"));
+            Self::grid_line(grid, &row)?;
+        }
+        Ok(())
+    }
+
+    fn with_bottom(grid: &mut RongtaPrinter, width: usize) -> Result<()> {
+        Self::grid_line(grid, &Self::border(width, '└', '┴', '┘'))
+    }
+
+    /// Frame the grid of `weeks` in a box and add it to the builder
+    fn with_page(&mut self, width: usize, weeks: &[[Option<NaiveDate>; 7]]) -> Result<()> {
+        let mut outline = self.outline();
+        let mut grid = RongtaPrinter::default().with_cpl(outline.inner_width() as u8);
+        self.with_header(&mut grid, width)?;
+        for week in weeks {
+            self.with_week(&mut grid, width, week)?;
+        }
+        Self::with_bottom(&mut grid, width)?;
+        grid.trim_trailing_blank_lines();
+        outline
+            .set_height(grid.lines().len() as u32)
+            .set_body_builder(grid);
+        self.builder.append(outline.into_builder()?);
+        Ok(())
+    }
+
+    fn build(&mut self) -> Result<()> {
+        let width = Self::cell_width(self.outline().inner_width())?;
+        let weeks = self.weeks()?;
+        let per_page = self.weeks_per_page.unwrap_or(weeks.len()).max(1);
+        self.with_title()?;
+        for (page, page_weeks) in weeks.chunks(per_page).enumerate() {
+            if page > 0 {
+                self.builder.page_break();
+            }
+            self.with_page(width, page_weeks)?;
+        }
+        Ok(())
+    }
+
+    pub fn print(&mut self, driver: SupportedDriver) -> Result<PrintReport> {
+        self.build()?;
+        let report = self.builder.print_detailed(None, driver)?;
+        log::info!("Printed calendar template: {report}");
+        Ok(report)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rendered_lines(template: &CalendarTemplateBuilder) -> Vec<String> {
+        template
+            .builder
+            .lines()
+            .iter()
+            .map(|line| line.chars.iter().map(|sc| sc.ch).collect())
+            .collect()
+    }
+
+    mod week_rows {
+        use super::*;
+
+        #[test]
+        fn counts_monday_first_rows() {
+            // February 2021 starts on a Monday and fills exactly 4 rows
+            assert_eq!(week_rows(2021, 2).unwrap(), 4);
+            // February 2026 starts on a Sunday
+            assert_eq!(week_rows(2026, 2).unwrap(), 5);
+            // October 2026 starts on a Thursday
+            assert_eq!(week_rows(2026, 10).unwrap(), 5);
+            // August 2026 starts on a Saturday and ends on a Monday
+            assert_eq!(week_rows(2026, 8).unwrap(), 6);
+        }
+
+        #[test]
+        fn rejects_invalid_months() {
+            assert!(week_rows(2026, 13).is_err());
+        }
+    }
+
+    mod build {
+        use super::*;
+
+        fn pattern() -> BoxPattern {
+            BoxPattern {
+                top: "-".repeat(48),
+                row: format!("|{}|", " ".repeat(46)),
+                bottom: "_".repeat(48),
+            }
+        }
+
+        fn month(cpl: u8) -> CalendarTemplateBuilder {
+            CalendarTemplateBuilder::new(
+                RongtaPrinter::default().with_cpl(cpl),
+                pattern(),
+                CalendarSpan::Month {
+                    year: 2026,
+                    month: 10,
+                },
+            )
+        }
+
+        #[test]
+        fn month_grid_fits_the_cpl() {
+            for cpl in [32, 48] {
+                let mut template = month(cpl);
+                template.build().unwrap();
+                let lines = rendered_lines(&template);
+                assert_eq!(lines[0], "October 2026");
+                assert!(
+                    lines[1..]
+                        .iter()
+                        .all(|line| line.chars().count() <= cpl as usize)
+                );
+            }
+        }
+
+        #[test]
+        fn grid_is_framed_in_the_box() {
+            let mut template = month(48);
+            template.set_cell_height(0);
+            template.build().unwrap();
+            let lines = rendered_lines(&template);
+            assert_eq!(lines[1], "-".repeat(48));
+            assert!(lines[2].starts_with("| ┌─────┬") && lines[2].ends_with("┐  |"));
+            // The builder ends on an empty line for content added after
+            assert_eq!(lines[lines.len() - 2], "_".repeat(48));
+        }
+
+        #[test]
+        fn days_outside_the_month_are_blank() {
+            let mut template = month(48);
+            template.set_cell_height(0);
+            template.build().unwrap();
+            let lines = rendered_lines(&template);
+            // Title, box top, grid top border, header, then the first week's border and numbers
+            assert!(lines[3].contains("│ Mo  │ Tu  │ We  │ Th  │ Fr  │ Sa  │ Su  │"));
+            assert!(lines[5].contains("│     │     │     │  1  │  2  │  3  │  4  │"));
+        }
+
+        #[test]
+        fn week_view_starts_on_its_date() {
+            let start = NaiveDate::from_ymd_opt(2026, 10, 15).unwrap();
+            let mut template = CalendarTemplateBuilder::new(
+                RongtaPrinter::default(),
+                pattern(),
+                CalendarSpan::Week(start),
+            );
+            template.set_lined(true).set_cell_height(1);
+            template.build().unwrap();
+            let lines = rendered_lines(&template);
+            assert_eq!(lines[0], "Week of Oct 15, 2026");
+            assert!(lines[3].starts_with("| │ Th  │ Fr  │"));
+            assert!(lines[5].starts_with("| │ 15  │ 16  │"));
+            assert!(lines[6].contains(&format!("│{}│", ["_____"; 7].join("│"))));
+        }
+
+        #[test]
+        fn month_pages_break_between_weeks() {
+            let mut template = month(48);
+            template.set_weeks_per_page(2);
+            template.build().unwrap();
+            let breaks = template
+                .builder
+                .lines()
+                .iter()
+                .filter(|line| line.page_break)
+                .count();
+            // 5 weeks in October 2026 take 3 pages, each in its own box
+            assert_eq!(breaks, 2);
+            let boxes = rendered_lines(&template)
+                .iter()
+                .filter(|line| **line == "-".repeat(48))
+                .count();
+            assert_eq!(boxes, 3);
+        }
+
+        #[test]
+        fn narrowest_cells_drop_the_margin() {
+            let mut template = month(24);
+            template.set_cell_height(0);
+            template.build().unwrap();
+            let lines = rendered_lines(&template);
+            assert_eq!(lines[3], "|│Mo│Tu│We│Th│Fr│Sa│Su│|");
+            assert_eq!(lines[lines.len() - 4], "|│26│27│28│29│30│31│  │|");
+        }
+
+        #[test]
+        fn rejects_too_narrow_paper() {
+            let err = month(23).build().unwrap_err();
+            assert_eq!(
+                err.to_string(),
+                "A calendar needs at least 22 columns inside its box, not 21"
+            );
+        }
+    }
+}
@@ -0,0 +1,286 @@
+use super::spread_row;
+use crate::interpreter::UNCHECKED_BOX;
+use anyhow::{Result, ensure};
+use rongta::{
+    PrintReport, RongtaPrinter, SupportedDriver,
+    elements::{Justify, TextSize},
+};
+use std::{convert::Infallible, str::FromStr};
+
+/// A checklist entry, parsed from `name` or `name:quantity`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChecklistItem {
+    pub name: String,
+    pub quantity: Option<String>,
+}
+
+impl FromStr for ChecklistItem {
+    type Err = Infallible;
+
+    /// Split on the last `:` so names like "Note: call back" keep their colon when no
+    /// quantity follows. A blank quantity is the same as none.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (name, quantity) = match s.rsplit_once(':') {
+            Some((name, quantity)) if !name.trim().is_empty() => (name, Some(quantity.trim())),
+            _ => (s, None),
+        };
+        Ok(Self {
+            name: name.trim().to_string(),
+            quantity: quantity
+                .filter(|quantity| !quantity.is_empty())
+                .map(str::to_string),
+        })
+    }
+}
+
+pub struct ChecklistTemplateBuilder {
+    builder: RongtaPrinter,
+    items: Vec<ChecklistItem>,
+    /// Boxed above the items, with a rule below them
+    title: Option<String>,
+    /// Mark rows `1.`, `2.`, ... instead of with a checkbox
+    numbered: bool,
+    /// Empty rows after the items to fill in by hand
+    blank_lines: usize,
+}
+
+impl ChecklistTemplateBuilder {
+    pub fn new(builder: RongtaPrinter, items: Vec<ChecklistItem>) -> Self {
+        Self {
+            builder,
+            items,
+            title: None,
+            numbered: false,
+            blank_lines: 0,
+        }
+    }
+
+    pub fn set_title(&mut self, title: Option<String>) -> &mut Self {
+        self.title = title;
+        self
+    }
+
+    pub fn set_numbered(&mut self, numbered: bool) -> &mut Self {
+        self.numbered = numbered;
+        self
+    }
+
+    pub fn set_blank_lines(&mut self, blank_lines: usize) -> &mut Self {
+        self.blank_lines = blank_lines;
+        self
+    }
+
+    /// The marker starting each row, a checkbox or the row's number right-aligned to the
+    /// widest number so the names line up
+    fn markers(&self) -> Vec<String> {
+        let rows = self.items.len() + self.blank_lines;
+        let number_width = rows.to_string().len();
+        (1..=rows)
+            .map(|row| match self.numbered {
+                true => format!("{row:>number_width$}. "),
+                false => UNCHECKED_BOX.to_string(),
+            })
+            .collect()
+    }
+
+    /// `marker name` on the left with `xN` right-aligned when the item has a quantity
+    fn item_row(marker: &str, item: &ChecklistItem, width: usize) -> String {
+        let check = format!("{marker}{}", item.name);
+        match &item.quantity {
+            Some(quantity) => spread_row(&check, &format!("x{quantity}"), width),
+            None => check,
+        }
+    }
+
+    /// The title, bold and centered in a box as wide as the paper
+    fn with_header(&mut self) -> Result<()> {
+        let Some(title) = &self.title else {
+            return Ok(());
+        };
+        let width = self.builder.cpl() as usize;
+        let inner = width.saturating_sub(2);
+        let title_width = title.chars().count();
+        ensure!(
+            title_width <= inner,
+            "Checklist title is {title_width} characters but only {inner} fit"
+        );
+        let rule = "─".repeat(inner);
+        self.builder.reset_styles();
+        self.builder.set_justify_content(Justify::Left);
+        self.builder.set_is_bold(true);
+        self.builder.add_content(&format!("┌{rule}┐"))?;
+        self.builder.new_line();
+        self.builder.add_content(&format!("│{title:^inner$}│"))?;
+        self.builder.new_line();
+        self.builder.add_content(&format!("└{rule}┘"))?;
+        self.builder.new_line();
+        self.builder.set_is_bold(false);
+        Ok(())
+    }
+
+    fn with_items(&mut self) -> Result<()> {
+        self.builder.set_justify_content(Justify::Left);
+        self.builder.set_text_size(TextSize::Medium);
+        let width = self.builder.cpl() as usize;
+        let markers = self.markers();
+        let (item_markers, blank_markers) = markers.split_at(self.items.len());
+        for (marker, item) in item_markers.iter().zip(&self.items) {
+            self.builder
+                .add_content(&Self::item_row(marker, item, width))?;
+            self.builder.new_line();
+        }
+        for marker in blank_markers {
+            self.builder.add_content(marker.trim_end())?;
+            self.builder.new_line();
+        }
+        Ok(())
+    }
+
+    /// A rule closing the list under a titled checklist
+    fn with_footer(&mut self) -> Result<()> {
+        if self.title.is_some() {
+            self.builder.set_is_bold(true);
+            self.builder
+                .add_content(&"─".repeat(self.builder.cpl() as usize))?;
+            self.builder.new_line();
+        }
+        Ok(())
+    }
+
+    fn build(&mut self) -> Result<()> {
+        ensure!(
+            !self.items.is_empty() || self.blank_lines > 0,
+            "A checklist needs at least one item or blank line"
+        );
+        self.with_header()?;
+        self.with_items()?;
+        self.with_footer()
+    }
+
+    pub fn print(&mut self, driver: SupportedDriver) -> Result<PrintReport> {
+        self.build()?;
+        let report = self.builder.print_detailed(None, driver)?;
+        log::info!("Printed checklist template: {report}");
+        Ok(report)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(s: &str) -> ChecklistItem {
+        s.parse().unwrap()
+    }
+
+    mod from_str {
+        use super::*;
+
+        #[test]
+        fn parses_name_and_quantity() {
+            assert_eq!(
+                item("Eggs:12"),
+                ChecklistItem {
+                    name: "Eggs".to_string(),
+                    quantity: Some("12".to_string()),
+                }
+            );
+        }
+
+        #[test]
+        fn quantity_is_optional() {
+            assert_eq!(item("Milk").quantity, None);
+            assert_eq!(item("Milk:").quantity, None);
+            assert_eq!(item(":2").name, ":2");
+        }
+    }
+
+    mod item_row {
+        use super::*;
+
+        #[test]
+        fn right_aligns_quantity() {
+            let row = ChecklistTemplateBuilder::item_row(UNCHECKED_BOX, &item("Milk:2"), 20);
+            assert_eq!(row, "[ ] Milk          x2");
+            assert_eq!(row.chars().count(), 20);
+        }
+
+        #[test]
+        fn name_only_without_quantity() {
+            assert_eq!(
+                ChecklistTemplateBuilder::item_row(UNCHECKED_BOX, &item("Bread"), 20),
+                "[ ] Bread"
+            );
+        }
+    }
+
+    mod build {
+        use super::*;
+
+        fn template(items: &[&str]) -> ChecklistTemplateBuilder {
+            ChecklistTemplateBuilder::new(
+                RongtaPrinter::default().with_cpl(20),
+                items.iter().map(|s| item(s)).collect(),
+            )
+        }
+
+        fn rendered_lines(template: &ChecklistTemplateBuilder) -> Vec<String> {
+            template
+                .builder
+                .lines()
+                .iter()
+                .map(|line| line.chars.iter().map(|sc| sc.ch).collect::<String>())
+                .filter(|text| !text.is_empty())
+                .collect()
+        }
+
+        #[test]
+        fn checkbox_rows_with_blank_padding() {
+            let mut template = template(&["Milk", "Eggs:12"]);
+            template.set_blank_lines(2);
+            template.build().unwrap();
+            assert_eq!(
+                rendered_lines(&template),
+                ["[ ] Milk", "[ ] Eggs         x12", "[ ]", "[ ]"]
+            );
+        }
+
+        #[test]
+        fn numbered_rows_continue_into_blank_lines() {
+            let mut template = template(&["Milk", "Eggs"]);
+            template.set_numbered(true).set_blank_lines(8);
+            template.build().unwrap();
+            let lines = rendered_lines(&template);
+            assert_eq!(lines.len(), 10);
+            assert_eq!(lines[0], " 1. Milk");
+            assert_eq!(lines[1], " 2. Eggs");
+            assert_eq!(lines[2], " 3.");
+            assert_eq!(lines[9], "10.");
+        }
+
+        #[test]
+        fn title_is_boxed_above_a_closing_rule() {
+            let mut template = template(&["Milk"]);
+            template.set_title(Some("Shop".to_string()));
+            template.build().unwrap();
+            let lines = rendered_lines(&template);
+            assert_eq!(
+                lines,
+                [
+                    format!("┌{}┐", "─".repeat(18)),
+                    "│       Shop       │".to_string(),
+                    format!("└{}┘", "─".repeat(18)),
+                    "[ ] Milk".to_string(),
+                    "─".repeat(20),
+                ]
+            );
+            assert!(template.builder.lines()[1].chars[1].state.is_bold);
+            assert!(!template.builder.lines()[3].chars[0].state.is_bold);
+        }
+
+        #[test]
+        fn empty_checklist_errors() {
+            assert!(template(&[]).build().is_err());
+        }
+    }
+}
@@ -1,17 +1,76 @@
-use super::BoxPattern;
-use anyhow::Result;
-use chrono::{DateTime, Datelike, Days, Duration, Utc};
+use super::{BannerTimezone, BoxPattern};
+use anyhow::{Result, bail};
+use chrono::{DateTime, Datelike, NaiveDate, TimeZone, Utc, Weekday};
 use rongta::{
-    RongtaPrinter, SupportedDriver,
+    PrintReport, RongtaPrinter, SupportedDriver,
     elements::{Justify, TextSize},
 };
+use std::fmt::Display;
+
+/// Date formats for the time period banner, from most to least detailed
+const TIME_PERIOD_FORMATS: [&str; 3] = ["%B %d, %Y", "%b %d, %Y", "%b %d"];
+
+/// Format the "start - end" banner with the most detailed format that fits within `cpl`.
+fn time_period_label<Tz>(start: DateTime<Tz>, end: DateTime<Tz>, cpl: usize) -> String
+where
+    Tz: TimeZone,
+    Tz::Offset: Display,
+{
+    let label = |fmt: &str| format!("{} - {}", start.format(fmt), end.format(fmt));
+    TIME_PERIOD_FORMATS
+        .iter()
+        .map(|fmt| label(fmt))
+        .find(|l| l.chars().count() <= cpl)
+        .unwrap_or_else(|| label(TIME_PERIOD_FORMATS[TIME_PERIOD_FORMATS.len() - 1]))
+}
+
+/// Width of a day cell, `( dd )` or `[ dd ]`
+const CELL_WIDTH: usize = 6;
+/// Fewest spaces between two day cells on a line
+const MIN_CELL_GAP: usize = 2;
+
+/// How many day cells fit on a line of `cpl` characters and the gap between them. The
+/// leftover width is spread over the gaps so the cells fill the line. At least one cell
+/// is placed even when it overruns a very narrow line.
+fn cell_layout(cpl: usize) -> (usize, usize) {
+    let per_line = ((cpl + MIN_CELL_GAP) / (CELL_WIDTH + MIN_CELL_GAP)).max(1);
+    let gap = match per_line {
+        1 => 0,
+        n => (cpl - n * CELL_WIDTH) / (n - 1),
+    };
+    (per_line, gap)
+}
+
+/// A day's checkmark cell, `[ dd ]` on weekends when `mark_weekends` is set and `( dd )`
+/// otherwise. Both are the same width so rows stay aligned.
+fn day_cell(date: NaiveDate, mark_weekends: bool) -> String {
+    let weekend = matches!(date.weekday(), Weekday::Sat | Weekday::Sun);
+    match mark_weekends && weekend {
+        true => format!("[ {:02} ]", date.day()),
+        false => format!("( {:02} )", date.day()),
+    }
+}
+
+/// A day's weekday initial, centered in a cell as wide as `day_cell`
+fn weekday_cell(date: NaiveDate) -> String {
+    let initial = match date.weekday() {
+        Weekday::Mon => 'M',
+        Weekday::Tue | Weekday::Thu => 'T',
+        Weekday::Wed => 'W',
+        Weekday::Fri => 'F',
+        Weekday::Sat | Weekday::Sun => 'S',
+    };
+    format!("{initial:^CELL_WIDTH$}")
+}
 
 pub struct HabitTrackerTemplateBuilder {
     builder: RongtaPrinter,
-    habit: String,
+    habits: Vec<String>,
     start_date: DateTime<Utc>,
     end_date: DateTime<Utc>,
     pattern: BoxPattern,
+    timezone: BannerTimezone,
+    mark_weekends: bool,
 }
 
 impl HabitTrackerTemplateBuilder {
@@ -22,23 +81,53 @@ impl HabitTrackerTemplateBuilder {
         start_date: DateTime<Utc>,
         end_date: DateTime<Utc>,
     ) -> Self {
+        Self::new_multi(builder, pattern, vec![habit], start_date, end_date)
+    }
+
+    /// A tracker with a section per habit, each with its own label and day cells, in one
+    /// print job
+    pub fn new_multi(
+        builder: RongtaPrinter,
+        pattern: BoxPattern,
+        habits: Vec<String>,
+        start_date: DateTime<Utc>,
+        end_date: DateTime<Utc>,
+    ) -> Self {
+        let pattern = pattern.fit_to(builder.cpl() as usize);
         Self {
             builder,
-            habit,
+            habits,
             start_date,
             end_date,
             pattern,
+            timezone: BannerTimezone::default(),
+            mark_weekends: false,
         }
     }
 
+    /// Show dates in `timezone` instead of the system local zone
+    pub fn set_timezone(&mut self, timezone: BannerTimezone) -> &mut Self {
+        self.timezone = timezone;
+        self
+    }
+
+    /// Draw Saturday and Sunday cells as `[ dd ]` instead of `( dd )`
+    pub fn set_mark_weekends(&mut self, mark_weekends: bool) -> &mut Self {
+        self.mark_weekends = mark_weekends;
+        self
+    }
+
     fn with_time_period(&mut self) -> Result<()> {
         self.builder.new_line();
         self.builder.set_justify_content(Justify::Center);
         self.builder.set_is_bold(true);
-        let start_str = self.start_date.format("%B %d, %Y").to_string();
-        let end_str = self.end_date.format("%B %d, %Y").to_string();
-        self.builder
-            .add_content(&format!("{} - {}", start_str, end_str))?;
+        let cpl = self.builder.cpl() as usize;
+        let time_period = time_period_label(
+            self.timezone.localize(self.start_date),
+            self.timezone.localize(self.end_date),
+            cpl,
+        );
+        self.builder.add_content(&time_period)?;
         self.builder.new_line();
         Ok(())
     }
@@ -52,48 +141,66 @@ impl HabitTrackerTemplateBuilder {
         Ok(())
     }
 
-    fn with_habit(&mut self) -> Result<()> {
+    fn with_habit(&mut self, habit: &str) -> Result<()> {
         self.builder.set_justify_content(Justify::Center);
         self.builder.set_text_size(TextSize::Large);
-        self.builder.add_content(&self.habit.to_ascii_uppercase())?;
+        self.builder.add_content(&habit.to_ascii_uppercase())?;
         self.builder.new_line();
         Ok(())
     }
 
-    fn with_checkmarks(&mut self) -> Result<()> {
-        self.builder.set_justify_content(Justify::Center);
-        self.builder.set_is_bold(true);
-        self.builder.set_text_size(TextSize::Medium);
-
-        const SEGMENTS_PER_LINE: usize = 4; // Max segments that fit in 48 chars with spacing
-
-        let mut current_date = self.start_date;
-        let mut day_numbers = Vec::new();
+    /// Lines of `cell`s for each day from the start to the end date inclusive, as many per
+    /// line as fit the CPL, see `cell_layout`. A single day span is one cell. Errors when
+    /// the end date is before the start date.
+    fn day_lines(&self, cell: impl Fn(NaiveDate) -> String) -> Result<Vec<String>> {
+        let (per_line, gap) = cell_layout(self.builder.cpl() as usize);
+        let separator = " ".repeat(gap);
 
-        // Collect all day numbers from start to end
-        while current_date
-            < self
-                .end_date
-                .checked_add_days(Days::new(1))
-                .expect("End date overflow")
-        {
-            day_numbers.push(current_date.day());
-            current_date = current_date
-                .checked_add_days(Days::new(1))
-                .unwrap_or(current_date + Duration::days(1));
+        let start = self.timezone.localize(self.start_date).date_naive();
+        let end = self.timezone.localize(self.end_date).date_naive();
+        if end < start {
+            bail!("Habit tracker end date {end} is before its start date {start}");
         }
+        let days: Vec<NaiveDate> = start.iter_days().take_while(|day| *day <= end).collect();
 
         // Process days in chunks and create lines
-        for chunk in day_numbers.chunks(SEGMENTS_PER_LINE) {
-            let line = chunk
-                .iter()
-                .map(|day| format!("( {:02} )", day))
-                .collect::<Vec<_>>()
-                .join("      ");
-            self.builder.add_content(&line)?;
+        Ok(days
+            .chunks(per_line)
+            .map(|chunk| {
+                chunk
+                    .iter()
+                    .map(|day| cell(*day))
+                    .collect::<Vec<_>>()
+                    .join(&separator)
+            })
+            .collect())
+    }
+
+    fn checkmark_lines(&self) -> Result<Vec<String>> {
+        self.day_lines(|day| day_cell(day, self.mark_weekends))
+    }
+
+    /// The weekday initials lined up under `checkmark_lines`
+    fn weekday_lines(&self) -> Result<Vec<String>> {
+        self.day_lines(weekday_cell)
+    }
+
+    /// Each line of day cells above its weekday initials. The rows are indented to center
+    /// them rather than justified, since centering trims the trailing spaces of the
+    /// weekday row and would shift it out from under the cells.
+    fn with_checkmarks(&mut self, lines: Vec<String>, weekdays: Vec<String>) -> Result<()> {
+        let cpl = self.builder.cpl() as usize;
+        self.builder.set_justify_content(Justify::Left);
+        self.builder.set_text_size(TextSize::Medium);
+        for (line, weekday) in lines.iter().zip(&weekdays) {
+            let indent = " ".repeat(cpl.saturating_sub(line.chars().count()) / 2);
+            self.builder.set_is_bold(true);
+            self.builder.add_content(&format!("{indent}{line}"))?;
+            self.builder.new_line();
+            self.builder.set_is_bold(false);
+            self.builder.add_content(&format!("{indent}{weekday}"))?;
             self.builder.new_line();
         }
-
         Ok(())
     }
 
@@ -105,15 +212,270 @@ impl HabitTrackerTemplateBuilder {
         Ok(())
     }
 
-    pub fn print(&mut self, driver: SupportedDriver) -> Result<()> {
+    /// Add the time period banner and a boxed section per habit to the page
+    fn render(&mut self) -> Result<()> {
+        if self.habits.is_empty() {
+            bail!("Habit tracker has no habits to track");
+        }
+        // Check the span before anything is added to the page
+        let checkmarks = self.checkmark_lines()?;
+        let weekdays = self.weekday_lines()?;
         self.with_time_period()?;
-        self.with_top()?;
-        self.with_habit()?;
-        self.with_top()?;
-        self.with_checkmarks()?;
-        self.with_bottom()?;
-        self.builder.print(None, driver)?;
-        log::info!("Printed habit tracker template");
+        for habit in self.habits.clone() {
+            self.with_top()?;
+            self.with_habit(&habit)?;
+            self.with_top()?;
+            self.with_checkmarks(checkmarks.clone(), weekdays.clone())?;
+            self.with_bottom()?;
+        }
         Ok(())
     }
+
+    pub fn print(&mut self, driver: SupportedDriver) -> Result<PrintReport> {
+        self.render()?;
+        let report = self.builder.print_detailed(None, driver)?;
+        log::info!("Printed habit tracker template: {report}");
+        Ok(report)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn pattern() -> BoxPattern {
+        BoxPattern {
+            top: "-".repeat(10),
+            row: format!("|{}|", " ".repeat(8)),
+            bottom: "_".repeat(10),
+        }
+    }
+
+    mod time_period_label {
+        use super::*;
+
+        fn date(y: i32, m: u32, d: u32) -> DateTime<Utc> {
+            Utc.with_ymd_and_hms(y, m, d, 0, 0, 0).unwrap()
+        }
+
+        #[test]
+        fn uses_full_format_when_it_fits() {
+            let label = time_period_label(date(2026, 9, 30), date(2026, 10, 14), 48);
+            assert_eq!(label, "September 30, 2026 - October 14, 2026");
+        }
+
+        #[test]
+        fn falls_back_to_abbreviated_format_when_full_overflows() {
+            let label = time_period_label(date(2026, 9, 30), date(2026, 10, 14), 32);
+            assert_eq!(label, "Sep 30, 2026 - Oct 14, 2026");
+            assert!(label.chars().count() <= 32);
+        }
+
+        #[test]
+        fn drops_the_year_as_a_last_resort() {
+            let label = time_period_label(date(2026, 9, 30), date(2026, 10, 14), 24);
+            assert_eq!(label, "Sep 30 - Oct 14");
+        }
+    }
+
+    mod new_multi {
+        use super::*;
+
+        fn texts(habits: &[&str]) -> Vec<String> {
+            let mut template = HabitTrackerTemplateBuilder::new_multi(
+                RongtaPrinter::default(),
+                pattern(),
+                habits.iter().map(|habit| habit.to_string()).collect(),
+                Utc.with_ymd_and_hms(2026, 10, 15, 12, 0, 0).unwrap(),
+                Utc.with_ymd_and_hms(2026, 10, 16, 12, 0, 0).unwrap(),
+            );
+            template.render().unwrap();
+            template
+                .builder
+                .lines()
+                .iter()
+                .map(|line| line.chars.iter().map(|sc| sc.ch).collect::<String>())
+                .map(|text| text.trim().to_string())
+                .filter(|text| !text.is_empty())
+                .collect()
+        }
+
+        #[test]
+        fn single_habit_is_one_section() {
+            let texts = texts(&["Read"]);
+            assert_eq!(texts.iter().filter(|text| *text == "READ").count(), 1);
+            assert_eq!(texts.iter().filter(|text| text.starts_with('_')).count(), 1);
+        }
+
+        #[test]
+        fn each_habit_gets_a_boxed_section_with_its_own_days() {
+            let texts = texts(&["Read", "Stretch"]);
+            let sections: Vec<&[String]> = texts[1..]
+                .split_inclusive(|text| text.starts_with('_'))
+                .collect();
+            assert_eq!(sections.len(), 2);
+            for (section, habit) in sections.iter().zip(["READ", "STRETCH"]) {
+                assert_eq!(section[1], habit);
+                assert!(section.iter().any(|text| text.contains("( 15 )")));
+            }
+        }
+
+        #[test]
+        fn no_habits_errors() {
+            let mut template = HabitTrackerTemplateBuilder::new_multi(
+                RongtaPrinter::default(),
+                BoxPattern::fallback(),
+                Vec::new(),
+                Utc.with_ymd_and_hms(2026, 10, 15, 12, 0, 0).unwrap(),
+                Utc.with_ymd_and_hms(2026, 10, 16, 12, 0, 0).unwrap(),
+            );
+            assert!(template.render().is_err());
+        }
+    }
+
+    mod set_mark_weekends {
+        use super::*;
+
+        fn template(mark_weekends: bool) -> HabitTrackerTemplateBuilder {
+            // Thursday October 15 to Monday October 19 2026
+            let mut template = HabitTrackerTemplateBuilder::new(
+                RongtaPrinter::default(),
+                pattern(),
+                "Read".to_string(),
+                Utc.with_ymd_and_hms(2026, 10, 15, 12, 0, 0).unwrap(),
+                Utc.with_ymd_and_hms(2026, 10, 19, 12, 0, 0).unwrap(),
+            );
+            template
+                .set_timezone(BannerTimezone::Fixed(
+                    chrono::FixedOffset::east_opt(0).unwrap(),
+                ))
+                .set_mark_weekends(mark_weekends);
+            template
+        }
+
+        #[test]
+        fn weekend_cells_use_square_brackets() {
+            let cells: Vec<String> = template(true)
+                .checkmark_lines()
+                .unwrap()
+                .join(" ")
+                .split_whitespace()
+                .collect::<Vec<_>>()
+                .chunks(3)
+                .map(|cell| cell.join(" "))
+                .collect();
+            assert_eq!(cells, ["( 15 )", "( 16 )", "[ 17 ]", "[ 18 ]", "( 19 )"]);
+        }
+
+        #[test]
+        fn uniform_by_default() {
+            let lines = template(false).checkmark_lines().unwrap();
+            assert!(!lines.concat().contains('['));
+        }
+    }
+
+    mod checkmark_lines {
+        use super::*;
+        use rongta::printer::DumpDriver;
+
+        fn template(start_day: u32, end_day: u32) -> HabitTrackerTemplateBuilder {
+            template_with_cpl(start_day, end_day, rongta::CPL)
+        }
+
+        fn template_with_cpl(start_day: u32, end_day: u32, cpl: u8) -> HabitTrackerTemplateBuilder {
+            let mut template = HabitTrackerTemplateBuilder::new(
+                RongtaPrinter::default().with_cpl(cpl),
+                pattern(),
+                "Read".to_string(),
+                Utc.with_ymd_and_hms(2026, 10, start_day, 12, 0, 0).unwrap(),
+                Utc.with_ymd_and_hms(2026, 10, end_day, 9, 0, 0).unwrap(),
+            );
+            template.set_timezone(BannerTimezone::Fixed(
+                chrono::FixedOffset::east_opt(0).unwrap(),
+            ));
+            template
+        }
+
+        #[test]
+        fn single_day_is_one_segment() {
+            assert_eq!(template(15, 15).checkmark_lines().unwrap(), ["( 15 )"]);
+        }
+
+        #[test]
+        fn fits_four_cells_on_58mm_paper() {
+            assert_eq!(cell_layout(32), (4, 2));
+            let lines = template_with_cpl(1, 14, 32).checkmark_lines().unwrap();
+            assert_eq!(lines[0], "( 01 )  ( 02 )  ( 03 )  ( 04 )");
+            assert_eq!(lines.len(), 4);
+            assert!(lines.iter().all(|line| line.chars().count() <= 32));
+        }
+
+        #[test]
+        fn fits_six_cells_on_80mm_paper() {
+            assert_eq!(cell_layout(48), (6, 2));
+            let lines = template_with_cpl(1, 14, 48).checkmark_lines().unwrap();
+            assert_eq!(lines.len(), 3);
+            assert_eq!(lines[0].matches('(').count(), 6);
+            assert!(lines.iter().all(|line| line.chars().count() <= 48));
+        }
+
+        #[test]
+        fn weekday_initials_sit_under_their_cells() {
+            // Thursday October 15 to Monday October 19 2026
+            let template = template(15, 19);
+            let weekdays = template.weekday_lines().unwrap();
+            let initials: String = weekdays.concat().split_whitespace().collect();
+            assert_eq!(initials, "TFSSM");
+
+            let cells = template.checkmark_lines().unwrap();
+            assert_eq!(cells.len(), weekdays.len());
+            for (cell_line, weekday_line) in cells.iter().zip(&weekdays) {
+                assert_eq!(cell_line.len(), weekday_line.len());
+                let digits: Vec<usize> =
+                    cell_line.match_indices("( ").map(|(i, _)| i + 2).collect();
+                let letters: Vec<usize> = weekday_line
+                    .match_indices(|ch: char| !ch.is_whitespace())
+                    .map(|(i, _)| i)
+                    .collect();
+                assert_eq!(digits, letters);
+            }
+        }
+
+        #[test]
+        fn typed_start_date_starts_on_that_day_west_of_utc() {
+            let timezone: BannerTimezone = "-05:00".parse().unwrap();
+            let start = NaiveDate::from_ymd_opt(2026, 10, 15).unwrap();
+            let end = NaiveDate::from_ymd_opt(2026, 10, 21).unwrap();
+            let mut template = HabitTrackerTemplateBuilder::new(
+                RongtaPrinter::default(),
+                pattern(),
+                "Read".to_string(),
+                timezone.start_of_day(start),
+                timezone.start_of_day(end),
+            );
+            template.set_timezone(timezone);
+            let lines = template.checkmark_lines().unwrap();
+            assert!(lines[0].starts_with("( 15 )"), "{}", lines[0]);
+        }
+
+        #[test]
+        fn narrow_line_still_gets_one_cell() {
+            assert_eq!(cell_layout(4), (1, 0));
+        }
+
+        #[test]
+        fn reversed_span_errors_before_printing() {
+            let mut template = template(19, 15);
+            assert!(template.checkmark_lines().is_err());
+            let driver = DumpDriver::new();
+            assert!(
+                template
+                    .print(SupportedDriver::Dump(driver.clone()))
+                    .is_err()
+            );
+            assert!(template.builder.lines().is_empty());
+            assert!(driver.bytes().is_empty());
+        }
+    }
 }
@@ -0,0 +1,314 @@
+use anyhow::{Context, Result, bail};
+use rongta::{
+    PrintReport, RongtaPrinter, SupportedDriver,
+    elements::{Justify, TextSize},
+};
+use serde_json::Value;
+
+/// Space between two columns
+const COLUMN_GAP: &str = " ";
+
+/// Rows of text cells under a header row
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Table {
+    pub headers: Vec<String>,
+    pub rows: Vec<Vec<String>>,
+}
+
+impl Table {
+    /// Parse CSV whose first record is the header row. Quoted fields may hold commas,
+    /// newlines and `""` escaped quotes. Short rows are padded with empty cells and cells
+    /// beyond the header are dropped.
+    pub fn from_csv(content: &str) -> Result<Self> {
+        let mut records = parse_csv(content)?.into_iter();
+        let Some(headers) = records.next() else {
+            return Ok(Self::default());
+        };
+        let rows = records
+            .enumerate()
+            .map(|(index, mut row)| {
+                if row.len() > headers.len() {
+                    log::warn!(
+                        "CSV row {} has {} cells but only {} headers, dropping the extra cells",
+                        index + 2,
+                        row.len(),
+                        headers.len()
+                    );
+                }
+                row.resize(headers.len(), String::new());
+                row
+            })
+            .collect();
+        Ok(Self { headers, rows })
+    }
+
+    /// Parse a JSON array of flat objects. The headers are every key found in any object,
+    /// in the order the first object lists them followed by keys only later objects have.
+    /// Objects missing a key get an empty cell for it.
+    pub fn from_json(content: &str) -> Result<Self> {
+        if content.trim().is_empty() {
+            return Ok(Self::default());
+        }
+        let objects: Vec<serde_json::Map<String, Value>> =
+            serde_json::from_str(content).context("Expected a JSON array of objects")?;
+        let mut headers: Vec<String> = Vec::new();
+        for key in objects.iter().flat_map(serde_json::Map::keys) {
+            if !headers.contains(key) {
+                headers.push(key.clone());
+            }
+        }
+        let rows = objects
+            .iter()
+            .map(|object| {
+                headers
+                    .iter()
+                    .map(|key| object.get(key).map(cell_text).unwrap_or_default())
+                    .collect()
+            })
+            .collect();
+        Ok(Self { headers, rows })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rows.is_empty()
+    }
+
+    /// Width of each column, shrinking the widest until the row fits in `cpl`. Columns that
+    /// can't get a single character are dropped from the end.
+    fn column_widths(&self, cpl: usize) -> Vec<usize> {
+        let mut widths: Vec<usize> = self
+            .headers
+            .iter()
+            .enumerate()
+            .map(|(column, header)| {
+                self.rows
+                    .iter()
+                    .map(|row| row[column].chars().count())
+                    .chain([header.chars().count()])
+                    .max()
+                    .unwrap_or(0)
+                    .max(1)
+            })
+            .collect();
+        let max_columns = (cpl + COLUMN_GAP.len()) / (COLUMN_GAP.len() + 1);
+        if widths.len() > max_columns {
+            log::warn!(
+                "Only {max_columns} of {} columns fit in {cpl} characters",
+                widths.len()
+            );
+            widths.truncate(max_columns);
+        }
+        let row_width = |widths: &[usize]| {
+            widths.iter().sum::<usize>() + COLUMN_GAP.len() * widths.len().saturating_sub(1)
+        };
+        while row_width(&widths) > cpl {
+            let widest = widths
+                .iter_mut()
+                .max()
+                .expect("A table wider than the page has columns");
+            *widest -= 1;
+        }
+        widths
+    }
+
+    /// The header, a rule beneath it and one line per row, each at most `cpl` wide
+    pub fn lines(&self, cpl: usize) -> Vec<String> {
+        let widths = self.column_widths(cpl);
+        let format_row = |cells: &[String]| {
+            widths
+                .iter()
+                .zip(cells)
+                .map(|(width, cell)| {
+                    let cell: String = cell.chars().take(*width).collect();
+                    format!("{cell:<width$}")
+                })
+                .collect::<Vec<_>>()
+                .join(COLUMN_GAP)
+                .trim_end()
+                .to_string()
+        };
+        let rule = widths
+            .iter()
+            .map(|width| "-".repeat(*width))
+            .collect::<Vec<_>>()
+            .join(COLUMN_GAP);
+        [format_row(&self.headers), rule]
+            .into_iter()
+            .chain(self.rows.iter().map(|row| format_row(row)))
+            .collect()
+    }
+}
+
+/// Strings print without their quotes and nulls as empty cells
+fn cell_text(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        Value::Null => String::new(),
+        other => other.to_string(),
+    }
+}
+
+/// Split CSV into records of fields, skipping blank lines
+fn parse_csv(content: &str) -> Result<Vec<Vec<String>>> {
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = content.chars().peekable();
+    while let Some(ch) = chars.next() {
+        match (ch, in_quotes) {
+            ('"', true) if chars.peek() == Some(&'"') => {
+                chars.next();
+                field.push('"');
+            }
+            ('"', true) => in_quotes = false,
+            ('"', false) if field.is_empty() => in_quotes = true,
+            (',', false) => record.push(std::mem::take(&mut field)),
+            ('\r', false) if chars.peek() == Some(&'\n') => {}
+            ('\n', false) => {
+                record.push(std::mem::take(&mut field));
+                if record.iter().any(|field| !field.is_empty()) {
+                    records.push(std::mem::take(&mut record));
+                }
+                record.clear();
+            }
+            (ch, _) => field.push(ch),
+        }
+    }
+    if in_quotes {
+        bail!("CSV ends inside a quoted field");
+    }
+    record.push(field);
+    if record.iter().any(|field| !field.is_empty()) {
+        records.push(record);
+    }
+    Ok(records)
+}
+
+pub struct TableTemplateBuilder {
+    builder: RongtaPrinter,
+    table: Table,
+}
+
+impl TableTemplateBuilder {
+    pub fn new(builder: RongtaPrinter, table: Table) -> Self {
+        Self { builder, table }
+    }
+
+    fn with_table(&mut self) -> Result<()> {
+        self.builder.set_justify_content(Justify::Left);
+        self.builder.set_text_size(TextSize::Medium);
+        let lines = self.table.lines(self.builder.cpl() as usize);
+        for (index, line) in lines.iter().enumerate() {
+            self.builder.set_is_bold(index == 0);
+            self.builder.add_content(line)?;
+            self.builder.new_line();
+        }
+        Ok(())
+    }
+
+    pub fn print(&mut self, driver: SupportedDriver) -> Result<PrintReport> {
+        if self.table.is_empty() {
+            bail!("The table has no rows to print");
+        }
+        self.with_table()?;
+        let report = self.builder.print_detailed(None, driver)?;
+        log::info!("Printed table template: {report}");
+        Ok(report)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod from_csv {
+        use super::*;
+
+        #[test]
+        fn matches_the_equivalent_json() {
+            let csv = Table::from_csv("name,qty\nEggs,12\n\"Milk, oat\",2\n").unwrap();
+            let json = Table::from_json(
+                r#"[{"name": "Eggs", "qty": 12}, {"name": "Milk, oat", "qty": "2"}]"#,
+            )
+            .unwrap();
+            assert_eq!(csv, json);
+            assert_eq!(
+                csv.lines(48),
+                [
+                    "name      qty",
+                    "--------- ---",
+                    "Eggs      12",
+                    "Milk, oat 2"
+                ]
+            );
+            assert_eq!(csv.lines(48), json.lines(48));
+        }
+
+        #[test]
+        fn matches_json_in_a_non_alphabetical_order() {
+            let csv = Table::from_csv("qty,name\n12,Eggs\n").unwrap();
+            let json = Table::from_json(r#"[{"qty": 12, "name": "Eggs"}]"#).unwrap();
+            assert_eq!(csv, json);
+            assert_eq!(json.headers, ["qty", "name"]);
+        }
+
+        #[test]
+        fn pads_short_rows_and_drops_extra_cells() {
+            let table = Table::from_csv("a,b\r\n1\r\n\r\n2,3,4").unwrap();
+            assert_eq!(table.rows, [["1", ""], ["2", "3"]]);
+        }
+
+        #[test]
+        fn empty_file_is_an_empty_table() {
+            assert!(Table::from_csv("").unwrap().is_empty());
+            assert!(Table::from_csv("\n\n").unwrap().headers.is_empty());
+        }
+
+        #[test]
+        fn unterminated_quote_is_an_error() {
+            assert!(Table::from_csv("a\n\"open").is_err());
+        }
+    }
+
+    mod from_json {
+        use super::*;
+
+        #[test]
+        fn missing_keys_are_empty_cells() {
+            let table = Table::from_json(r#"[{"b": 1}, {"c": null, "a": true}]"#).unwrap();
+            assert_eq!(table.headers, ["b", "c", "a"]);
+            assert_eq!(table.rows, [["1", "", ""], ["", "", "true"]]);
+        }
+
+        #[test]
+        fn empty_file_is_an_empty_table() {
+            assert!(Table::from_json(" \n").unwrap().is_empty());
+            assert!(Table::from_json("[]").unwrap().is_empty());
+        }
+
+        #[test]
+        fn rejects_nested_shapes() {
+            assert!(Table::from_json(r#"{"a": 1}"#).is_err());
+            assert!(Table::from_json("[1, 2]").is_err());
+        }
+    }
+
+    mod lines {
+        use super::*;
+
+        #[test]
+        fn truncates_the_widest_column_to_fit() {
+            let table = Table {
+                headers: vec!["id".to_string(), "note".to_string()],
+                rows: vec![vec![
+                    "1".to_string(),
+                    "a long note that overflows".to_string(),
+                ]],
+            };
+            let lines = table.lines(16);
+            assert!(lines.iter().all(|line| line.chars().count() <= 16));
+            assert_eq!(lines[2], "1  a long note t");
+        }
+    }
+}
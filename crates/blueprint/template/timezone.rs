@@ -0,0 +1,143 @@
+use chrono::{DateTime, FixedOffset, Local, NaiveDate, TimeZone, Utc};
+use chrono_tz::Tz;
+use serde::Deserialize;
+use std::str::FromStr;
+
+/// The timezone dates are shown in on printed banners. Dates travel as UTC, so a print
+/// triggered remotely would otherwise show the UTC date rather than the reader's.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Deserialize)]
+#[serde(try_from = "String")]
+pub enum BannerTimezone {
+    /// The system's local zone
+    #[default]
+    Local,
+    /// A fixed UTC offset such as `-05:00`
+    Fixed(FixedOffset),
+    /// A named IANA zone such as `America/New_York`
+    Named(Tz),
+}
+impl BannerTimezone {
+    /// `date` as seen in this timezone
+    pub fn localize(&self, date: DateTime<Utc>) -> DateTime<FixedOffset> {
+        match self {
+            BannerTimezone::Local => date.with_timezone(&Local).fixed_offset(),
+            BannerTimezone::Fixed(offset) => date.with_timezone(offset),
+            BannerTimezone::Named(tz) => date.with_timezone(tz).fixed_offset(),
+        }
+    }
+
+    /// The first instant of `date` in this timezone, so a typed calendar date localizes
+    /// back to itself
+    pub fn start_of_day(&self, date: NaiveDate) -> DateTime<Utc> {
+        match self {
+            BannerTimezone::Local => first_instant(&Local, date),
+            BannerTimezone::Fixed(offset) => first_instant(offset, date),
+            BannerTimezone::Named(tz) => first_instant(tz, date),
+        }
+    }
+}
+
+/// The first whole hour of `date` that exists in `tz`, midnight unless a daylight saving
+/// change skips it
+fn first_instant<Tz: TimeZone>(tz: &Tz, date: NaiveDate) -> DateTime<Utc> {
+    (0..24)
+        .find_map(|hour| {
+            tz.from_local_datetime(&date.and_hms_opt(hour, 0, 0)?)
+                .earliest()
+        })
+        .map_or_else(
+            || date.and_time(chrono::NaiveTime::MIN).and_utc(),
+            |start| start.with_timezone(&Utc),
+        )
+}
+
+impl FromStr for BannerTimezone {
+    type Err = anyhow::Error;
+
+    /// Accepts `local`, an offset like `+05:30`, or an IANA zone name
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        if value.eq_ignore_ascii_case("local") {
+            return Ok(BannerTimezone::Local);
+        }
+        if let Ok(offset) = FixedOffset::from_str(value) {
+            return Ok(BannerTimezone::Fixed(offset));
+        }
+        Tz::from_str(value).map(BannerTimezone::Named).map_err(|_| {
+            anyhow::anyhow!("Unknown timezone '{value}', expected local, an offset or a zone name")
+        })
+    }
+}
+
+impl TryFrom<String> for BannerTimezone {
+    type Error = anyhow::Error;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn near_midnight_utc() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 10, 15, 2, 30, 0).unwrap()
+    }
+
+    mod localize {
+        use super::*;
+
+        #[test]
+        fn negative_offset_shows_previous_day() {
+            let timezone: BannerTimezone = "-05:00".parse().unwrap();
+            let local = timezone.localize(near_midnight_utc());
+            assert_eq!(
+                local.format("%A, %B %d, %Y").to_string(),
+                "Wednesday, October 14, 2026"
+            );
+        }
+
+        #[test]
+        fn named_zone_applies_daylight_saving() {
+            let timezone: BannerTimezone = "America/New_York".parse().unwrap();
+            let local = timezone.localize(near_midnight_utc());
+            assert_eq!(
+                local.format("%Y-%m-%d %H:%M").to_string(),
+                "2026-10-14 22:30"
+            );
+        }
+    }
+
+    mod start_of_day {
+        use super::*;
+
+        #[test]
+        fn localizes_back_to_the_same_date() {
+            let date = NaiveDate::from_ymd_opt(2026, 10, 15).unwrap();
+            for timezone in ["-05:00", "+09:00", "America/New_York", "local"] {
+                let timezone: BannerTimezone = timezone.parse().unwrap();
+                let local = timezone.localize(timezone.start_of_day(date));
+                assert_eq!(local.date_naive(), date);
+                assert_eq!(local.format("%H:%M").to_string(), "00:00");
+            }
+        }
+    }
+
+    mod from_str {
+        use super::*;
+
+        #[test]
+        fn parses_local_case_insensitively() {
+            assert_eq!(
+                "Local".parse::<BannerTimezone>().unwrap(),
+                BannerTimezone::Local
+            );
+        }
+
+        #[test]
+        fn rejects_unknown_names() {
+            let err = "Mars/Olympus".parse::<BannerTimezone>().unwrap_err();
+            assert!(err.to_string().contains("Mars/Olympus"));
+        }
+    }
+}
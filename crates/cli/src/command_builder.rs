@@ -1,5 +1,15 @@
 use clap::ValueEnum;
-use std::fmt::Display;
+use std::{
+    fmt::Display,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+/// Set once from `--debug-hex`, forwarded to every pi_cli command
+static DEBUG_HEX: AtomicBool = AtomicBool::new(false);
+
+pub fn set_debug_hex(enabled: bool) {
+    DEBUG_HEX.store(enabled, Ordering::Relaxed);
+}
 
 fn shell_escape(value: &str) -> String {
     value.replace('\'', "'\\''")
@@ -11,9 +21,14 @@ pub struct PiCommandBuilder {
 
 impl PiCommandBuilder {
     pub fn new(subcommand: &str) -> Self {
+        Self::with_debug_hex(subcommand, DEBUG_HEX.load(Ordering::Relaxed))
+    }
+
+    fn with_debug_hex(subcommand: &str, debug_hex: bool) -> Self {
         Self {
             parts: vec!["pi_cli".to_string(), subcommand.to_string()],
         }
+        .flag("debug-hex", debug_hex)
     }
 
     pub fn positional(mut self, value: &str) -> Self {
@@ -36,6 +51,15 @@ impl PiCommandBuilder {
         self
     }
 
+    /// Repeat `--name 'value'` for every value
+    pub fn named_each(mut self, name: &str, values: &[String]) -> Self {
+        for value in values {
+            self.parts
+                .push(format!("--{name} '{}'", shell_escape(value)));
+        }
+        self
+    }
+
     pub fn named_enum<V: ValueEnum>(mut self, name: &str, value: Option<V>) -> Self {
         if let Some(v) = value {
             let enum_name = v
@@ -52,3 +76,24 @@ impl PiCommandBuilder {
         self.parts.join(" ")
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod with_debug_hex {
+        use super::*;
+
+        #[test]
+        fn forwards_the_flag_only_when_set() {
+            assert_eq!(
+                PiCommandBuilder::with_debug_hex("now", true).build(),
+                "pi_cli now --debug-hex"
+            );
+            assert_eq!(
+                PiCommandBuilder::with_debug_hex("now", false).build(),
+                "pi_cli now"
+            );
+        }
+    }
+}
@@ -0,0 +1,24 @@
+use crate::{
+    command_builder::PiCommandBuilder,
+    network::{Network, SupportedExtension},
+};
+use anyhow::Context;
+pub use cli_shared::data_command::DataArgs;
+
+pub async fn handle_data_command(
+    args: DataArgs,
+    cut: bool,
+    width: u8,
+    json: bool,
+) -> anyhow::Result<()> {
+    let mut conn = Network::new()?;
+    let remote_file = conn
+        .upload_file(&args.path, true, SupportedExtension::DATA)
+        .with_context(|| format!("Failed to upload data file '{}'", args.path.display()))?;
+    let cmd = PiCommandBuilder::new("data")
+        .positional(&remote_file)
+        .flag("no-cut", !cut)
+        .named("width", Some(width))
+        .flag("json", json);
+    conn.execute_command(cmd)
+}
@@ -1,17 +1,90 @@
-use crate::{command_builder::PiCommandBuilder, network::Network};
+use crate::{
+    command_builder::PiCommandBuilder,
+    network::{Network, SupportedExtension},
+};
 use anyhow::bail;
-pub use cli_shared::file_command::FileArgs;
+pub use cli_shared::file_command::{FileArgs, FileBatchArgs};
 
-pub async fn handle_file_command(args: FileArgs, cut: bool) -> anyhow::Result<()> {
+pub async fn handle_file_command(
+    mut args: FileArgs,
+    cut: bool,
+    width: u8,
+    json: bool,
+) -> anyhow::Result<()> {
+    if !args.is_stdin() {
+        let mut conn = Network::new()?;
+        return print_file(&mut conn, &args, cut, width, json);
+    }
+    args.take_stdin(std::io::stdin().lock(), &std::env::temp_dir())?;
+    let result = Network::new().and_then(|mut conn| print_file(&mut conn, &args, cut, width, json));
+    // The spooled copy is only needed for the upload
+    if let Err(e) = std::fs::remove_file(&args.path) {
+        log::warn!("Failed to remove '{}': {e}", args.path.display());
+    }
+    result
+}
+
+/// Print every markdown and text file in a directory as its own job, in name order.
+/// A file that fails is logged and the rest still print.
+pub async fn handle_file_batch_command(
+    args: FileBatchArgs,
+    cut: bool,
+    width: u8,
+    json: bool,
+) -> anyhow::Result<()> {
+    let files = cli_shared::file_command::batch_files(&args.dir, args.format)?;
+    if files.is_empty() {
+        log::warn!("No printable files in '{}'", args.dir.display());
+        return Ok(());
+    }
     let mut conn = Network::new()?;
-    match conn.upload_file(&args.path, true) {
+    let mut failed = 0;
+    for path in &files {
+        let file_args = FileArgs {
+            path: path.clone(),
+            format: None,
+            rows: None,
+            prehook_command: None,
+            prehook_command_args: None,
+            line_numbers: false,
+            split_on_rule: false,
+            compact: false,
+            title: args.titles.then_some(None),
+            dry_run: false,
+        };
+        if let Err(e) = print_file(&mut conn, &file_args, cut, width, json) {
+            log::error!("Failed to print '{}': {e:#}", path.display());
+            failed += 1;
+        }
+    }
+    if failed > 0 {
+        bail!("{failed} of {} files failed to print", files.len());
+    }
+    Ok(())
+}
+
+fn print_file(
+    conn: &mut Network,
+    args: &FileArgs,
+    cut: bool,
+    width: u8,
+    json: bool,
+) -> anyhow::Result<()> {
+    match conn.upload_file(&args.path, true, SupportedExtension::DOCUMENTS) {
         Ok(remote_file) => {
             let cmd = PiCommandBuilder::new("file")
                 .positional(&remote_file)
                 .named("rows", args.rows)
                 .flag("no-cut", !cut)
-                .named("prehook-command", args.prehook_command)
-                .named("prehook-command-args", args.prehook_command_args);
+                .named("width", Some(width))
+                .flag("json", json)
+                .named("prehook-command", args.prehook_command.clone())
+                .named("prehook-command-args", args.prehook_command_args.clone())
+                .flag("line-numbers", args.line_numbers)
+                .flag("split-on-rule", args.split_on_rule)
+                .flag("compact", args.compact)
+                .named("title", args.title())
+                .flag("dry-run", args.dry_run);
             conn.execute_command(cmd)
         }
         Err(e) => {
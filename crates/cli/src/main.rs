@@ -1,19 +1,40 @@
 mod command_builder;
+mod data_command;
 mod file_command;
 mod network;
+mod now_command;
 mod pulse_command;
+mod qr_command;
 mod template_command;
 
-use clap::{Parser, Subcommand};
+use clap::{CommandFactory, Parser, Subcommand};
+use clap_complete::Shell;
 
 #[derive(Debug, Subcommand)]
 pub enum Commands {
     #[clap(about = "Print a file")]
     File(file_command::FileArgs),
+    #[clap(about = "Print every markdown and text file in a directory, one job each")]
+    FileBatch(file_command::FileBatchArgs),
+    #[clap(about = "Print a JSON or CSV file as a table")]
+    Data(data_command::DataArgs),
     #[clap(about = "Print a predefined template")]
-    Template(cli_shared::template_command::TemplateArgs),
+    Template(template_command::TemplateArgs),
+    #[clap(about = "Print a QR code")]
+    Qr(qr_command::QrArgs),
+    #[clap(about = "Print the current date and time")]
+    Now {
+        #[clap(
+            long,
+            help = "strftime format, e.g. \"%Y-%m-%d %H:%M\"",
+            long_help = "strftime format. Defaults to \"%A %B %d, %Y  %H:%M\""
+        )]
+        format: Option<String>,
+    },
     #[clap(about = "Schedule a recurring print job")]
     Pulse(pulse_command::PulseArgs),
+    #[clap(about = "Print a shell completion script to stdout", hide = true)]
+    Completions { shell: Shell },
 }
 
 #[derive(Debug, clap::Parser)]
@@ -28,6 +49,29 @@ pub struct App {
         global = true
     )]
     no_cut: bool,
+    #[clap(
+        long,
+        help = "Characters per line, 16 to 72. 48 for 80mm paper, 32 for 58mm",
+        default_value_t = cli_shared::tasks::DEFAULT_CPL,
+        value_parser = clap::value_parser!(u8)
+            .range(cli_shared::tasks::MIN_CPL as i64..=cli_shared::tasks::MAX_CPL as i64),
+        global = true
+    )]
+    width: u8,
+    #[clap(
+        long,
+        help = "Have the printer report a JSON result object instead of a message",
+        global = true
+    )]
+    json: bool,
+    #[clap(
+        long,
+        help = "Have the printer log the ESC/POS bytes it sends in hex",
+        long_help = "Have the printer log every ESC/POS instruction it sends in hex, to capture \
+                     the exact byte stream for a bug report",
+        global = true
+    )]
+    debug_hex: bool,
 }
 
 #[tokio::main]
@@ -35,16 +79,66 @@ async fn main() -> anyhow::Result<()> {
     cli_shared::init_logging("konan");
 
     let app = App::parse();
+    command_builder::set_debug_hex(app.debug_hex);
 
     match app.command {
         Commands::File(file_args) => {
-            file_command::handle_file_command(file_args, !app.no_cut).await
+            file_command::handle_file_command(file_args, !app.no_cut, app.width, app.json).await
+        }
+        Commands::FileBatch(batch_args) => {
+            file_command::handle_file_batch_command(batch_args, !app.no_cut, app.width, app.json)
+                .await
+        }
+        Commands::Data(data_args) => {
+            data_command::handle_data_command(data_args, !app.no_cut, app.width, app.json).await
         }
         Commands::Template(template_args) => {
-            template_command::handle_template_command(template_args, !app.no_cut).await
+            template_command::handle_template_command(
+                template_args,
+                !app.no_cut,
+                app.width,
+                app.json,
+            )
+            .await
+        }
+        Commands::Qr(qr_args) => {
+            qr_command::handle_qr_command(qr_args, !app.no_cut, app.width, app.json).await
+        }
+        Commands::Now { format } => {
+            now_command::handle_now_command(format, !app.no_cut, app.width, app.json).await
         }
         Commands::Pulse(pulse_args) => {
-            pulse_command::handle_pulse_command(pulse_args, !app.no_cut).await
+            pulse_command::handle_pulse_command(pulse_args, !app.no_cut, app.width, app.json).await
         }
+        Commands::Completions { shell } => {
+            write_completions(shell, &mut std::io::stdout());
+            Ok(())
+        }
+    }
+}
+
+/// Write the completion script for `shell`, e.g. `konan completions zsh > _konan`
+fn write_completions(shell: Shell, out: &mut impl std::io::Write) {
+    clap_complete::generate(shell, &mut App::command(), "konan", out);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bash_completions_cover_subcommands() {
+        let mut script = Vec::new();
+        write_completions(Shell::Bash, &mut script);
+        let script = String::from_utf8(script).unwrap();
+        assert!(script.contains("template"));
+    }
+
+    #[test]
+    fn width_is_checked() {
+        let width = |value: &str| App::try_parse_from(["konan", "now", "--width", value]);
+        assert_eq!(width("72").unwrap().width, 72);
+        assert!(width("0").is_err());
+        assert!(width("200").is_err());
     }
 }
@@ -1,11 +1,7 @@
 use crate::command_builder::PiCommandBuilder;
 use anyhow::{Context, Result};
 use ssh2::Session;
-use std::{
-    io::prelude::*,
-    net::TcpStream,
-    path::{Path, PathBuf},
-};
+use std::{io::prelude::*, net::TcpStream, path::Path};
 
 pub struct Network {
     session: Session,
@@ -63,7 +59,11 @@ impl Network {
         Ok(())
     }
 
-    fn prepare_file(p: &Path, replace_file_name: bool) -> Result<(String, i32, u64)> {
+    fn prepare_file(
+        p: &Path,
+        replace_file_name: bool,
+        accepted: &[SupportedExtension],
+    ) -> Result<(String, i32, u64)> {
         // Check the path exists and is a file
         if !p.exists() {
             anyhow::bail!("File does not exist: {}", p.display());
@@ -72,25 +72,24 @@ impl Network {
             anyhow::bail!("Path is not a file: {}", p.display());
         }
 
-        let extension: SupportedExtension = match p.extension() {
-            Some(extension) => match extension.to_str() {
-                Some("md") => SupportedExtension::Md,
-                Some("txt") => SupportedExtension::Txt,
-                _ => anyhow::bail!(
-                    "File must be a markdown (.md) or text (.txt) file, got: {:?}",
-                    extension
-                ),
+        let expected = accepted
+            .iter()
+            .map(SupportedExtension::description)
+            .collect::<Vec<_>>()
+            .join(" or ");
+        let extension = match p.extension() {
+            Some(extension) => match accepted
+                .iter()
+                .find(|accepted| extension.to_str() == Some(accepted.as_str()))
+            {
+                Some(accepted) => accepted,
+                None => anyhow::bail!("File must be a {expected} file, got: {:?}", extension),
             },
-            None => {
-                anyhow::bail!("File must be a markdown (.md) or text (.txt) file")
-            }
+            None => anyhow::bail!("File must be a {expected} file"),
         };
 
         let file_name = match replace_file_name {
-            true => match extension {
-                SupportedExtension::Txt => "konan_print.txt".to_string(),
-                SupportedExtension::Md => "konan_print.md".to_string(),
-            },
+            true => format!("konan_print.{}", extension.as_str()),
             false => p
                 .file_name()
                 .context("Path has no file name")?
@@ -121,8 +120,14 @@ impl Network {
         )
     }
 
-    pub fn upload_file(&mut self, path: &PathBuf, replace_file_name: bool) -> Result<String> {
-        let (file_name, mode, size) = Self::prepare_file(path, replace_file_name)?;
+    /// Upload `path` to the printer files directory, refusing extensions not in `accepted`
+    pub fn upload_file(
+        &mut self,
+        path: &Path,
+        replace_file_name: bool,
+        accepted: &[SupportedExtension],
+    ) -> Result<String> {
+        let (file_name, mode, size) = Self::prepare_file(path, replace_file_name, accepted)?;
         let remote_path = Self::remote_files_path(&file_name);
         self.scp_upload(path, &remote_path, mode, size)?;
         Ok(file_name)
@@ -143,7 +148,35 @@ impl Network {
     }
 }
 
-enum SupportedExtension {
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SupportedExtension {
     Txt,
     Md,
+    Json,
+    Csv,
+}
+impl SupportedExtension {
+    /// Files printed as documents by the file and pulse commands
+    pub const DOCUMENTS: &[SupportedExtension] = &[SupportedExtension::Md, SupportedExtension::Txt];
+    /// Files printed as a table by the data command
+    pub const DATA: &[SupportedExtension] = &[SupportedExtension::Json, SupportedExtension::Csv];
+
+    fn as_str(&self) -> &'static str {
+        match self {
+            SupportedExtension::Txt => "txt",
+            SupportedExtension::Md => "md",
+            SupportedExtension::Json => "json",
+            SupportedExtension::Csv => "csv",
+        }
+    }
+
+    fn description(&self) -> String {
+        let kind = match self {
+            SupportedExtension::Txt => "text",
+            SupportedExtension::Md => "markdown",
+            SupportedExtension::Json => "JSON",
+            SupportedExtension::Csv => "CSV",
+        };
+        format!("{kind} (.{})", self.as_str())
+    }
 }
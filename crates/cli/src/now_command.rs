@@ -0,0 +1,16 @@
+use crate::{command_builder::PiCommandBuilder, network::Network};
+
+pub async fn handle_now_command(
+    format: Option<String>,
+    cut: bool,
+    width: u8,
+    json: bool,
+) -> anyhow::Result<()> {
+    let mut conn = Network::new()?;
+    let cmd = PiCommandBuilder::new("now")
+        .named("format", format)
+        .flag("no-cut", !cut)
+        .named("width", Some(width))
+        .flag("json", json);
+    conn.execute_command(cmd)
+}
@@ -1,4 +1,7 @@
-use crate::{command_builder::PiCommandBuilder, network::Network};
+use crate::{
+    command_builder::PiCommandBuilder,
+    network::{Network, SupportedExtension},
+};
 use anyhow::Result;
 use clap::{Parser, Subcommand};
 use cli_shared::{PulseRecipe, file_command::FileArgs, tasks, template_command::TemplateCommand};
@@ -35,7 +38,7 @@ pub enum PulseDirectCommand {
     List,
 }
 
-pub async fn handle_pulse_command(args: PulseArgs, cut: bool) -> Result<()> {
+pub async fn handle_pulse_command(args: PulseArgs, cut: bool, width: u8, json: bool) -> Result<()> {
     let mut conn = Network::new()?;
     match args.command {
         PulseDirectCommand::AddTemplate(template_args) => {
@@ -48,30 +51,68 @@ pub async fn handle_pulse_command(args: PulseArgs, cut: bool) -> Result<()> {
 
             let recipe = match template_args.command {
                 TemplateCommand::Box {
-                    rows,
+                    height,
                     date,
                     banner,
                     lined,
+                    checkboxes,
+                    columns,
+                    ledger,
                 } => PulseRecipe::BoxTemplate(tasks::BoxTemplatePulseRecipe {
                     cut,
-                    rows,
+                    cpl: width,
+                    height,
                     lined,
+                    checkboxes,
                     banner,
                     date,
+                    columns,
+                    ledger,
+                }),
+                TemplateCommand::Checklist {
+                    items,
+                    title,
+                    numbered,
+                    blank_lines,
+                } => PulseRecipe::Checklist(tasks::ChecklistTemplate {
+                    cut,
+                    cpl: width,
+                    items,
+                    title,
+                    numbered,
+                    blank_lines: blank_lines.unwrap_or_default(),
                 }),
                 TemplateCommand::HabitTracker {
-                    habit, time_period, ..
+                    habit,
+                    time_period,
+                    mark_weekends,
+                    ..
                 } => PulseRecipe::HabitTracker(tasks::HabitTrackerPulseRecipe {
                     cut,
+                    cpl: width,
                     habit,
                     time_period: time_period.unwrap_or_default(),
+                    mark_weekends,
+                }),
+                TemplateCommand::Calendar {
+                    week,
+                    lined,
+                    weeks_per_page,
+                    ..
+                } => PulseRecipe::Calendar(tasks::CalendarPulseRecipe {
+                    cut,
+                    cpl: width,
+                    week,
+                    lined,
+                    weeks_per_page,
                 }),
             };
             let command_json = recipe.to_json()?;
             let cmd = PiCommandBuilder::new("pulse add")
                 .positional(&name)
                 .positional(&rrule)
-                .positional(&command_json);
+                .positional(&command_json)
+                .flag("json", json);
             conn.execute_command(cmd)
         }
         PulseDirectCommand::AddFile(file_args) => {
@@ -82,28 +123,38 @@ pub async fn handle_pulse_command(args: PulseArgs, cut: bool) -> Result<()> {
                 .rrule
                 .ok_or_else(|| anyhow::anyhow!("--rrule is required when adding a pulse"))?;
 
-            let filename = conn.upload_file(&file_args.path, false)?;
+            let filename =
+                conn.upload_file(&file_args.path, false, SupportedExtension::DOCUMENTS)?;
+            let title = file_args.title();
             let recipe = PulseRecipe::File(tasks::KonanFile {
                 cut,
+                cpl: width,
                 name: filename,
                 rows: file_args.rows,
                 prehook_command: file_args.prehook_command,
                 prehook_command_arg: file_args.prehook_command_args,
+                line_numbers: file_args.line_numbers,
+                split_on_rule: file_args.split_on_rule,
+                compact: file_args.compact,
+                title,
             });
 
             let command_json = recipe.to_json()?;
             let cmd = PiCommandBuilder::new("pulse add")
                 .positional(&name)
                 .positional(&rrule)
-                .positional(&command_json);
+                .positional(&command_json)
+                .flag("json", json);
             conn.execute_command(cmd)
         }
         PulseDirectCommand::Delete { id } => {
-            let cmd = PiCommandBuilder::new("pulse delete").positional(&id.to_string());
+            let cmd = PiCommandBuilder::new("pulse delete")
+                .positional(&id.to_string())
+                .flag("json", json);
             conn.execute_command(cmd)
         }
         PulseDirectCommand::List => {
-            let cmd = PiCommandBuilder::new("pulse list");
+            let cmd = PiCommandBuilder::new("pulse list").flag("json", json);
             conn.execute_command(cmd)
         }
     }
@@ -0,0 +1,19 @@
+use crate::{command_builder::PiCommandBuilder, network::Network};
+pub use cli_shared::qr_command::QrArgs;
+
+pub async fn handle_qr_command(
+    args: QrArgs,
+    cut: bool,
+    width: u8,
+    json: bool,
+) -> anyhow::Result<()> {
+    let mut conn = Network::new()?;
+    let cmd = PiCommandBuilder::new("qr")
+        .positional(&args.data)
+        .named("size", args.size)
+        .named("caption", args.caption)
+        .flag("no-cut", !cut)
+        .named("width", Some(width))
+        .flag("json", json);
+    conn.execute_command(cmd)
+}
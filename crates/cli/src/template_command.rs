@@ -1,33 +1,141 @@
 use crate::{command_builder::PiCommandBuilder, network::Network};
-use cli_shared::template_command::{TemplateArgs, TemplateCommand};
+use anyhow::Result;
+use clap::{Parser, Subcommand};
+use cli_shared::{
+    saved_templates::{TemplateStore, saved_templates_path},
+    template_command::TemplateCommand,
+};
 
-pub async fn handle_template_command(args: TemplateArgs, cut: bool) -> anyhow::Result<()> {
-    let mut conn = Network::new()?;
+#[derive(Debug, Parser)]
+pub struct TemplateArgs {
+    #[clap(subcommand)]
+    pub command: KonanTemplateCommand,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum KonanTemplateCommand {
+    #[clap(flatten)]
+    Template(TemplateCommand),
+    #[clap(about = "Save a template configuration under a name, e.g. `save daily box --lined`")]
+    Save {
+        #[clap(help = "Name to print the template by")]
+        name: String,
+        #[clap(subcommand)]
+        template: TemplateCommand,
+    },
+    #[clap(about = "Print a saved template")]
+    Print { name: String },
+    #[clap(about = "List saved templates")]
+    List,
+    #[clap(about = "Delete a saved template")]
+    Delete { name: String },
+}
+
+pub async fn handle_template_command(
+    args: TemplateArgs,
+    cut: bool,
+    width: u8,
+    json: bool,
+) -> Result<()> {
+    let path = saved_templates_path()?;
     match args.command {
+        KonanTemplateCommand::Template(template) => print_template(template, cut, width, json),
+        KonanTemplateCommand::Save { name, template } => {
+            let mut store = TemplateStore::load(&path)?;
+            if store.insert(&name, template).is_some() {
+                log::info!("Replaced saved template '{name}'");
+            }
+            store.save(&path)
+        }
+        KonanTemplateCommand::Print { name } => {
+            let template = TemplateStore::load(&path)?.get(&name)?.clone();
+            print_template(template, cut, width, json)
+        }
+        KonanTemplateCommand::List => {
+            for (name, template) in TemplateStore::load(&path)?.iter() {
+                println!("{name}\t{}", serde_json::to_string(template)?);
+            }
+            Ok(())
+        }
+        KonanTemplateCommand::Delete { name } => {
+            let mut store = TemplateStore::load(&path)?;
+            store.remove(&name)?;
+            store.save(&path)
+        }
+    }
+}
+
+fn print_template(template: TemplateCommand, cut: bool, width: u8, json: bool) -> Result<()> {
+    let mut conn = Network::new()?;
+    match template {
         TemplateCommand::Box {
-            rows,
+            height,
             lined,
+            checkboxes,
             date,
             banner,
+            columns,
+            ledger,
         } => {
             let cmd = PiCommandBuilder::new("template box")
-                .named("rows", rows)
+                .named("height", height)
                 .flag("lined", lined)
+                .flag("checkboxes", checkboxes)
                 .named_enum("date", date)
                 .named("banner", banner)
-                .flag("no-cut", !cut);
+                .named("columns", (!columns.is_empty()).then(|| columns.join(",")))
+                .named("ledger", (!ledger.is_empty()).then(|| ledger.join(",")))
+                .flag("no-cut", !cut)
+                .named("width", Some(width))
+                .flag("json", json);
+            conn.execute_command(cmd)
+        }
+        TemplateCommand::Checklist {
+            items,
+            title,
+            numbered,
+            blank_lines,
+        } => {
+            let cmd = PiCommandBuilder::new("template checklist")
+                .named_each("item", &items)
+                .named("title", title)
+                .flag("numbered", numbered)
+                .named("blank-lines", blank_lines)
+                .flag("no-cut", !cut)
+                .named("width", Some(width))
+                .flag("json", json);
             conn.execute_command(cmd)
         }
         TemplateCommand::HabitTracker {
             habit,
             start_date,
             time_period,
+            mark_weekends,
         } => {
             let cmd = PiCommandBuilder::new("template habit-tracker")
                 .positional(&habit)
                 .named("start-date", start_date)
                 .named_enum("time-period", time_period)
-                .flag("no-cut", !cut);
+                .flag("mark-weekends", mark_weekends)
+                .flag("no-cut", !cut)
+                .named("width", Some(width))
+                .flag("json", json);
+            conn.execute_command(cmd)
+        }
+        TemplateCommand::Calendar {
+            date,
+            week,
+            lined,
+            weeks_per_page,
+        } => {
+            let cmd = PiCommandBuilder::new("template calendar")
+                .named("date", date)
+                .flag("week", week)
+                .flag("lined", lined)
+                .named("weeks-per-page", weeks_per_page)
+                .flag("no-cut", !cut)
+                .named("width", Some(width))
+                .flag("json", json);
             conn.execute_command(cmd)
         }
     }
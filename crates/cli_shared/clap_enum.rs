@@ -1,10 +1,10 @@
-use std::{fmt::Display, path::PathBuf};
-
 use anyhow::{Context, bail};
-use chrono::{DateTime, Datelike, Duration, Months, Utc, Weekday};
+use chrono::{DateTime, Datelike, Duration, Months, NaiveDate, Utc, Weekday};
+use clap::ValueEnum;
 use serde::{Deserialize, Serialize};
+use std::{fmt::Display, path::PathBuf};
 
-#[derive(clap::ValueEnum, Clone, Copy, Debug, Default, Serialize, Deserialize)]
+#[derive(clap::ValueEnum, Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
 pub enum DateBanner {
     #[default]
     Today,
@@ -25,34 +25,66 @@ pub enum DateBanner {
     Sun,
 }
 impl DateBanner {
-    /// Calculate the next occurrence of a given weekday.
-    /// If today is that weekday, returns next week's occurrence.
-    fn next_weekday(target: Weekday) -> DateTime<Utc> {
-        let now = Utc::now();
+    /// Calculate the next occurrence of a given weekday after `now`.
+    /// If `now` is that weekday, returns next week's occurrence.
+    fn next_weekday(target: Weekday, now: DateTime<Utc>) -> DateTime<Utc> {
         let current = now.weekday().num_days_from_monday();
         let target_day = target.num_days_from_monday();
         let days_until = (target_day as i64 - current as i64 + 7) % 7;
         let days_until = if days_until == 0 { 7 } else { days_until };
         now + Duration::days(days_until)
     }
+
+    /// Resolve the banner to a concrete date relative to `now`.
+    pub fn resolve(self, now: DateTime<Utc>) -> DateTime<Utc> {
+        match self {
+            DateBanner::Today => now,
+            DateBanner::Tomorrow => now + Duration::days(1),
+            DateBanner::Mon => DateBanner::next_weekday(Weekday::Mon, now),
+            DateBanner::Tue => DateBanner::next_weekday(Weekday::Tue, now),
+            DateBanner::Wed => DateBanner::next_weekday(Weekday::Wed, now),
+            DateBanner::Thu => DateBanner::next_weekday(Weekday::Thu, now),
+            DateBanner::Fri => DateBanner::next_weekday(Weekday::Fri, now),
+            DateBanner::Sat => DateBanner::next_weekday(Weekday::Sat, now),
+            DateBanner::Sun => DateBanner::next_weekday(Weekday::Sun, now),
+        }
+    }
 }
 impl From<DateBanner> for chrono::DateTime<Utc> {
     fn from(val: DateBanner) -> Self {
-        match val {
-            DateBanner::Today => Utc::now(),
-            DateBanner::Tomorrow => Utc::now() + Duration::days(1),
-            DateBanner::Mon => DateBanner::next_weekday(Weekday::Mon),
-            DateBanner::Tue => DateBanner::next_weekday(Weekday::Tue),
-            DateBanner::Wed => DateBanner::next_weekday(Weekday::Wed),
-            DateBanner::Thu => DateBanner::next_weekday(Weekday::Thu),
-            DateBanner::Fri => DateBanner::next_weekday(Weekday::Fri),
-            DateBanner::Sat => DateBanner::next_weekday(Weekday::Sat),
-            DateBanner::Sun => DateBanner::next_weekday(Weekday::Sun),
-        }
+        val.resolve(Utc::now())
     }
 }
 
-#[derive(clap::ValueEnum, Clone, Copy, Debug, Default, Serialize, Deserialize)]
+/// Parse a user supplied date relative to `now`.
+/// Accepts `YYYY-MM-DD`, any `DateBanner` name ("today", "tomorrow", "mon".."sun")
+/// and day offsets such as "+3d". A `YYYY-MM-DD` date is the reader's calendar date,
+/// placed at the instant `start_of_day` gives for it.
+pub fn parse_relative_date(
+    value: &str,
+    now: DateTime<Utc>,
+    start_of_day: impl Fn(NaiveDate) -> DateTime<Utc>,
+) -> anyhow::Result<DateTime<Utc>> {
+    let value = value.trim();
+    if let Ok(date) = NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+        return Ok(start_of_day(date));
+    }
+    if let Ok(banner) = DateBanner::from_str(value, true) {
+        return Ok(banner.resolve(now));
+    }
+    if let Some(days) = value
+        .strip_prefix('+')
+        .and_then(|v| v.strip_suffix('d'))
+        .and_then(|v| v.parse::<u32>().ok())
+    {
+        return now
+            .checked_add_signed(Duration::days(days.into()))
+            .with_context(|| format!("Date '{value}' is too far in the future"));
+    }
+    bail!("Unrecognized date '{value}'. Expected YYYY-MM-DD, today, tomorrow, mon..sun or +Nd")
+}
+
+#[derive(clap::ValueEnum, Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
 pub enum TimePeriod {
     Week,
     #[default]
@@ -79,6 +111,29 @@ impl From<TimePeriod> for chrono::DateTime<Utc> {
     }
 }
 
+/// Printable file types
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq)]
+pub enum FileFormat {
+    Markdown,
+    Text,
+}
+impl FileFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            FileFormat::Markdown => "md",
+            FileFormat::Text => "txt",
+        }
+    }
+
+    pub fn from_path(path: &std::path::Path) -> Option<Self> {
+        match path.extension()?.to_str()? {
+            "md" => Some(FileFormat::Markdown),
+            "txt" => Some(FileFormat::Text),
+            _ => None,
+        }
+    }
+}
+
 #[derive(clap::ValueEnum, Clone, Debug, Serialize, Deserialize)]
 pub enum AllowedCommand {
     DailyBugleNow,
@@ -120,3 +175,133 @@ impl AllowedCommand {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{NaiveTime, TimeZone};
+
+    mod parse_relative_date {
+        use super::*;
+
+        /// Wednesday, October 14 2026 at noon
+        fn now() -> DateTime<Utc> {
+            Utc.with_ymd_and_hms(2026, 10, 14, 12, 0, 0).unwrap()
+        }
+
+        fn utc_midnight(date: NaiveDate) -> DateTime<Utc> {
+            Utc.from_utc_datetime(&date.and_time(NaiveTime::MIN))
+        }
+
+        fn parsed_day(value: &str) -> NaiveDate {
+            parse_relative_date(value, now(), utc_midnight)
+                .unwrap()
+                .date_naive()
+        }
+
+        fn day(d: u32) -> NaiveDate {
+            NaiveDate::from_ymd_opt(2026, 10, d).unwrap()
+        }
+
+        #[test]
+        fn parses_iso_dates_at_midnight() {
+            let parsed = parse_relative_date("2026-10-01", now(), utc_midnight).unwrap();
+            assert_eq!(parsed, Utc.with_ymd_and_hms(2026, 10, 1, 0, 0, 0).unwrap());
+        }
+
+        #[test]
+        fn places_iso_dates_with_start_of_day() {
+            let start_of_day = |date: NaiveDate| utc_midnight(date) + Duration::hours(5);
+            let parsed = parse_relative_date("2026-10-15", now(), start_of_day).unwrap();
+            assert_eq!(parsed, Utc.with_ymd_and_hms(2026, 10, 15, 5, 0, 0).unwrap());
+        }
+
+        #[test]
+        fn parses_today_and_tomorrow() {
+            assert_eq!(parsed_day("today"), day(14));
+            assert_eq!(parsed_day("tomorrow"), day(15));
+        }
+
+        #[test]
+        fn parses_weekdays_as_next_occurrence() {
+            assert_eq!(parsed_day("mon"), day(19));
+            assert_eq!(parsed_day("tue"), day(20));
+            assert_eq!(parsed_day("wed"), day(21));
+            assert_eq!(parsed_day("thu"), day(15));
+            assert_eq!(parsed_day("fri"), day(16));
+            assert_eq!(parsed_day("sat"), day(17));
+            assert_eq!(parsed_day("sun"), day(18));
+        }
+
+        #[test]
+        fn is_case_insensitive() {
+            assert_eq!(parsed_day("Mon"), day(19));
+        }
+
+        #[test]
+        fn parses_day_offsets() {
+            assert_eq!(parsed_day("+3d"), day(17));
+            assert_eq!(parsed_day("+0d"), day(14));
+        }
+
+        #[test]
+        fn rejects_unrecognized_forms() {
+            for value in ["someday", "+3w", "-1d", "2026-13-01", "+99999999d"] {
+                assert!(
+                    parse_relative_date(value, now(), utc_midnight).is_err(),
+                    "{value}"
+                );
+            }
+        }
+    }
+
+    mod resolve {
+        use super::*;
+
+        #[test]
+        fn same_weekday_resolves_to_next_week() {
+            // A Wednesday
+            let now = Utc.with_ymd_and_hms(2026, 10, 14, 12, 0, 0).unwrap();
+            assert_eq!(DateBanner::Wed.resolve(now), now + Duration::weeks(1));
+        }
+
+        #[test]
+        fn keeps_time_of_day() {
+            let now = Utc.with_ymd_and_hms(2026, 10, 17, 23, 30, 0).unwrap();
+            assert_eq!(
+                DateBanner::Sun.resolve(now),
+                Utc.with_ymd_and_hms(2026, 10, 18, 23, 30, 0).unwrap()
+            );
+        }
+    }
+
+    mod into_datetime {
+        use super::*;
+
+        fn end(period: TimePeriod, start: DateTime<Utc>) -> NaiveDate {
+            TimePeriod::into_datetime(period, start).date_naive()
+        }
+
+        #[test]
+        fn adds_weeks() {
+            let start = Utc.with_ymd_and_hms(2026, 10, 14, 0, 0, 0).unwrap();
+            assert_eq!(
+                end(TimePeriod::Week, start),
+                start.date_naive() + Duration::weeks(1)
+            );
+            assert_eq!(
+                end(TimePeriod::TwoWeek, start),
+                start.date_naive() + Duration::weeks(2)
+            );
+        }
+
+        #[test]
+        fn month_clamps_to_the_last_day() {
+            let start = Utc.with_ymd_and_hms(2026, 1, 31, 0, 0, 0).unwrap();
+            assert_eq!(
+                end(TimePeriod::Month, start),
+                NaiveDate::from_ymd_opt(2026, 2, 28).unwrap()
+            );
+        }
+    }
+}
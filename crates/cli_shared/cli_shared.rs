@@ -2,17 +2,28 @@ use anyhow::Context;
 use serde::{Deserialize, Serialize};
 
 pub mod clap_enum;
+pub mod content_kind;
+pub mod data_command;
 pub mod file_command;
+pub mod qr_command;
+pub mod saved_templates;
 pub mod tasks;
 pub mod template_command;
 
 /// Direct data passed to enqueue print process
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type")]
 pub enum PrintTask {
     BoxTemplate(tasks::BoxTemplate),
+    Calendar(tasks::CalendarTemplate),
+    Checklist(tasks::ChecklistTemplate),
     HabitTracker(tasks::HabitTrackerTemplate),
     Markdown(tasks::DirectPrintOut),
     Text(tasks::DirectPrintOut),
     File(tasks::KonanFile),
+    Data(tasks::KonanData),
+    QrCode(tasks::QrCode),
+    Timestamp(tasks::Timestamp),
 }
 
 /// Tagged enum for pulse recipes that can round-trip through JSON in the database.
@@ -20,6 +31,8 @@ pub enum PrintTask {
 #[serde(tag = "type")]
 pub enum PulseRecipe {
     BoxTemplate(tasks::BoxTemplatePulseRecipe),
+    Calendar(tasks::CalendarPulseRecipe),
+    Checklist(tasks::ChecklistTemplate),
     HabitTracker(tasks::HabitTrackerPulseRecipe),
     File(tasks::KonanFile),
 }
@@ -38,6 +51,8 @@ impl From<PulseRecipe> for PrintTask {
     fn from(recipe: PulseRecipe) -> Self {
         match recipe {
             PulseRecipe::BoxTemplate(r) => PrintTask::BoxTemplate(r.into()),
+            PulseRecipe::Calendar(r) => PrintTask::Calendar(r.into()),
+            PulseRecipe::Checklist(r) => PrintTask::Checklist(r),
             PulseRecipe::HabitTracker(r) => PrintTask::HabitTracker(r.into()),
             PulseRecipe::File(r) => PrintTask::File(r),
         }
@@ -51,6 +66,11 @@ pub const APPLICATION_STORAGE_DIR: &str = ".local/share/konan";
 pub const CORE_MEMBERS: [&str; 3] = ["rongta", "blueprint", "cli_shared"];
 
 pub fn init_logging(package: &str) {
+    init_logging_with(package, &[]);
+}
+
+/// Same as `init_logging` with extra `module=level` filters, e.g. `escpos=debug`
+pub fn init_logging_with(package: &str, extra_filters: &[&str]) {
     // Get global log level from env or use default
     let level = std::env::var("RUST_LOG").unwrap_or_else(|_| "info".to_string());
 
@@ -61,6 +81,9 @@ pub fn init_logging(package: &str) {
         .collect::<Vec<_>>()
         .join(",");
     filters.push_str(&format!(",{}={level}", package));
+    for filter in extra_filters {
+        filters.push_str(&format!(",{filter}"));
+    }
 
     let env = env_logger::Env::default()
         .filter_or("RUST_LOG", &filters)
@@ -0,0 +1,88 @@
+use crate::clap_enum::FileFormat;
+use serde_json::Value;
+
+/// What printable content looks like, for input that arrives without a file extension
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContentKind {
+    /// A TipTap/ProseMirror JSON document, `{"type": "doc", ...}`
+    TipTap,
+    Markdown,
+    Text,
+}
+
+impl ContentKind {
+    /// Classify `input`: a JSON document first, then markdown if any line starts with a
+    /// block marker or holds inline emphasis or a link, otherwise plain text
+    pub fn detect(input: &str) -> Self {
+        let trimmed = input.trim_start();
+        if trimmed.starts_with('{')
+            && serde_json::from_str::<Value>(trimmed)
+                .is_ok_and(|doc| doc.get("type").and_then(Value::as_str) == Some("doc"))
+        {
+            return ContentKind::TipTap;
+        }
+        if input.lines().any(has_markdown_marker) {
+            return ContentKind::Markdown;
+        }
+        ContentKind::Text
+    }
+
+    /// How the content prints, `None` for kinds without an interpreter
+    pub fn file_format(self) -> Option<FileFormat> {
+        match self {
+            ContentKind::TipTap => None,
+            ContentKind::Markdown => Some(FileFormat::Markdown),
+            ContentKind::Text => Some(FileFormat::Text),
+        }
+    }
+}
+
+impl From<&str> for ContentKind {
+    fn from(input: &str) -> Self {
+        ContentKind::detect(input)
+    }
+}
+
+const BLOCK_MARKERS: [&str; 7] = ["# ", "## ", "### ", "- ", "* ", "> ", "```"];
+const INLINE_MARKERS: [&str; 3] = ["**", "](", "`"];
+
+fn has_markdown_marker(line: &str) -> bool {
+    let line = line.trim_start();
+    let ordered_item = line.split_once(". ").is_some_and(|(number, _)| {
+        !number.is_empty() && number.chars().all(|c| c.is_ascii_digit())
+    });
+    ordered_item
+        || BLOCK_MARKERS.iter().any(|marker| line.starts_with(marker))
+        || INLINE_MARKERS.iter().any(|marker| line.contains(marker))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod detect {
+        use super::*;
+
+        #[test]
+        fn json_document_is_tiptap() {
+            let doc = r#"{"type": "doc", "content": [{"type": "paragraph"}]}"#;
+            assert_eq!(ContentKind::detect(doc), ContentKind::TipTap);
+        }
+
+        #[test]
+        fn heading_is_markdown() {
+            assert_eq!(ContentKind::detect("# Groceries"), ContentKind::Markdown);
+            assert_eq!(
+                ContentKind::detect("1. first\n2. second"),
+                ContentKind::Markdown
+            );
+        }
+
+        #[test]
+        fn paragraph_is_text() {
+            let paragraph = "Call the plumber about the sink.\nBring the receipt.";
+            assert_eq!(ContentKind::detect(paragraph), ContentKind::Text);
+            assert_eq!(ContentKind::from("{not json"), ContentKind::Text);
+        }
+    }
+}
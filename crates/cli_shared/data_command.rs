@@ -0,0 +1,12 @@
+use clap::Parser;
+use std::path::PathBuf;
+
+#[derive(Debug, Parser)]
+pub struct DataArgs {
+    #[clap(
+        help = "A .json array of flat objects or a .csv file with a header row",
+        long_help = "A .json array of flat objects or a .csv file with a header row. Columns \
+                     are shrunk to fit the paper width, longest first"
+    )]
+    pub path: PathBuf,
+}
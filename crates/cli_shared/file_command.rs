@@ -1,15 +1,301 @@
-use crate::clap_enum::AllowedCommand;
+use crate::{
+    clap_enum::{AllowedCommand, FileFormat},
+    content_kind::ContentKind,
+};
+use anyhow::{Context, Result};
 use clap::Parser;
-use std::path::PathBuf;
+use std::{
+    fs::OpenOptions,
+    io::{BufRead, Write},
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// The `path` that reads the file from stdin instead
+pub const STDIN_PATH: &str = "-";
+
+/// The header of piped content when `--title` is given without a value
+pub const STDIN_TITLE: &str = "Standard input";
 
 #[derive(Debug, Parser)]
 pub struct FileArgs {
-    #[clap(help = "The file path")]
+    #[clap(help = "The file path, or - to read from stdin")]
     pub path: PathBuf,
+    #[clap(
+        long,
+        help = "Format of content read from stdin, detected from the content when not given"
+    )]
+    pub format: Option<FileFormat>,
     #[clap(long, help = "Number of rows per page (cuts after each page)")]
     pub rows: Option<u32>,
     #[clap(long, help = "A cli command whose output is piped to file")]
     pub prehook_command: Option<AllowedCommand>,
     #[clap(long, help = "Dynamic cli command arg")]
     pub prehook_command_args: Option<String>,
+    #[clap(long, help = "Print the raw file with line numbers in the margin")]
+    pub line_numbers: bool,
+    #[clap(long, help = "Cut at each horizontal rule of a markdown file")]
+    pub split_on_rule: bool,
+    #[clap(
+        long,
+        help = "Save paper with a smaller font, tighter lines and single blank lines",
+        long_help = "Save paper with Font B, tight line spacing and at most one blank line in a \
+                     row. Applies to markdown and line numbered files, plain text prints as is."
+    )]
+    pub compact: bool,
+    #[clap(
+        long,
+        num_args = 0..=1,
+        value_name = "TITLE",
+        help = "Head the printout with a title, the file name unless one is given"
+    )]
+    pub title: Option<Option<String>>,
+    #[clap(
+        long,
+        help = "Report characters the printer can't print instead of printing",
+        long_help = "Render the file as it would print and report the line and column of every \
+                     character the printer's code page can't show, without printing anything"
+    )]
+    pub dry_run: bool,
+}
+
+impl FileArgs {
+    /// Whether the content is piped in rather than read from `path`
+    pub fn is_stdin(&self) -> bool {
+        self.path == Path::new(STDIN_PATH)
+    }
+
+    /// Spool piped content into `dir` and point `path` at the spooled file. `--title` on its
+    /// own heads it with `STDIN_TITLE` rather than the spooled file's name.
+    pub fn take_stdin(&mut self, reader: impl BufRead, dir: &Path) -> Result<()> {
+        self.title = self
+            .title
+            .take()
+            .map(|title| Some(title.unwrap_or_else(|| STDIN_TITLE.to_string())));
+        self.path = spool_stdin(reader, self.format, dir)?;
+        Ok(())
+    }
+
+    /// The header title, `--title` on its own falls back to the file's base name
+    pub fn title(&self) -> Option<String> {
+        self.title.as_ref().map(|title| {
+            title.clone().unwrap_or_else(|| {
+                self.path.file_name().map_or_else(
+                    || self.path.display().to_string(),
+                    |name| name.to_string_lossy().into_owned(),
+                )
+            })
+        })
+    }
+}
+
+#[derive(Debug, Parser)]
+pub struct FileBatchArgs {
+    #[clap(help = "Directory of files to print")]
+    pub dir: PathBuf,
+    #[clap(
+        long,
+        help = "Only print files of this type (defaults to markdown and text)"
+    )]
+    pub format: Option<FileFormat>,
+    #[clap(long, help = "Head each printout with its file name")]
+    pub titles: bool,
+}
+
+/// Read piped content line by line into a new `stdin-*.md` or `stdin-*.txt` file in `dir`,
+/// so it prints like any other file. The name is unique to the process and time, so
+/// concurrent runs don't overwrite each other. The format comes from `format`, or the
+/// content when not given.
+pub fn spool_stdin(
+    reader: impl BufRead,
+    format: Option<FileFormat>,
+    dir: &Path,
+) -> Result<PathBuf> {
+    let mut content = String::new();
+    for line in reader.lines() {
+        content.push_str(&line.context("Failed to read from stdin")?);
+        content.push('\n');
+    }
+    let format = match format {
+        Some(format) => format,
+        None => ContentKind::detect(&content)
+            .file_format()
+            .context("Content from stdin isn't markdown or text, pass --format")?,
+    };
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos();
+    let path = dir.join(format!(
+        "stdin-{}-{nanos}.{}",
+        std::process::id(),
+        format.extension()
+    ));
+    OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(&path)
+        .and_then(|mut file| file.write_all(content.as_bytes()))
+        .with_context(|| format!("Failed to write stdin to '{}'", path.display()))?;
+    Ok(path)
+}
+
+/// The printable files directly inside `dir` sorted by name, limited to `format` when given.
+/// Entries that can't be read are logged and skipped.
+pub fn batch_files(dir: &Path, format: Option<FileFormat>) -> Result<Vec<PathBuf>> {
+    let entries = std::fs::read_dir(dir)
+        .with_context(|| format!("Failed to read directory '{}'", dir.display()))?;
+    let mut files: Vec<PathBuf> = entries
+        .filter_map(|entry| {
+            entry
+                .inspect_err(|e| {
+                    log::warn!("Skipping unreadable entry in '{}': {e}", dir.display())
+                })
+                .ok()
+        })
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file())
+        .filter(|path| match FileFormat::from_path(path) {
+            Some(file_format) => format.is_none_or(|format| format == file_format),
+            None => false,
+        })
+        .collect();
+    files.sort();
+    Ok(files)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod title {
+        use super::*;
+
+        fn parse(args: &[&str]) -> FileArgs {
+            FileArgs::parse_from(["file", "notes/todo.md"].iter().chain(args))
+        }
+
+        #[test]
+        fn defaults_to_the_file_name() {
+            assert_eq!(parse(&["--title"]).title(), Some("todo.md".to_string()));
+        }
+
+        #[test]
+        fn given_title_wins() {
+            let args = parse(&["--title", "Groceries"]);
+            assert_eq!(args.title(), Some("Groceries".to_string()));
+        }
+
+        #[test]
+        fn no_title_without_the_flag() {
+            assert_eq!(parse(&[]).title(), None);
+        }
+    }
+
+    mod spool_stdin {
+        use super::*;
+        use std::io::Cursor;
+
+        fn temp_dir(test: &str) -> PathBuf {
+            let dir = std::env::temp_dir()
+                .join(format!("konan-spool-stdin-{test}-{}", std::process::id()));
+            let _ = std::fs::remove_dir_all(&dir);
+            std::fs::create_dir_all(&dir).unwrap();
+            dir
+        }
+
+        #[test]
+        fn reads_content_in_the_given_format() {
+            let dir = temp_dir("format");
+            let stdin = Cursor::new("# Notes\r\nfirst line");
+            let path = spool_stdin(stdin, Some(FileFormat::Text), &dir).unwrap();
+            assert_eq!(path.parent(), Some(dir.as_path()));
+            assert_eq!(FileFormat::from_path(&path), Some(FileFormat::Text));
+            let content = std::fs::read_to_string(path).unwrap();
+            assert_eq!(content, "# Notes\nfirst line\n");
+        }
+
+        #[test]
+        fn detects_the_format_without_one() {
+            let dir = temp_dir("detect");
+            let path = spool_stdin(Cursor::new("# Notes"), None, &dir).unwrap();
+            assert_eq!(FileFormat::from_path(&path), Some(FileFormat::Markdown));
+        }
+
+        #[test]
+        fn each_run_gets_its_own_file() {
+            let dir = temp_dir("unique");
+            let first = spool_stdin(Cursor::new("first"), Some(FileFormat::Text), &dir).unwrap();
+            let second = spool_stdin(Cursor::new("second"), Some(FileFormat::Text), &dir).unwrap();
+            assert_ne!(first, second);
+            assert_eq!(std::fs::read_to_string(first).unwrap(), "first\n");
+            assert_eq!(std::fs::read_to_string(second).unwrap(), "second\n");
+        }
+
+        #[test]
+        fn bare_title_names_the_input_not_the_spooled_file() {
+            let dir = temp_dir("title");
+            let mut args = FileArgs::parse_from(["file", "-", "--format", "text", "--title"]);
+            args.take_stdin(Cursor::new("hello"), &dir).unwrap();
+            assert_eq!(args.title(), Some(STDIN_TITLE.to_string()));
+            assert_eq!(args.path.parent(), Some(dir.as_path()));
+        }
+
+        #[test]
+        fn dash_reads_stdin() {
+            let args = FileArgs::parse_from(["file", "-", "--format", "markdown"]);
+            assert!(args.is_stdin());
+            assert_eq!(args.format, Some(FileFormat::Markdown));
+        }
+    }
+
+    mod batch_files {
+        use super::*;
+
+        fn temp_dir(test: &str) -> PathBuf {
+            let dir = std::env::temp_dir().join(format!("konan-batch-files-{test}"));
+            let _ = std::fs::remove_dir_all(&dir);
+            std::fs::create_dir_all(&dir).unwrap();
+            dir
+        }
+
+        fn names(files: &[PathBuf]) -> Vec<String> {
+            files
+                .iter()
+                .map(|path| path.file_name().unwrap().to_string_lossy().into_owned())
+                .collect()
+        }
+
+        #[test]
+        fn lists_printable_files_in_sorted_order() {
+            let dir = temp_dir("sorted");
+            for name in ["c.md", "a.txt", "b.md", "image.png"] {
+                std::fs::write(dir.join(name), "content").unwrap();
+            }
+            std::fs::create_dir(dir.join("nested.md")).unwrap();
+            let files = batch_files(&dir, None).unwrap();
+            assert_eq!(names(&files), ["a.txt", "b.md", "c.md"]);
+        }
+
+        #[test]
+        fn filters_by_format() {
+            let dir = temp_dir("format");
+            for name in ["a.txt", "b.md"] {
+                std::fs::write(dir.join(name), "content").unwrap();
+            }
+            let files = batch_files(&dir, Some(FileFormat::Markdown)).unwrap();
+            assert_eq!(names(&files), ["b.md"]);
+        }
+
+        #[test]
+        fn empty_directory_has_no_files() {
+            assert!(batch_files(&temp_dir("empty"), None).unwrap().is_empty());
+        }
+
+        #[test]
+        fn missing_directory_is_an_error() {
+            let dir = temp_dir("missing").join("absent");
+            assert!(batch_files(&dir, None).is_err());
+        }
+    }
 }
@@ -0,0 +1,15 @@
+use clap::Parser;
+
+#[derive(Debug, Parser)]
+pub struct QrArgs {
+    #[clap(help = "Text or URL to encode")]
+    pub data: String,
+    #[clap(
+        long,
+        help = "Dots per QR module, 1 to 15",
+        value_parser = clap::value_parser!(u8).range(1..=15)
+    )]
+    pub size: Option<u8>,
+    #[clap(long, help = "Text printed centered beneath the code")]
+    pub caption: Option<String>,
+}
@@ -0,0 +1,139 @@
+use crate::template_command::TemplateCommand;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+
+/// Relative path (from home directory) to the saved template store
+pub const SAVED_TEMPLATES_PATH: &str = ".config/konan/templates.json";
+
+pub fn saved_templates_path() -> Result<PathBuf> {
+    Ok(std::env::home_dir()
+        .context("Could not determine home directory")?
+        .join(SAVED_TEMPLATES_PATH))
+}
+
+/// Template configurations saved under a name so they can be printed again later
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct TemplateStore {
+    templates: BTreeMap<String, TemplateCommand>,
+}
+
+impl TemplateStore {
+    /// Read the store at `path`. A missing file is an empty store.
+    pub fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let raw = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read saved templates '{}'", path.display()))?;
+        serde_json::from_str(&raw)
+            .with_context(|| format!("Failed to parse saved templates '{}'", path.display()))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create directory '{}'", parent.display()))?;
+        }
+        let raw = serde_json::to_string_pretty(self).context("Failed to serialize templates")?;
+        std::fs::write(path, raw)
+            .with_context(|| format!("Failed to write saved templates '{}'", path.display()))
+    }
+
+    /// Save `template` as `name`, returning the template it replaced
+    pub fn insert(&mut self, name: &str, template: TemplateCommand) -> Option<TemplateCommand> {
+        self.templates.insert(name.to_string(), template)
+    }
+
+    pub fn get(&self, name: &str) -> Result<&TemplateCommand> {
+        self.templates
+            .get(name)
+            .with_context(|| format!("No saved template named '{name}'"))
+    }
+
+    pub fn remove(&mut self, name: &str) -> Result<TemplateCommand> {
+        self.templates
+            .remove(name)
+            .with_context(|| format!("No saved template named '{name}'"))
+    }
+
+    /// Saved templates ordered by name
+    pub fn iter(&self) -> impl Iterator<Item = (&String, &TemplateCommand)> {
+        self.templates.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clap_enum::{DateBanner, TimePeriod};
+
+    fn store_path(test: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("konan-saved-templates-{test}"));
+        let _ = std::fs::remove_dir_all(&dir);
+        dir.join("templates.json")
+    }
+
+    fn daily() -> TemplateCommand {
+        TemplateCommand::Box {
+            height: Some(20),
+            date: Some(DateBanner::Today),
+            banner: None,
+            lined: true,
+            checkboxes: false,
+            columns: vec!["Time".to_string(), "Task".to_string()],
+            ledger: Vec::new(),
+        }
+    }
+
+    mod load {
+        use super::*;
+
+        #[test]
+        fn round_trips_saved_templates() {
+            let path = store_path("round-trip");
+            let mut store = TemplateStore::default();
+            store.insert("daily", daily());
+            store.insert(
+                "reading",
+                TemplateCommand::HabitTracker {
+                    habit: "Read".to_string(),
+                    start_date: Some("mon".to_string()),
+                    time_period: Some(TimePeriod::Month),
+                    mark_weekends: true,
+                },
+            );
+            store.save(&path).unwrap();
+
+            let loaded = TemplateStore::load(&path).unwrap();
+            assert_eq!(loaded, store);
+            assert_eq!(loaded.get("daily").unwrap(), &daily());
+            let names: Vec<&String> = loaded.iter().map(|(name, _)| name).collect();
+            assert_eq!(names, ["daily", "reading"]);
+        }
+
+        #[test]
+        fn missing_file_is_an_empty_store() {
+            let store = TemplateStore::load(&store_path("missing")).unwrap();
+            assert_eq!(store.iter().count(), 0);
+        }
+    }
+
+    mod remove {
+        use super::*;
+
+        #[test]
+        fn reports_unknown_names() {
+            let mut store = TemplateStore::default();
+            store.insert("daily", daily());
+            assert_eq!(store.remove("daily").unwrap(), daily());
+            assert_eq!(
+                store.remove("daily").unwrap_err().to_string(),
+                "No saved template named 'daily'"
+            );
+        }
+    }
+}
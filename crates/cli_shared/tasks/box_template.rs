@@ -1,3 +1,4 @@
+use super::PrinterName;
 use crate::clap_enum::DateBanner;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
@@ -6,20 +7,41 @@ use serde::{Deserialize, Serialize};
 pub struct BoxTemplate {
     #[serde(default = "super::default_true")]
     pub cut: bool,
-    pub rows: Option<u32>,
+    #[serde(
+        default = "super::default_cpl",
+        deserialize_with = "super::cpl_in_range"
+    )]
+    pub cpl: u8,
+    /// Box height in rows, not page size
+    #[serde(alias = "rows")]
+    pub height: Option<u32>,
     pub lined: bool,
+    #[serde(default)]
+    pub checkboxes: bool,
     pub banner: Option<String>,
     pub date: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub columns: Vec<String>,
+    /// Labels of ledger columns ruled down the box, see `columns` for a header row only
+    #[serde(default)]
+    pub ledger: Vec<String>,
+    #[serde(default)]
+    pub printer: PrinterName,
 }
 
 impl From<BoxTemplatePulseRecipe> for BoxTemplate {
     fn from(value: BoxTemplatePulseRecipe) -> Self {
         Self {
             cut: value.cut,
-            rows: value.rows,
+            cpl: value.cpl,
+            height: value.height,
             lined: value.lined,
+            checkboxes: value.checkboxes,
             banner: value.banner,
             date: value.date.map(|v| v.into()),
+            columns: value.columns,
+            ledger: value.ledger,
+            printer: None,
         }
     }
 }
@@ -27,8 +49,22 @@ impl From<BoxTemplatePulseRecipe> for BoxTemplate {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BoxTemplatePulseRecipe {
     pub cut: bool,
-    pub rows: Option<u32>,
+    #[serde(
+        default = "super::default_cpl",
+        deserialize_with = "super::cpl_in_range"
+    )]
+    pub cpl: u8,
+    /// Box height in rows, not page size
+    #[serde(alias = "rows")]
+    pub height: Option<u32>,
     pub lined: bool,
+    #[serde(default)]
+    pub checkboxes: bool,
     pub banner: Option<String>,
     pub date: Option<DateBanner>,
+    #[serde(default)]
+    pub columns: Vec<String>,
+    /// Labels of ledger columns ruled down the box, see `columns` for a header row only
+    #[serde(default)]
+    pub ledger: Vec<String>,
 }
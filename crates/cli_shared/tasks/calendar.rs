@@ -0,0 +1,52 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CalendarTemplate {
+    #[serde(default = "super::default_true")]
+    pub cut: bool,
+    #[serde(
+        default = "super::default_cpl",
+        deserialize_with = "super::cpl_in_range"
+    )]
+    pub cpl: u8,
+    /// A day of the month to print, or the first of the 7 days with `week`
+    pub date: DateTime<Utc>,
+    #[serde(default)]
+    pub week: bool,
+    #[serde(default)]
+    pub lined: bool,
+    /// Weeks of the month printed before each cut, all of them when unset
+    #[serde(default)]
+    pub weeks_per_page: Option<u32>,
+}
+
+impl From<CalendarPulseRecipe> for CalendarTemplate {
+    fn from(value: CalendarPulseRecipe) -> Self {
+        Self {
+            cut: value.cut,
+            cpl: value.cpl,
+            date: Utc::now(),
+            week: value.week,
+            lined: value.lined,
+            weeks_per_page: value.weeks_per_page,
+        }
+    }
+}
+
+/// A calendar of the month, or week, the pulse fires in
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CalendarPulseRecipe {
+    pub cut: bool,
+    #[serde(
+        default = "super::default_cpl",
+        deserialize_with = "super::cpl_in_range"
+    )]
+    pub cpl: u8,
+    #[serde(default)]
+    pub week: bool,
+    #[serde(default)]
+    pub lined: bool,
+    #[serde(default)]
+    pub weeks_per_page: Option<u32>,
+}
@@ -0,0 +1,24 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChecklistTemplate {
+    #[serde(default = "super::default_true")]
+    pub cut: bool,
+    #[serde(
+        default = "super::default_cpl",
+        deserialize_with = "super::cpl_in_range"
+    )]
+    pub cpl: u8,
+    /// Items as `name` or `name:quantity`
+    #[serde(default)]
+    pub items: Vec<String>,
+    /// Boxed above the items
+    #[serde(default)]
+    pub title: Option<String>,
+    /// Number the rows instead of giving them checkboxes
+    #[serde(default)]
+    pub numbered: bool,
+    /// Empty rows after the items
+    #[serde(default)]
+    pub blank_lines: u32,
+}
@@ -0,0 +1,14 @@
+use serde::{Deserialize, Serialize};
+
+/// A JSON or CSV file in the printer files directory, printed as a table
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KonanData {
+    #[serde(default = "super::default_true")]
+    pub cut: bool,
+    #[serde(
+        default = "super::default_cpl",
+        deserialize_with = "super::cpl_in_range"
+    )]
+    pub cpl: u8,
+    pub name: String,
+}
@@ -4,8 +4,25 @@ use serde::{Deserialize, Serialize};
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct KonanFile {
     pub cut: bool,
+    #[serde(
+        default = "super::default_cpl",
+        deserialize_with = "super::cpl_in_range"
+    )]
+    pub cpl: u8,
     pub name: String,
     pub prehook_command: Option<AllowedCommand>,
     pub prehook_command_arg: Option<String>,
     pub rows: Option<u32>,
+    /// Print the raw source with a line number gutter, whatever the file type
+    #[serde(default)]
+    pub line_numbers: bool,
+    /// Cut at each horizontal rule of a markdown file
+    #[serde(default)]
+    pub split_on_rule: bool,
+    /// Print with `RongtaPrinter::compact_preset`
+    #[serde(default)]
+    pub compact: bool,
+    /// Printed as a header above the content, see `blueprint::add_title_header`
+    #[serde(default)]
+    pub title: Option<String>,
 }
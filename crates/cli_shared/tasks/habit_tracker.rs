@@ -1,23 +1,53 @@
+use super::PrinterName;
 use crate::clap_enum::TimePeriod;
 use chrono::{DateTime, Utc};
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
+
+/// Accept a single habit as a string or several as an array of strings
+fn one_or_many<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<String>, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum OneOrMany {
+        One(String),
+        Many(Vec<String>),
+    }
+    Ok(match OneOrMany::deserialize(deserializer)? {
+        OneOrMany::One(habit) => vec![habit],
+        OneOrMany::Many(habits) => habits,
+    })
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct HabitTrackerTemplate {
     #[serde(default = "super::default_true")]
     pub cut: bool,
-    pub habit: String,
+    #[serde(
+        default = "super::default_cpl",
+        deserialize_with = "super::cpl_in_range"
+    )]
+    pub cpl: u8,
+    /// Each habit is printed as its own section, `"habit": "Read"` or
+    /// `"habit": ["Read", "Stretch"]`
+    #[serde(rename = "habit", alias = "habits", deserialize_with = "one_or_many")]
+    pub habits: Vec<String>,
     pub start_date: DateTime<Utc>,
     pub end_date: DateTime<Utc>,
+    #[serde(default)]
+    pub mark_weekends: bool,
+    #[serde(default)]
+    pub printer: PrinterName,
 }
 
 impl From<HabitTrackerPulseRecipe> for HabitTrackerTemplate {
     fn from(value: HabitTrackerPulseRecipe) -> Self {
         Self {
             cut: value.cut,
-            habit: value.habit,
+            cpl: value.cpl,
+            habits: vec![value.habit],
             start_date: Utc::now(),
             end_date: value.time_period.into(),
+            mark_weekends: value.mark_weekends,
+            printer: None,
         }
     }
 }
@@ -25,6 +55,52 @@ impl From<HabitTrackerPulseRecipe> for HabitTrackerTemplate {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct HabitTrackerPulseRecipe {
     pub cut: bool,
+    #[serde(
+        default = "super::default_cpl",
+        deserialize_with = "super::cpl_in_range"
+    )]
+    pub cpl: u8,
     pub habit: String,
     pub time_period: TimePeriod,
+    #[serde(default)]
+    pub mark_weekends: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod habit_tracker_template {
+        use super::*;
+
+        fn habits(payload: &str) -> Vec<String> {
+            serde_json::from_str::<HabitTrackerTemplate>(payload)
+                .unwrap()
+                .habits
+        }
+
+        #[test]
+        fn habit_may_be_a_string_or_an_array() {
+            let dates =
+                r#""start_date": "2026-10-01T00:00:00Z", "end_date": "2026-10-14T00:00:00Z""#;
+            assert_eq!(
+                habits(&format!(r#"{{"habit": "Read", {dates}}}"#)),
+                ["Read"]
+            );
+            assert_eq!(
+                habits(&format!(r#"{{"habit": ["Read", "Stretch"], {dates}}}"#)),
+                ["Read", "Stretch"]
+            );
+            assert_eq!(
+                habits(&format!(r#"{{"habits": ["Read"], {dates}}}"#)),
+                ["Read"]
+            );
+            assert!(
+                serde_json::from_str::<HabitTrackerTemplate>(&format!(
+                    r#"{{"habit": 3, {dates}}}"#
+                ))
+                .is_err()
+            );
+        }
+    }
 }
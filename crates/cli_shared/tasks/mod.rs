@@ -1,20 +1,89 @@
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
 
 mod box_template;
 pub use box_template::{BoxTemplate, BoxTemplatePulseRecipe};
+mod calendar;
+pub use calendar::{CalendarPulseRecipe, CalendarTemplate};
+mod checklist;
+pub use checklist::ChecklistTemplate;
+mod data;
+pub use data::KonanData;
 mod file;
 pub use file::KonanFile;
 mod habit_tracker;
 pub use habit_tracker::{HabitTrackerPulseRecipe, HabitTrackerTemplate};
+mod qr_code;
+pub use qr_code::QrCode;
+mod timestamp;
+pub use timestamp::{DEFAULT_TIMESTAMP_FORMAT, Timestamp};
 
 pub(crate) fn default_true() -> bool {
     true
 }
 
+/// Characters per line used when a task doesn't specify one. Matches `rongta::CPL`.
+pub const DEFAULT_CPL: u8 = 48;
+
+/// Narrowest `--width`, below it templates and wrapped text no longer fit
+pub const MIN_CPL: u8 = 16;
+/// Widest `--width`, Font A on 112mm paper
+pub const MAX_CPL: u8 = 72;
+
+pub(crate) fn default_cpl() -> u8 {
+    DEFAULT_CPL
+}
+
+/// Reject a task's `cpl` outside `MIN_CPL..=MAX_CPL`, the range `--width` accepts
+pub(crate) fn cpl_in_range<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u8, D::Error> {
+    let cpl = u8::deserialize(deserializer)?;
+    if !(MIN_CPL..=MAX_CPL).contains(&cpl) {
+        return Err(serde::de::Error::custom(format!(
+            "cpl {cpl} is outside {MIN_CPL} to {MAX_CPL}"
+        )));
+    }
+    Ok(cpl)
+}
+
+/// Name of a printer from the pi's `printers` config, the default printer when unset.
+/// Only MQTT routing reads it, to pick the driver a message prints on. Every other path
+/// ignores it, including text printed from the CLI and jobs replayed from the spool, which
+/// print on the default printer.
+pub type PrinterName = Option<String>;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DirectPrintOut {
     #[serde(default = "default_true")]
     pub cut: bool,
+    #[serde(default = "default_cpl", deserialize_with = "cpl_in_range")]
+    pub cpl: u8,
     pub content: String,
     pub rows: Option<u32>,
+    #[serde(default)]
+    pub printer: PrinterName,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod cpl_in_range {
+        use super::*;
+
+        fn cpl(payload: &str) -> Result<u8, serde_json::Error> {
+            serde_json::from_str::<DirectPrintOut>(payload).map(|task| task.cpl)
+        }
+
+        #[test]
+        fn accepts_the_width_range_and_defaults_when_unset() {
+            assert_eq!(cpl(r#"{"content": "hi"}"#).unwrap(), DEFAULT_CPL);
+            assert_eq!(cpl(r#"{"content": "hi", "cpl": 16}"#).unwrap(), MIN_CPL);
+            assert_eq!(cpl(r#"{"content": "hi", "cpl": 72}"#).unwrap(), MAX_CPL);
+        }
+
+        #[test]
+        fn rejects_a_cpl_outside_the_width_range() {
+            assert!(cpl(r#"{"content": "hi", "cpl": 0}"#).is_err());
+            assert!(cpl(r#"{"content": "hi", "cpl": 73}"#).is_err());
+        }
+    }
 }
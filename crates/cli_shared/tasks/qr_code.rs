@@ -0,0 +1,15 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QrCode {
+    #[serde(default = "super::default_true")]
+    pub cut: bool,
+    #[serde(
+        default = "super::default_cpl",
+        deserialize_with = "super::cpl_in_range"
+    )]
+    pub cpl: u8,
+    pub data: String,
+    pub size: Option<u8>,
+    pub caption: Option<String>,
+}
@@ -0,0 +1,21 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// strftime format used when a timestamp doesn't give one
+pub const DEFAULT_TIMESTAMP_FORMAT: &str = "%A %B %d, %Y  %H:%M";
+
+/// Print `time` large and centered, e.g. to date a physical document
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Timestamp {
+    #[serde(default = "super::default_true")]
+    pub cut: bool,
+    #[serde(
+        default = "super::default_cpl",
+        deserialize_with = "super::cpl_in_range"
+    )]
+    pub cpl: u8,
+    /// When the job was requested, so a spooled job still prints the original time
+    pub time: DateTime<Utc>,
+    /// strftime format, `DEFAULT_TIMESTAMP_FORMAT` when absent
+    pub format: Option<String>,
+}
@@ -1,22 +1,63 @@
 use crate::clap_enum::{DateBanner, TimePeriod};
 use clap::{Parser, Subcommand};
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Subcommand)]
+#[derive(Debug, Clone, PartialEq, Subcommand, Serialize, Deserialize)]
+#[serde(tag = "type")]
 pub enum TemplateCommand {
     #[clap(about = "Create a box with random borders")]
     Box {
         #[clap(
             long,
+            alias = "rows",
             help = "The height of the box in rows. 1in ~= 8rows.",
+            long_help = "The height of the box in rows. 1in ~= 8rows. Unlike the `rows` of \
+                         other commands this does not paginate the print.",
             default_value = "29"
         )]
-        rows: Option<u32>,
+        height: Option<u32>,
         #[clap(short, long, help = "Add a date to the top of the template")]
         date: Option<DateBanner>,
         #[clap(short, long, help = "Add a message to the top of the template")]
         banner: Option<String>,
         #[clap(short, long, help = "Print a lined piece of paper")]
         lined: bool,
+        #[clap(long, help = "Start each row with a checkbox")]
+        checkboxes: bool,
+        #[clap(
+            long,
+            value_delimiter = ',',
+            help = "Comma separated labels for a header row, e.g. \"Time,Task,Done\""
+        )]
+        columns: Vec<String>,
+        #[clap(
+            long,
+            value_delimiter = ',',
+            conflicts_with = "columns",
+            help = "Rule the box into columns with these labels, e.g. \"Date,Desc,Amount\"",
+            long_help = "Rule the box into a ledger: equal columns headed by these comma \
+                         separated labels, with a bar running down every row between them"
+        )]
+        #[serde(default)]
+        ledger: Vec<String>,
+    },
+    #[clap(about = "Create a checklist of items to tick off")]
+    Checklist {
+        #[clap(
+            long = "item",
+            required_unless_present = "blank_lines",
+            help = "An item as name or name:quantity, e.g. \"Eggs:12\". Repeat for more items"
+        )]
+        items: Vec<String>,
+        #[clap(long, help = "Print a title in a box above the items")]
+        #[serde(default)]
+        title: Option<String>,
+        #[clap(long, help = "Number the items instead of giving them checkboxes")]
+        #[serde(default)]
+        numbered: bool,
+        #[clap(long, help = "Empty rows to add after the items")]
+        #[serde(default)]
+        blank_lines: Option<u32>,
     },
     #[clap(about = "Create a habit tracker template")]
     HabitTracker {
@@ -25,7 +66,7 @@ pub enum TemplateCommand {
         #[clap(
             short,
             long,
-            help = "Start date in YYYY-MM-DD format (defaults to today)"
+            help = "Start date as YYYY-MM-DD, today, tomorrow, mon..sun or +Nd (defaults to today)"
         )]
         start_date: Option<String>,
         #[clap(
@@ -35,6 +76,30 @@ pub enum TemplateCommand {
             default_value = "two-week"
         )]
         time_period: Option<TimePeriod>,
+        #[clap(long, help = "Draw Saturday and Sunday as [ dd ] instead of ( dd )")]
+        mark_weekends: bool,
+    },
+    #[clap(about = "Create a month or week calendar")]
+    Calendar {
+        #[clap(
+            short,
+            long,
+            help = "A day of the month to print as YYYY-MM-DD, today, tomorrow, mon..sun or +Nd \
+                    (defaults to today)"
+        )]
+        date: Option<String>,
+        #[clap(long, help = "Print the 7 days from the date instead of its month")]
+        #[serde(default)]
+        week: bool,
+        #[clap(short, long, help = "Rule the writing rows of each day")]
+        #[serde(default)]
+        lined: bool,
+        #[clap(
+            long,
+            help = "Cut after this many weeks of the month, repeating the weekday header"
+        )]
+        #[serde(default)]
+        weeks_per_page: Option<u32>,
     },
 }
 
@@ -5,14 +5,25 @@ use lambda_http::{
     http::{Response, StatusCode},
     run, service_fn,
 };
-use lambda_shared::{IotConfigEnv, Message, create_iot_client};
+use lambda_shared::{IotConfigEnv, JobId, Message, PrinterName, create_iot_client};
 use serde::{Deserialize, Serialize};
 
+/// A single habit or several, each printed as its own section by the pi
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(untagged)]
+enum Habits {
+    One(String),
+    Many(Vec<String>),
+}
+
 #[derive(Debug, Deserialize, Serialize)]
 struct HabitTrackerTemplate {
-    habit: String,
+    habit: Habits,
     start_date: DateTime<Utc>,
     end_date: DateTime<Utc>,
+    printer: PrinterName,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    job: JobId,
 }
 
 async fn handler(event: Request) -> Result<impl IntoResponse, Error> {
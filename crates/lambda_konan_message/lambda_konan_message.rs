@@ -4,13 +4,16 @@ use lambda_http::{
     http::{Response, StatusCode},
     run, service_fn,
 };
-use lambda_shared::{IotConfigEnv, Message, create_iot_client};
+use lambda_shared::{IotConfigEnv, JobId, Message, PrinterName, create_iot_client};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Deserialize, Serialize)]
 struct PrintableMessage {
     content: String,
     rows: Option<u32>,
+    printer: PrinterName,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    job: JobId,
 }
 
 async fn handler(event: Request) -> Result<impl IntoResponse, Error> {
@@ -39,3 +42,24 @@ async fn main() -> Result<(), Error> {
     lambda_shared::initialize_tracing();
     run(service_fn(handler)).await
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    /// Every lambda's request carries `job` the same way, this one stands in for them all
+    #[test]
+    fn job_is_forwarded_only_when_set() {
+        let forwarded = |job: Option<&str>| {
+            let mut payload = json!({"content": "hi", "rows": null, "printer": null});
+            if let Some(job) = job {
+                payload["job"] = json!(job);
+            }
+            let body: PrintableMessage = serde_json::from_value(payload).unwrap();
+            serde_json::to_value(&body).unwrap()
+        };
+        assert_eq!(forwarded(Some("abc"))["job"], "abc");
+        assert!(forwarded(None).get("job").is_none());
+    }
+}
@@ -5,7 +5,7 @@ use lambda_http::{
     http::{Response, StatusCode},
     run, service_fn,
 };
-use lambda_shared::{IotConfigEnv, Message, create_iot_client};
+use lambda_shared::{IotConfigEnv, JobId, Message, PrinterName, create_iot_client};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -14,6 +14,9 @@ struct OutlineTemplate {
     date: Option<DateTime<Utc>>,
     banner: Option<String>,
     lined: Option<bool>,
+    printer: PrinterName,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    job: JobId,
 }
 
 async fn handler(event: Request) -> Result<impl IntoResponse, Error> {
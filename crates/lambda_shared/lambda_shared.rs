@@ -2,6 +2,13 @@ use aws_config::Region;
 use aws_sdk_iotdataplane::Client;
 use serde::Serialize;
 
+/// Printer a request is routed to on the pi, forwarded untouched. Mirrors
+/// `cli_shared::tasks::PrinterName`, which documents what reads it.
+pub type PrinterName = Option<String>;
+
+/// Id the client gave its request, forwarded so the pi echoes it back in the print status
+pub type JobId = Option<String>;
+
 #[derive(Debug, Serialize)]
 pub struct Message {
     pub message: String,
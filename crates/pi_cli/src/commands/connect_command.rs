@@ -1,19 +1,30 @@
-use crate::{config::KonanIotConfig, print_ops::enqueue_print};
-use anyhow::bail;
+use crate::{
+    config::{Config, KonanIotConfig, PrinterTarget},
+    print_ops::try_enqueue_print_to,
+    spool::Spooled,
+};
+use anyhow::{Context, bail};
 use chrono::{Local, NaiveTime};
 use cli_shared::{
     PrintTask,
     tasks::{BoxTemplate, DirectPrintOut, HabitTrackerTemplate},
 };
+use rongta::{PrintReport, SupportedDriver};
 use rumqttc::{AsyncClient, ConnectionError, MqttOptions, QoS, TlsConfiguration, Transport};
 use rustls::pki_types::{CertificateDer, PrivateKeyDer, PrivatePkcs1KeyDer, PrivatePkcs8KeyDer};
+use serde::Serialize;
 use std::{
+    collections::{HashMap, HashSet},
     fs::File,
+    hash::{DefaultHasher, Hash, Hasher},
     io::BufReader,
     path::{Path, PathBuf},
-    sync::Arc,
+    sync::{Arc, Mutex},
+};
+use tokio::{
+    sync::oneshot,
+    time::{Duration, Instant},
 };
-use tokio::time::Duration;
 
 enum MqttTopic {
     Habits,
@@ -28,6 +39,14 @@ impl MqttTopic {
             MqttTopic::Outline => "command/konan_pi/outline",
         }
     }
+    /// Where the outcome of each print requested on this topic is published
+    fn status_topic(&self) -> &'static str {
+        match self {
+            MqttTopic::Habits => "status/konan_pi/habits",
+            MqttTopic::Message => "status/konan_pi/message",
+            MqttTopic::Outline => "status/konan_pi/outline",
+        }
+    }
     async fn subscribe_client(&self, client: &AsyncClient) -> anyhow::Result<()> {
         let topic = self.as_topic();
         client
@@ -45,11 +64,243 @@ impl TryFrom<String> for MqttTopic {
             "command/konan_pi/habits" => Ok(MqttTopic::Habits),
             "command/konan_pi/message" => Ok(MqttTopic::Message),
             "command/konan_pi/outline" => Ok(MqttTopic::Outline),
-            _ => Err(anyhow::anyhow!("Unsupported variation")),
+            _ => Err(anyhow::anyhow!("Unsupported topic '{value}'")),
+        }
+    }
+}
+
+/// Hashes of template payloads printed recently. MQTT delivers at least once, so a payload
+/// seen again within `window` is a redelivery rather than a request for another sheet.
+/// Payloads still in the queue count too, until their print settles.
+struct RecentPayloads {
+    window: Duration,
+    printed: HashMap<u64, Instant>,
+    pending: HashSet<u64>,
+}
+impl RecentPayloads {
+    fn new(window: Duration) -> Self {
+        Self {
+            window,
+            printed: HashMap::new(),
+            pending: HashSet::new(),
+        }
+    }
+
+    fn hash(topic: &str, payload: &[u8]) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        (topic, payload).hash(&mut hasher);
+        hasher.finish()
+    }
+
+    /// Whether the same `payload` on `topic` is queued, or printed less than `window`
+    /// before `now`
+    fn is_redelivery(&mut self, topic: &str, payload: &[u8], now: Instant) -> bool {
+        self.printed
+            .retain(|_, printed_at| now.duration_since(*printed_at) < self.window);
+        let hash = Self::hash(topic, payload);
+        self.printed.contains_key(&hash) || self.pending.contains(&hash)
+    }
+
+    /// Record `payload` on `topic` as queued, until `settle` is called with its outcome
+    fn queued(&mut self, topic: &str, payload: &[u8]) {
+        if !self.window.is_zero() {
+            self.pending.insert(Self::hash(topic, payload));
+        }
+    }
+
+    /// Settle a queued `payload` on `topic` with its print `result`. Only a payload that
+    /// printed, or was spooled to print once the printer is back, is skipped when it comes
+    /// again, one that failed prints when it is sent again.
+    fn settle(
+        &mut self,
+        topic: &str,
+        payload: &[u8],
+        result: &anyhow::Result<PrintReport>,
+        now: Instant,
+    ) {
+        let printed = match result {
+            Ok(_) => true,
+            Err(e) => e.downcast_ref::<Spooled>().is_some(),
+        };
+        let hash = Self::hash(topic, payload);
+        if self.pending.remove(&hash) && printed {
+            self.printed.insert(hash, now);
+        }
+    }
+}
+
+/// The error of a template payload skipped as a redelivery of one queued or printed
+/// within the dedup window, reported as skipped rather than failed
+#[derive(Debug)]
+struct Redelivery(Duration);
+impl std::fmt::Display for Redelivery {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Skipped a payload already queued or printed in the last {}s",
+            self.0.as_secs()
+        )
+    }
+}
+
+/// The printer a message names, `None` for the default printer. Names missing from the
+/// registry are an error rather than a silent fallback.
+fn resolve_printer(
+    printers: &HashMap<String, PrinterTarget>,
+    name: Option<&str>,
+) -> anyhow::Result<Option<SupportedDriver>> {
+    name.map(|name| {
+        printers
+            .get(name)
+            .cloned()
+            .map(SupportedDriver::from)
+            .with_context(|| format!("No printer named '{name}' in the printers config"))
+    })
+    .transpose()
+}
+
+/// The print task a message on `topic` asks for and the name of the printer it goes to,
+/// `None` for the default printer. Unknown topics and payloads that don't parse are errors.
+fn parse_message(topic: &str, payload: &[u8]) -> anyhow::Result<(PrintTask, Option<String>)> {
+    let parsed = match MqttTopic::try_from(topic.to_string())? {
+        MqttTopic::Habits => {
+            let params: HabitTrackerTemplate = parse_payload(topic, payload)?;
+            let printer = params.printer.clone();
+            (PrintTask::HabitTracker(params), printer)
+        }
+        MqttTopic::Message => {
+            let params: DirectPrintOut = parse_payload(topic, payload)?;
+            let printer = params.printer.clone();
+            (PrintTask::Markdown(params), printer)
+        }
+        MqttTopic::Outline => {
+            let params: BoxTemplate = parse_payload(topic, payload)?;
+            let printer = params.printer.clone();
+            (PrintTask::BoxTemplate(params), printer)
+        }
+    };
+    Ok(parsed)
+}
+
+fn parse_payload<T: serde::de::DeserializeOwned>(topic: &str, payload: &[u8]) -> anyhow::Result<T> {
+    serde_json::from_slice(payload).with_context(|| format!("Invalid payload on {topic}"))
+}
+
+/// Queue a message's task on the printer it names, returning the channel its outcome
+/// arrives on. A full queue turns the message away rather than blocking the event loop,
+/// which would miss keep-alives and lose the connection.
+fn route_print(
+    task: PrintTask,
+    printer: Option<&str>,
+) -> anyhow::Result<oneshot::Receiver<anyhow::Result<PrintReport>>> {
+    let printers = Config::get()
+        .map(|config| config.printers.clone())
+        .unwrap_or_default();
+    resolve_printer(&printers, printer).and_then(|route| try_enqueue_print_to(task, route))
+}
+
+/// Parse a message and queue it with `route`. Template payloads queued or printed within
+/// the dedup window are skipped with a `Redelivery` error. A payload only counts once it is
+/// queued, so one turned away by a full queue or an unknown printer prints when it is sent
+/// again. Settle queued payloads with `RecentPayloads::settle` once they print or fail.
+fn queue_message(
+    recent: &mut RecentPayloads,
+    topic: &str,
+    payload: &[u8],
+    now: Instant,
+    route: impl FnOnce(
+        PrintTask,
+        Option<&str>,
+    ) -> anyhow::Result<oneshot::Receiver<anyhow::Result<PrintReport>>>,
+) -> anyhow::Result<oneshot::Receiver<anyhow::Result<PrintReport>>> {
+    let (task, printer) = parse_message(topic, payload)?;
+    let template = matches!(task, PrintTask::HabitTracker(_) | PrintTask::BoxTemplate(_));
+    if template && recent.is_redelivery(topic, payload, now) {
+        return Err(anyhow::Error::msg(Redelivery(recent.window)));
+    }
+    let queued = route(task, printer.as_deref())?;
+    if template {
+        recent.queued(topic, payload);
+    }
+    Ok(queued)
+}
+
+/// Published after each print attempt so the client that sent the message can show
+/// whether it printed
+#[derive(Debug, Serialize)]
+struct PrintStatus {
+    /// The `job` field of the message, for the client to match the status to its request
+    job: Option<String>,
+    status: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+impl PrintStatus {
+    fn new(job: Option<String>, result: &anyhow::Result<PrintReport>) -> Self {
+        match result {
+            Ok(_) => Self {
+                job,
+                status: "printed",
+                error: None,
+            },
+            Err(e) if e.downcast_ref::<Redelivery>().is_some() => Self {
+                job,
+                status: "skipped",
+                error: Some(format!("{e:#}")),
+            },
+            // Spooled jobs print once the printer is back
+            Err(e) if e.downcast_ref::<Spooled>().is_some() => Self {
+                job,
+                status: "spooled",
+                error: Some(format!("{e:#}")),
+            },
+            Err(e) => Self {
+                job,
+                status: "failed",
+                error: Some(format!("{e:#}")),
+            },
         }
     }
 }
 
+/// The optional `job` id of a message payload, echoed back in its `PrintStatus`
+fn job_id(payload: &[u8]) -> Option<String> {
+    serde_json::from_slice::<serde_json::Value>(payload)
+        .ok()?
+        .get("job")?
+        .as_str()
+        .map(str::to_string)
+}
+
+trait StatusPublisher {
+    async fn publish_status(&self, topic: &str, payload: Vec<u8>) -> anyhow::Result<()>;
+}
+impl StatusPublisher for AsyncClient {
+    async fn publish_status(&self, topic: &str, payload: Vec<u8>) -> anyhow::Result<()> {
+        self.publish(topic, QoS::AtLeastOnce, false, payload)
+            .await
+            .with_context(|| format!("Failed to publish to {topic}"))
+    }
+}
+
+/// Publish how a print attempt went to `topic`. Failing to publish is logged, the print
+/// itself is already done.
+async fn acknowledge(
+    publisher: &impl StatusPublisher,
+    topic: &str,
+    job: Option<String>,
+    result: anyhow::Result<PrintReport>,
+) {
+    let status = PrintStatus::new(job, &result);
+    let published = match serde_json::to_vec(&status) {
+        Ok(payload) => publisher.publish_status(topic, payload).await,
+        Err(e) => Err(anyhow::Error::new(e).context("Failed to serialize the print status")),
+    };
+    if let Err(e) = published {
+        log::error!("Failed to acknowledge print: {e:#}");
+    }
+}
+
 const ACTIVE_WINDOW_START: u32 = 9;
 const ACTIVE_WINDOW_END: u32 = 22;
 
@@ -83,6 +334,10 @@ pub async fn handle_connect_command(config: KonanIotConfig) -> anyhow::Result<()
         config.root_trust_path,
     )?;
 
+    let recent = Arc::new(Mutex::new(RecentPayloads::new(Duration::from_secs(
+        config.dedup_window_secs,
+    ))));
+
     loop {
         if !is_within_active_window() {
             let wait = duration_until_window_start();
@@ -118,27 +373,46 @@ pub async fn handle_connect_command(config: KonanIotConfig) -> anyhow::Result<()
             match eventloop.poll().await {
                 Ok(notification) => {
                     if let rumqttc::Event::Incoming(rumqttc::Packet::Publish(msg)) = notification {
-                        if let Ok(topic) = MqttTopic::try_from(msg.topic) {
-                            match topic {
-                                MqttTopic::Habits => {
-                                    let params: HabitTrackerTemplate =
-                                        serde_json::from_slice(&msg.payload).unwrap();
-                                    enqueue_print(PrintTask::HabitTracker(params)).await;
-                                }
-                                MqttTopic::Message => {
-                                    let params: DirectPrintOut =
-                                        serde_json::from_slice(&msg.payload).unwrap();
-                                    enqueue_print(PrintTask::Markdown(params)).await;
-                                }
-                                MqttTopic::Outline => {
-                                    let params: BoxTemplate =
-                                        serde_json::from_slice(&msg.payload).unwrap();
-                                    enqueue_print(PrintTask::BoxTemplate(params)).await;
-                                }
+                        let queued = queue_message(
+                            &mut recent.lock().unwrap(),
+                            &msg.topic,
+                            &msg.payload,
+                            Instant::now(),
+                            route_print,
+                        );
+                        match &queued {
+                            Err(e) if e.downcast_ref::<Redelivery>().is_some() => {
+                                log::info!("{} on {}", e, msg.topic)
                             }
-                        } else {
-                            log::warn!("Called invalid topic")
+                            Err(e) => log::error!("Dropping message: {e:#}"),
+                            Ok(_) => {}
                         }
+                        // Unknown topics have nowhere to report to
+                        let Ok(topic) = MqttTopic::try_from(msg.topic.clone()) else {
+                            continue;
+                        };
+                        let job = job_id(&msg.payload);
+                        let client = client.clone();
+                        let recent = recent.clone();
+                        tokio::spawn(async move {
+                            let result = match queued {
+                                Ok(outcome) => {
+                                    let result = outcome
+                                        .await
+                                        .context("PRINT_QUEUE dropped the task before printing it")
+                                        .and_then(|result| result);
+                                    recent.lock().unwrap().settle(
+                                        &msg.topic,
+                                        &msg.payload,
+                                        &result,
+                                        Instant::now(),
+                                    );
+                                    result
+                                }
+                                Err(e) => Err(e),
+                            };
+                            acknowledge(&client, topic.status_topic(), job, result).await;
+                        });
                     }
                 }
                 Err(e) => {
@@ -318,3 +592,349 @@ fn is_fatal_error(error: &ConnectionError) -> bool {
         ConnectionError::MqttState(_) => true,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod parse_message {
+        use super::*;
+        use crate::print_ops::{print_task, tests::setup};
+        use rongta::printer::DumpDriver;
+
+        /// Print what `payload` on `topic` asks for, returning the bytes sent
+        fn printed(topic: &str, payload: &str) -> String {
+            let (task, _) = parse_message(topic, payload.as_bytes()).unwrap();
+            let driver = DumpDriver::new();
+            print_task(task, &setup(), SupportedDriver::Dump(driver.clone())).unwrap();
+            String::from_utf8_lossy(&driver.bytes()).into_owned()
+        }
+
+        #[test]
+        fn message_prints_markdown() {
+            let (task, _) =
+                parse_message(MqttTopic::Message.as_topic(), br#"{"content": "**eggs**"}"#)
+                    .unwrap();
+            assert!(matches!(task, PrintTask::Markdown(_)));
+            let bytes = printed(
+                MqttTopic::Message.as_topic(),
+                r##"{"content": "# Groceries\n\n- eggs", "cut": false}"##,
+            );
+            assert!(bytes.contains("Groceries"));
+            assert!(bytes.contains("eggs"));
+            // Markdown is rendered, not printed as source
+            assert!(!bytes.contains("# Groceries"));
+        }
+
+        #[test]
+        fn outline_prints_a_box() {
+            let (task, _) = parse_message(
+                MqttTopic::Outline.as_topic(),
+                br#"{"lined": false, "height": 2, "banner": "Chores"}"#,
+            )
+            .unwrap();
+            assert!(matches!(task, PrintTask::BoxTemplate(_)));
+            let bytes = printed(
+                MqttTopic::Outline.as_topic(),
+                r#"{"lined": false, "height": 2, "banner": "Chores"}"#,
+            );
+            assert!(bytes.contains("Chores"));
+        }
+
+        #[test]
+        fn habits_print_a_tracker() {
+            let payload = r#"{
+                "habit": "Stretch",
+                "start_date": "2026-10-01T12:00:00Z",
+                "end_date": "2026-10-07T12:00:00Z"
+            }"#;
+            let (task, _) =
+                parse_message(MqttTopic::Habits.as_topic(), payload.as_bytes()).unwrap();
+            assert!(matches!(task, PrintTask::HabitTracker(_)));
+            // Habits are printed in capitals
+            assert!(printed(MqttTopic::Habits.as_topic(), payload).contains("STRETCH"));
+        }
+
+        #[test]
+        fn habits_may_be_a_list() {
+            let payload = r#"{
+                "habit": ["Stretch", "Read"],
+                "start_date": "2026-10-01T12:00:00Z",
+                "end_date": "2026-10-07T12:00:00Z"
+            }"#;
+            let bytes = printed(MqttTopic::Habits.as_topic(), payload);
+            assert!(bytes.contains("STRETCH") && bytes.contains("READ"));
+        }
+
+        #[test]
+        fn printer_name_is_passed_on() {
+            let (_, printer) = parse_message(
+                MqttTopic::Message.as_topic(),
+                br#"{"content": "hi", "printer": "kitchen"}"#,
+            )
+            .unwrap();
+            assert_eq!(printer.as_deref(), Some("kitchen"));
+        }
+
+        #[test]
+        fn unknown_topic_is_rejected() {
+            let err = parse_message("command/konan_pi/fax", b"{}").unwrap_err();
+            assert_eq!(err.to_string(), "Unsupported topic 'command/konan_pi/fax'");
+        }
+
+        #[test]
+        fn invalid_payload_is_an_error() {
+            let err = parse_message(MqttTopic::Outline.as_topic(), b"{\"lined\": 3}").unwrap_err();
+            assert_eq!(
+                err.to_string(),
+                "Invalid payload on command/konan_pi/outline"
+            );
+        }
+    }
+
+    mod acknowledge {
+        use super::*;
+        use crate::print_ops::{print_task, tests::setup};
+        use rongta::printer::DumpDriver;
+        use std::sync::Mutex;
+
+        #[derive(Default)]
+        struct RecordingPublisher(Mutex<Vec<(String, serde_json::Value)>>);
+        impl StatusPublisher for RecordingPublisher {
+            async fn publish_status(&self, topic: &str, payload: Vec<u8>) -> anyhow::Result<()> {
+                let payload = serde_json::from_slice(&payload)?;
+                self.0.lock().unwrap().push((topic.to_string(), payload));
+                Ok(())
+            }
+        }
+
+        #[tokio::test]
+        async fn printed_job_publishes_printed() {
+            let payload = br#"{"content": "hi", "cut": false, "job": "abc"}"#;
+            let (task, _) = parse_message(MqttTopic::Message.as_topic(), payload).unwrap();
+            let result = print_task(task, &setup(), SupportedDriver::Dump(DumpDriver::new()));
+
+            let publisher = RecordingPublisher::default();
+            let topic = MqttTopic::Message.status_topic();
+            acknowledge(&publisher, topic, job_id(payload), result).await;
+
+            let published = publisher.0.into_inner().unwrap();
+            assert_eq!(
+                published,
+                [(
+                    "status/konan_pi/message".to_string(),
+                    serde_json::json!({"job": "abc", "status": "printed"})
+                )]
+            );
+        }
+
+        #[tokio::test]
+        async fn failed_job_publishes_the_error() {
+            let publisher = RecordingPublisher::default();
+            let result = Err(anyhow::anyhow!("Printer cover is open"));
+            acknowledge(&publisher, "status/konan_pi/habits", None, result).await;
+
+            let published = publisher.0.into_inner().unwrap();
+            assert_eq!(
+                published[0].1,
+                serde_json::json!({"job": null, "status": "failed", "error": "Printer cover is open"})
+            );
+        }
+
+        #[tokio::test]
+        async fn redelivery_publishes_skipped() {
+            let publisher = RecordingPublisher::default();
+            let result = Err(anyhow::Error::msg(Redelivery(Duration::from_secs(300))));
+            acknowledge(
+                &publisher,
+                "status/konan_pi/habits",
+                Some("abc".into()),
+                result,
+            )
+            .await;
+
+            let published = publisher.0.into_inner().unwrap();
+            assert_eq!(published[0].1["status"], "skipped");
+            assert_eq!(published[0].1["job"], "abc");
+        }
+
+        #[tokio::test]
+        async fn spooled_job_publishes_spooled() {
+            let publisher = RecordingPublisher::default();
+            let result = Err(anyhow::anyhow!("Printer not found")
+                .context(Spooled(PathBuf::from("/spool/1.json"))));
+            acknowledge(&publisher, "status/konan_pi/message", None, result).await;
+
+            let published = publisher.0.into_inner().unwrap();
+            assert_eq!(published[0].1["status"], "spooled");
+        }
+    }
+
+    mod resolve_printer {
+        use super::*;
+
+        fn printers() -> HashMap<String, PrinterTarget> {
+            let config: HashMap<String, HashMap<String, PrinterTarget>> = toml::from_str(
+                r#"
+                [printers]
+                kitchen = { network = { host = "192.168.1.50", port = 9100 } }
+                desk = { usb = { vendor_id = 0x0FE6, product_id = 0x811E } }
+                "#,
+            )
+            .unwrap();
+            config["printers"].clone()
+        }
+
+        fn payload_printer(json: &str) -> Option<String> {
+            serde_json::from_str::<DirectPrintOut>(json)
+                .unwrap()
+                .printer
+        }
+
+        #[test]
+        fn named_printer_routes_to_its_driver() {
+            let printer =
+                payload_printer(r#"{"content": "hi", "rows": null, "printer": "kitchen"}"#);
+            let route = resolve_printer(&printers(), printer.as_deref()).unwrap();
+            assert!(matches!(
+                route,
+                Some(SupportedDriver::Network(host, 9100)) if host == "192.168.1.50"
+            ));
+        }
+
+        #[test]
+        fn absent_printer_uses_the_default() {
+            let printer = payload_printer(r#"{"content": "hi", "rows": null}"#);
+            assert!(
+                resolve_printer(&printers(), printer.as_deref())
+                    .unwrap()
+                    .is_none()
+            );
+        }
+
+        #[test]
+        fn unknown_printer_is_an_error() {
+            assert!(resolve_printer(&printers(), Some("garage")).is_err());
+        }
+    }
+
+    mod queue_message {
+        use super::*;
+
+        const HABITS: &str = "command/konan_pi/habits";
+        const PAYLOAD: &[u8] = br#"{"habit": "Read", "start_date": "2026-10-01T00:00:00Z", "end_date": "2026-10-14T00:00:00Z"}"#;
+
+        type Queued = anyhow::Result<oneshot::Receiver<anyhow::Result<PrintReport>>>;
+
+        fn queued(_: PrintTask, _: Option<&str>) -> Queued {
+            Ok(oneshot::channel().1)
+        }
+
+        fn queue_full(_: PrintTask, _: Option<&str>) -> Queued {
+            Err(anyhow::anyhow!("Print queue is full"))
+        }
+
+        fn is_redelivery(result: &Queued) -> bool {
+            result
+                .as_ref()
+                .is_err_and(|e| e.downcast_ref::<Redelivery>().is_some())
+        }
+
+        #[test]
+        fn redelivery_within_the_window_prints_once() {
+            let mut recent = RecentPayloads::new(Duration::from_secs(300));
+            let now = Instant::now();
+            assert!(queue_message(&mut recent, HABITS, PAYLOAD, now, queued).is_ok());
+            recent.settle(HABITS, PAYLOAD, &Ok(PrintReport::default()), now);
+            let again = now + Duration::from_secs(60);
+            assert!(is_redelivery(&queue_message(
+                &mut recent,
+                HABITS,
+                PAYLOAD,
+                again,
+                queued
+            )));
+            let later = now + Duration::from_secs(301);
+            assert!(queue_message(&mut recent, HABITS, PAYLOAD, later, queued).is_ok());
+        }
+
+        #[test]
+        fn redelivery_while_queued_is_skipped() {
+            let mut recent = RecentPayloads::new(Duration::from_secs(300));
+            let now = Instant::now();
+            assert!(queue_message(&mut recent, HABITS, PAYLOAD, now, queued).is_ok());
+            assert!(is_redelivery(&queue_message(
+                &mut recent,
+                HABITS,
+                PAYLOAD,
+                now,
+                queued
+            )));
+        }
+
+        #[test]
+        fn failed_print_prints_when_resent() {
+            let mut recent = RecentPayloads::new(Duration::from_secs(300));
+            let now = Instant::now();
+            assert!(queue_message(&mut recent, HABITS, PAYLOAD, now, queued).is_ok());
+            let failed = Err(anyhow::anyhow!("Printer not found"));
+            recent.settle(HABITS, PAYLOAD, &failed, now);
+            let resent = now + Duration::from_secs(30);
+            assert!(queue_message(&mut recent, HABITS, PAYLOAD, resent, queued).is_ok());
+        }
+
+        #[test]
+        fn spooled_print_is_not_queued_again() {
+            let mut recent = RecentPayloads::new(Duration::from_secs(300));
+            let now = Instant::now();
+            assert!(queue_message(&mut recent, HABITS, PAYLOAD, now, queued).is_ok());
+            let spooled = Err(anyhow::anyhow!("Printer not found")
+                .context(Spooled(PathBuf::from("/spool/1.json"))));
+            recent.settle(HABITS, PAYLOAD, &spooled, now);
+            let redelivered = now + Duration::from_secs(30);
+            assert!(is_redelivery(&queue_message(
+                &mut recent,
+                HABITS,
+                PAYLOAD,
+                redelivered,
+                queued
+            )));
+        }
+
+        #[test]
+        fn payload_turned_away_is_not_recorded() {
+            let mut recent = RecentPayloads::new(Duration::from_secs(300));
+            let now = Instant::now();
+            let full = queue_message(&mut recent, HABITS, PAYLOAD, now, queue_full);
+            assert!(full.is_err() && !is_redelivery(&full));
+            assert!(queue_message(&mut recent, HABITS, PAYLOAD, now, queued).is_ok());
+        }
+
+        #[test]
+        fn changed_payload_prints_again() {
+            let mut recent = RecentPayloads::new(Duration::from_secs(300));
+            let now = Instant::now();
+            assert!(queue_message(&mut recent, HABITS, PAYLOAD, now, queued).is_ok());
+            let changed = String::from_utf8_lossy(PAYLOAD).replace("Read", "Run");
+            assert!(queue_message(&mut recent, HABITS, changed.as_bytes(), now, queued).is_ok());
+        }
+
+        #[test]
+        fn zero_window_prints_every_delivery() {
+            let mut recent = RecentPayloads::new(Duration::ZERO);
+            let now = Instant::now();
+            assert!(queue_message(&mut recent, HABITS, PAYLOAD, now, queued).is_ok());
+            assert!(queue_message(&mut recent, HABITS, PAYLOAD, now, queued).is_ok());
+        }
+
+        #[test]
+        fn messages_are_never_skipped() {
+            let mut recent = RecentPayloads::new(Duration::from_secs(300));
+            let now = Instant::now();
+            let message = MqttTopic::Message.as_topic();
+            let payload = br#"{"content": "hi"}"#;
+            assert!(queue_message(&mut recent, message, payload, now, queued).is_ok());
+            assert!(queue_message(&mut recent, message, payload, now, queued).is_ok());
+        }
+    }
+}
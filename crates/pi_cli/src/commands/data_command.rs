@@ -0,0 +1,21 @@
+use crate::print_ops::print_and_wait;
+use cli_shared::{PrintTask, data_command::DataArgs, tasks::KonanData};
+use rongta::PrintReport;
+
+pub async fn handle_data_command(
+    args: DataArgs,
+    cut: bool,
+    width: u8,
+) -> anyhow::Result<PrintReport> {
+    let name = args
+        .path
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_else(|| args.path.to_string_lossy().into_owned());
+    print_and_wait(PrintTask::Data(KonanData {
+        cut,
+        cpl: width,
+        name,
+    }))
+    .await
+}
@@ -1,19 +1,54 @@
-use crate::print_ops::enqueue_print;
+use crate::{
+    config::printer_files_dir_path,
+    print_ops::{print_and_wait, validate_file},
+};
+use anyhow::bail;
 use cli_shared::{PrintTask, file_command::FileArgs, tasks::KonanFile};
+use rongta::PrintReport;
 
-pub async fn handle_file_command(args: FileArgs, cut: bool) -> anyhow::Result<String> {
+pub async fn handle_file_command(
+    args: FileArgs,
+    cut: bool,
+    width: u8,
+) -> anyhow::Result<PrintReport> {
+    print_and_wait(PrintTask::File(konan_file(args, cut, width)?)).await
+}
+
+/// Check the file with `--dry-run`, erroring with the location of every character the
+/// printer can't print
+pub fn handle_file_dry_run(args: FileArgs, cut: bool, width: u8) -> anyhow::Result<String> {
+    let unprintable = validate_file(konan_file(args, cut, width)?)?;
+    if unprintable.is_empty() {
+        return Ok("No unprintable characters found".to_string());
+    }
+    let locations: Vec<String> = unprintable.iter().map(ToString::to_string).collect();
+    bail!(
+        "Found {} unprintable characters:\n{}",
+        unprintable.len(),
+        locations.join("\n")
+    )
+}
+
+fn konan_file(mut args: FileArgs, cut: bool, width: u8) -> anyhow::Result<KonanFile> {
+    if args.is_stdin() {
+        args.take_stdin(std::io::stdin().lock(), &printer_files_dir_path()?)?;
+    }
+    let title = args.title();
     let name = args
         .path
         .file_name()
         .map(|n| n.to_string_lossy().into_owned())
         .unwrap_or_else(|| args.path.to_string_lossy().into_owned());
-    enqueue_print(PrintTask::File(KonanFile {
+    Ok(KonanFile {
         name,
         cut,
+        cpl: width,
         rows: args.rows,
         prehook_command: args.prehook_command,
         prehook_command_arg: args.prehook_command_args,
-    }))
-    .await;
-    Ok("File printed successfully.".to_string())
+        line_numbers: args.line_numbers,
+        split_on_rule: args.split_on_rule,
+        compact: args.compact,
+        title,
+    })
 }
@@ -1,8 +1,14 @@
 mod connect_command;
 pub use connect_command::handle_connect_command;
+mod data_command;
+pub use data_command::handle_data_command;
 mod file_command;
-pub use file_command::handle_file_command;
+pub use file_command::{handle_file_command, handle_file_dry_run};
 mod template_command;
 pub use template_command::handle_template_command;
+mod qr_command;
+pub use qr_command::handle_qr_command;
+mod now_command;
+pub use now_command::handle_now_command;
 mod pulse_command;
 pub use pulse_command::{PulseArgs, handle_pulse_command};
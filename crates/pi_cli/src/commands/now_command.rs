@@ -0,0 +1,18 @@
+use crate::print_ops::print_and_wait;
+use chrono::Utc;
+use cli_shared::{PrintTask, tasks::Timestamp};
+use rongta::PrintReport;
+
+pub async fn handle_now_command(
+    format: Option<String>,
+    cut: bool,
+    width: u8,
+) -> anyhow::Result<PrintReport> {
+    print_and_wait(PrintTask::Timestamp(Timestamp {
+        cut,
+        cpl: width,
+        time: Utc::now(),
+        format,
+    }))
+    .await
+}
@@ -9,7 +9,10 @@ use crate::{
 use anyhow::{Context, Result};
 use chrono::Utc;
 use clap::{Parser, Subcommand};
-use cli_shared::{PrintTask, PulseRecipe, tasks::DirectPrintOut};
+use cli_shared::{
+    PrintTask, PulseRecipe,
+    tasks::{DEFAULT_CPL, DirectPrintOut},
+};
 use std::str::FromStr;
 
 #[derive(Debug, Parser)]
@@ -108,8 +111,10 @@ async fn print_error(message: &str) {
     eprintln!("{message}");
     enqueue_print(PrintTask::Text(DirectPrintOut {
         cut: true,
+        cpl: DEFAULT_CPL,
         content: message.to_string(),
         rows: None,
+        printer: None,
     }))
     .await;
 }
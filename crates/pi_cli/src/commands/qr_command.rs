@@ -0,0 +1,14 @@
+use crate::print_ops::print_and_wait;
+use cli_shared::{PrintTask, qr_command::QrArgs, tasks::QrCode};
+use rongta::PrintReport;
+
+pub async fn handle_qr_command(args: QrArgs, cut: bool, width: u8) -> anyhow::Result<PrintReport> {
+    print_and_wait(PrintTask::QrCode(QrCode {
+        cut,
+        cpl: width,
+        data: args.data,
+        size: args.size,
+        caption: args.caption,
+    }))
+    .await
+}
@@ -1,48 +1,103 @@
-use crate::print_ops::enqueue_print;
-use chrono::{NaiveDate, TimeZone, Utc};
+use crate::print_ops::{banner_timezone, print_and_wait};
+use chrono::Utc;
 use cli_shared::{
-    clap_enum::TimePeriod, tasks::HabitTrackerTemplate, template_command::TemplateArgs,
+    clap_enum::{TimePeriod, parse_relative_date},
+    tasks::{CalendarTemplate, ChecklistTemplate, HabitTrackerTemplate},
+    template_command::TemplateArgs,
 };
+use rongta::PrintReport;
 
-pub async fn handle_template_command(args: TemplateArgs, cut: bool) -> anyhow::Result<String> {
+pub async fn handle_template_command(
+    args: TemplateArgs,
+    cut: bool,
+    width: u8,
+) -> anyhow::Result<PrintReport> {
     match args.command {
         cli_shared::template_command::TemplateCommand::Box {
-            rows,
+            height,
             date,
             banner,
             lined,
+            checkboxes,
+            columns,
+            ledger,
         } => {
-            enqueue_print(cli_shared::PrintTask::BoxTemplate(
+            print_and_wait(cli_shared::PrintTask::BoxTemplate(
                 cli_shared::tasks::BoxTemplate {
                     cut,
-                    rows,
+                    cpl: width,
+                    height,
                     lined,
+                    checkboxes,
                     banner,
                     date: date.map(|v| v.into()),
+                    columns,
+                    ledger,
+                    printer: None,
                 },
             ))
-            .await;
-            Ok("Box Template printed successfully.".to_string())
+            .await
+        }
+        cli_shared::template_command::TemplateCommand::Checklist {
+            items,
+            title,
+            numbered,
+            blank_lines,
+        } => {
+            print_and_wait(cli_shared::PrintTask::Checklist(ChecklistTemplate {
+                cut,
+                cpl: width,
+                items,
+                title,
+                numbered,
+                blank_lines: blank_lines.unwrap_or_default(),
+            }))
+            .await
         }
         cli_shared::template_command::TemplateCommand::HabitTracker {
             habit,
             start_date,
             time_period,
+            mark_weekends,
         } => {
             let start_date = match start_date {
-                Some(s) => NaiveDate::parse_from_str(&s, "%Y-%m-%d")
-                    .map(|d| Utc.from_utc_datetime(&d.and_hms_opt(0, 0, 0).unwrap()))
-                    .map_err(|_| anyhow::anyhow!("Invalid date format. Expected YYYY-MM-DD"))?,
+                Some(s) => parse_relative_date(&s, Utc::now(), |date| {
+                    banner_timezone().start_of_day(date)
+                })?,
                 None => Utc::now(),
             };
-            enqueue_print(cli_shared::PrintTask::HabitTracker(HabitTrackerTemplate {
+            print_and_wait(cli_shared::PrintTask::HabitTracker(HabitTrackerTemplate {
                 cut,
-                habit,
+                cpl: width,
+                habits: vec![habit],
                 start_date,
                 end_date: TimePeriod::into_datetime(time_period.unwrap_or_default(), start_date),
+                mark_weekends,
+                printer: None,
+            }))
+            .await
+        }
+        cli_shared::template_command::TemplateCommand::Calendar {
+            date,
+            week,
+            lined,
+            weeks_per_page,
+        } => {
+            let date = match date {
+                Some(s) => parse_relative_date(&s, Utc::now(), |date| {
+                    banner_timezone().start_of_day(date)
+                })?,
+                None => Utc::now(),
+            };
+            print_and_wait(cli_shared::PrintTask::Calendar(CalendarTemplate {
+                cut,
+                cpl: width,
+                date,
+                week,
+                lined,
+                weeks_per_page,
             }))
-            .await;
-            Ok("Habit Tracker printed successfully.".to_string())
+            .await
         }
     }
 }
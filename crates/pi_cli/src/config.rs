@@ -1,6 +1,8 @@
 use anyhow::{Context, Result};
-use serde::Deserialize;
-use std::{path::PathBuf, sync::LazyLock};
+use blueprint::template::BannerTimezone;
+use rongta::{SupportedDriver, profile::PrinterModel};
+use serde::{Deserialize, Deserializer};
+use std::{collections::HashMap, path::PathBuf, sync::LazyLock};
 
 #[derive(Debug, Clone, Deserialize)]
 pub struct KonanIotConfig {
@@ -10,11 +12,75 @@ pub struct KonanIotConfig {
     pub cert_path: PathBuf,
     pub private_key_path: PathBuf,
     pub root_trust_path: PathBuf,
+    /// Seconds during which a template payload identical to one already printed, or still
+    /// queued, is taken for an MQTT redelivery and skipped. 0 prints every delivery.
+    #[serde(default = "default_dedup_window_secs")]
+    pub dedup_window_secs: u64,
+}
+
+fn default_dedup_window_secs() -> u64 {
+    300
+}
+
+/// Settings that depend on the attached printer model
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct PrinterConfig {
+    /// Lines fed before every cut so the last printed line clears the blade. Printers with
+    /// a long head to cutter distance need 3-5, most need none.
+    #[serde(default)]
+    pub cutter_offset_lines: u8,
+    /// Model of the attached printer, e.g. `rongta-rp58`, which caps the characters per line
+    /// and skips cuts on printers without a cutter. `custom` leaves both as requested.
+    #[serde(default, deserialize_with = "deserialize_model")]
+    pub model: PrinterModel,
+    /// Don't reset the printer before each job, keeping settings such as a code page made
+    /// through the printer's own utility
+    #[serde(default)]
+    pub no_init: bool,
+    /// Seconds a network printer may sit idle before it is woken ahead of the next job,
+    /// for printers that drop the first bytes after sleeping. Never woken when unset.
+    #[serde(default)]
+    pub wake_after_idle_secs: Option<u64>,
+}
+
+fn deserialize_model<'de, D: Deserializer<'de>>(deserializer: D) -> Result<PrinterModel, D::Error> {
+    String::deserialize(deserializer)?
+        .parse()
+        .map_err(serde::de::Error::custom)
+}
+
+/// Where a named printer from the `printers` table is attached, e.g.
+/// `kitchen = { network = { host = "192.168.1.50", port = 9100 } }`
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PrinterTarget {
+    Usb { vendor_id: u16, product_id: u16 },
+    Network { host: String, port: u16 },
+}
+impl From<PrinterTarget> for SupportedDriver {
+    fn from(target: PrinterTarget) -> Self {
+        match target {
+            PrinterTarget::Usb {
+                vendor_id,
+                product_id,
+            } => SupportedDriver::Usb(vendor_id, product_id),
+            PrinterTarget::Network { host, port } => SupportedDriver::Network(host, port),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Deserialize)]
 pub struct Config {
     pub connect: KonanIotConfig,
+    /// Timezone for dates on printed banners: `local`, an offset like `-05:00`, or a zone
+    /// name like `America/New_York`. Defaults to the system local zone.
+    #[serde(default)]
+    pub timezone: BannerTimezone,
+    #[serde(default)]
+    pub printer: PrinterConfig,
+    /// Extra printers MQTT messages can name, the attached USB printer stays the default
+    #[serde(default)]
+    pub printers: HashMap<String, PrinterTarget>,
 }
 
 pub static CONFIG: LazyLock<Result<Config>> = LazyLock::new(Config::load);
@@ -57,6 +123,15 @@ pub fn printer_lock_path() -> Result<PathBuf> {
     Ok(storage.join("printer.lock"))
 }
 
+pub fn spool_dir_path() -> Result<PathBuf> {
+    let spool_path = application_storage_path()?.join("spool");
+    if !spool_path.exists() {
+        std::fs::create_dir_all(&spool_path)
+            .with_context(|| format!("Failed to create directory '{}'", spool_path.display()))?;
+    }
+    Ok(spool_path)
+}
+
 pub fn printer_files_dir_path() -> Result<PathBuf> {
     let files_path = application_storage_path()?.join("files");
     if !files_path.exists() {
@@ -1,19 +1,37 @@
 use crate::config::Config;
 use clap::{Parser, Subcommand};
-use cli_shared::{file_command, template_command};
+use cli_shared::{data_command, file_command, qr_command, template_command};
+use output::JsonOutput;
+use rongta::{PrintReport, profile::PrinterModel};
+use std::path::PathBuf;
 
 mod commands;
 mod config;
 mod database;
+mod output;
 mod print_ops;
+mod spool;
 
 #[derive(Debug, Subcommand)]
 pub enum Commands {
     Connect,
     #[clap(about = "Print a file")]
     File(file_command::FileArgs),
+    #[clap(about = "Print a JSON or CSV file as a table")]
+    Data(data_command::DataArgs),
     #[clap(about = "Print a predefined template")]
     Template(template_command::TemplateArgs),
+    #[clap(about = "Print a QR code")]
+    Qr(qr_command::QrArgs),
+    #[clap(about = "Print the current date and time")]
+    Now {
+        #[clap(
+            long,
+            help = "strftime format, e.g. \"%Y-%m-%d %H:%M\"",
+            long_help = "strftime format. Defaults to \"%A %B %d, %Y  %H:%M\""
+        )]
+        format: Option<String>,
+    },
     #[clap(about = "Print scheduled jobs")]
     Pulse(commands::PulseArgs),
 }
@@ -31,30 +49,201 @@ pub struct App {
         global = true
     )]
     no_cut: bool,
+    #[clap(
+        long,
+        help = "Characters per line, 16 to 72. 48 for 80mm paper, 32 for 58mm",
+        default_value_t = cli_shared::tasks::DEFAULT_CPL,
+        value_parser = clap::value_parser!(u8)
+            .range(cli_shared::tasks::MIN_CPL as i64..=cli_shared::tasks::MAX_CPL as i64),
+        global = true
+    )]
+    width: u8,
+    #[clap(
+        long,
+        help = "Write a JSON result object to stdout instead of a message",
+        global = true
+    )]
+    json: bool,
+    #[clap(
+        long,
+        help = "Log the ESC/POS bytes sent to the printer in hex",
+        long_help = "Log every ESC/POS instruction sent to the printer in hex, to capture the \
+                     exact byte stream for a bug report",
+        global = true
+    )]
+    debug_hex: bool,
+    #[clap(
+        long,
+        value_name = "PATH",
+        help = "Write the ESC/POS bytes to a file instead of printing",
+        long_help = "Write the ESC/POS byte stream to a file instead of printing, appending \
+                     each job. Send it on with e.g. `lp -o raw <PATH>`",
+        global = true
+    )]
+    output: Option<PathBuf>,
+    #[clap(
+        long,
+        help = "Printer model, e.g. rongta-rp80, rongta-rp58 or epson-tm-t20",
+        long_help = "Printer model, which caps the characters per line and skips cuts on \
+                     printers without a cutter. Overrides `model` in the [printer] config. \
+                     Known models: rongta-rp80, rongta-rp58, epson-tm-t20 or custom",
+        global = true
+    )]
+    model: Option<PrinterModel>,
+    #[clap(
+        long,
+        help = "Don't reset the printer before printing",
+        long_help = "Don't reset the printer before printing, keeping settings such as a \
+                     code page configured through the printer's own utility",
+        global = true
+    )]
+    no_init: bool,
 }
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
-    cli_shared::init_logging("pi_cli");
-    print_ops::init_queue();
     let app = App::parse();
+    match app.debug_hex {
+        true => cli_shared::init_logging_with("pi_cli", &["escpos=debug"]),
+        false => cli_shared::init_logging("pi_cli"),
+    }
+    print_ops::set_debug_hex(app.debug_hex);
+    print_ops::set_no_init(app.no_init);
+    if let Some(output) = app.output {
+        print_ops::set_output(output);
+    }
+    if let Some(model) = app.model {
+        print_ops::set_model(model);
+    }
+    // Only the long-running MQTT loop spools jobs for later, one-shot commands report failures
+    let spool = match app.command {
+        Commands::Connect => Some(spool::Spool::new(config::spool_dir_path()?)),
+        _ => None,
+    };
+    print_ops::init_queue(spool);
     let config = Config::get()?;
-    match app.command {
-        Commands::Connect => commands::handle_connect_command(config.connect.clone()).await,
+    let result = match app.command {
+        Commands::Connect => {
+            return commands::handle_connect_command(config.connect.clone()).await;
+        }
+        Commands::File(file_args) if file_args.dry_run => {
+            commands::handle_file_dry_run(file_args, !app.no_cut, app.width)
+                .map(|message| (message.clone(), JsonOutput::message(message)))
+        }
         Commands::File(file_args) => {
-            let message = commands::handle_file_command(file_args, !app.no_cut).await?;
-            println!("{message}");
-            Ok(())
+            commands::handle_file_command(file_args, !app.no_cut, app.width)
+                .await
+                .map(|report| printed("File", report))
+        }
+        Commands::Data(data_args) => {
+            commands::handle_data_command(data_args, !app.no_cut, app.width)
+                .await
+                .map(|report| printed("Table", report))
         }
         Commands::Template(template_args) => {
-            let message = commands::handle_template_command(template_args, !app.no_cut).await?;
-            println!("{message}");
-            Ok(())
+            commands::handle_template_command(template_args, !app.no_cut, app.width)
+                .await
+                .map(|report| printed("Template", report))
         }
-        Commands::Pulse(pulse_args) => {
-            let message = commands::handle_pulse_command(pulse_args).await?;
-            println!("{message}");
-            Ok(())
+        Commands::Qr(qr_args) => commands::handle_qr_command(qr_args, !app.no_cut, app.width)
+            .await
+            .map(|report| printed("QR code", report)),
+        Commands::Now { format } => commands::handle_now_command(format, !app.no_cut, app.width)
+            .await
+            .map(|report| printed("Date", report)),
+        Commands::Pulse(pulse_args) => commands::handle_pulse_command(pulse_args)
+            .await
+            .map(|message| (message.clone(), JsonOutput::message(message))),
+    };
+    emit(result, app.json)
+}
+
+fn printed(what: &str, report: PrintReport) -> (String, JsonOutput) {
+    (
+        format!("{what} printed successfully. {report}"),
+        JsonOutput::printed(&report),
+    )
+}
+
+/// Write the command outcome to stdout, as the human message or as JSON
+fn emit(result: anyhow::Result<(String, JsonOutput)>, json: bool) -> anyhow::Result<()> {
+    match result {
+        Ok((message, _)) if !json => println!("{message}"),
+        Ok((_, output)) => println!("{}", serde_json::to_string(&output)?),
+        Err(e) => {
+            if json {
+                println!("{}", serde_json::to_string(&JsonOutput::error(&e))?);
+            }
+            return Err(e);
         }
     }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn width_defaults_to_cpl() {
+        let app = App::try_parse_from(["konan_pi", "file", "notes.md"]).unwrap();
+        assert_eq!(app.width, rongta::CPL);
+    }
+
+    #[test]
+    fn width_is_global() {
+        let app = App::try_parse_from(["konan_pi", "file", "notes.md", "--width", "32"]).unwrap();
+        assert_eq!(app.width, 32);
+    }
+
+    #[test]
+    fn qr_size_is_validated() {
+        assert!(App::try_parse_from(["konan_pi", "qr", "data", "--size", "15"]).is_ok());
+        assert!(App::try_parse_from(["konan_pi", "qr", "data", "--size", "0"]).is_err());
+        assert!(App::try_parse_from(["konan_pi", "qr", "data", "--size", "16"]).is_err());
+    }
+
+    #[test]
+    fn checklist_needs_items_or_blank_lines() {
+        let checklist = |args: &[&str]| {
+            App::try_parse_from(["konan_pi", "template", "checklist"].iter().chain(args))
+        };
+        assert!(checklist(&["--item", "Milk"]).is_ok());
+        assert!(checklist(&["--blank-lines", "5", "--numbered"]).is_ok());
+        assert!(checklist(&["--title", "Chores"]).is_err());
+    }
+
+    #[test]
+    fn debug_hex_is_global() {
+        let app = App::try_parse_from(["konan_pi", "now", "--debug-hex"]).unwrap();
+        assert!(app.debug_hex);
+    }
+
+    #[test]
+    fn width_is_checked() {
+        let width = |value: &str| App::try_parse_from(["konan_pi", "now", "--width", value]);
+        assert_eq!(width("32").unwrap().width, 32);
+        assert!(width("0").is_err());
+        assert!(width("15").is_err());
+        assert!(width("73").is_err());
+    }
+
+    #[test]
+    fn output_is_global() {
+        let app = App::try_parse_from(["konan_pi", "now", "--output", "job.bin"]).unwrap();
+        assert_eq!(app.output, Some(PathBuf::from("job.bin")));
+    }
+
+    #[test]
+    fn model_is_global() {
+        let app = App::try_parse_from(["konan_pi", "now", "--model", "Rongta RP58"]).unwrap();
+        assert_eq!(app.model, Some(PrinterModel::RongtaRp58));
+        assert!(App::try_parse_from(["konan_pi", "now", "--model", "rp100"]).is_err());
+    }
+
+    #[test]
+    fn json_is_global() {
+        let app = App::try_parse_from(["konan_pi", "qr", "data", "--json"]).unwrap();
+        assert!(app.json);
+    }
 }
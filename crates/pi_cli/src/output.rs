@@ -0,0 +1,76 @@
+use rongta::PrintReport;
+use serde::Serialize;
+
+/// Result object written to stdout with `--json`
+#[derive(Debug, Default, PartialEq, Serialize)]
+pub struct JsonOutput {
+    pub ok: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lines: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pages: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+impl JsonOutput {
+    pub fn printed(report: &PrintReport) -> Self {
+        Self {
+            ok: true,
+            lines: Some(report.lines_printed),
+            pages: Some(report.pages),
+            ..Default::default()
+        }
+    }
+
+    pub fn message(message: String) -> Self {
+        Self {
+            ok: true,
+            message: Some(message),
+            ..Default::default()
+        }
+    }
+
+    pub fn error(error: &anyhow::Error) -> Self {
+        Self {
+            error: Some(format!("{error:#}")),
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod printed {
+        use super::*;
+
+        #[test]
+        fn serializes_report_shape() {
+            let report = PrintReport {
+                lines_printed: 42,
+                bytes_sent: 900,
+                pages: 2,
+                cut: true,
+            };
+            let json = serde_json::to_string(&JsonOutput::printed(&report)).unwrap();
+            assert_eq!(json, r#"{"ok":true,"lines":42,"pages":2}"#);
+        }
+    }
+
+    mod error {
+        use super::*;
+
+        #[test]
+        fn serializes_error_chain() {
+            let error = anyhow::anyhow!("USB device not found").context("Failed to print");
+            let json = serde_json::to_string(&JsonOutput::error(&error)).unwrap();
+            assert_eq!(
+                json,
+                r#"{"ok":false,"error":"Failed to print: USB device not found"}"#
+            );
+        }
+    }
+}
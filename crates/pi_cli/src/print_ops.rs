@@ -1,74 +1,421 @@
-use crate::config::{printer_files_dir_path, printer_lock_path};
+use crate::{
+    config::{Config, printer_files_dir_path, printer_lock_path},
+    spool::{Spool, Spooled},
+};
 use anyhow::{Context, bail};
 use blueprint::{
-    interpreter::{markdown::MarkdownInterpreter, text::TextInterpreter},
+    interpreter::{
+        markdown::MarkdownInterpreter,
+        text::{TextInterpreter, line_numbered},
+    },
     template::{
-        box_outline::BoxTemplateBuilder, get_random_box_pattern,
+        BannerTimezone, BoxPattern,
+        box_outline::BoxTemplateBuilder,
+        calendar::{CalendarSpan, CalendarTemplateBuilder},
+        checklist::{ChecklistItem, ChecklistTemplateBuilder},
+        get_random_box_pattern,
         habit_tracker::HabitTrackerTemplateBuilder,
+        table::{Table, TableTemplateBuilder},
     },
 };
+use chrono::Datelike;
 use cli_shared::{
     PrintTask,
-    tasks::{BoxTemplate, DirectPrintOut, HabitTrackerTemplate, KonanFile},
+    tasks::{
+        BoxTemplate, CalendarTemplate, ChecklistTemplate, DEFAULT_TIMESTAMP_FORMAT, DirectPrintOut,
+        HabitTrackerTemplate, KonanData, KonanFile, QrCode, Timestamp,
+    },
 };
 use fs4::fs_std::FileExt;
-use rongta::{RongtaPrinter, SupportedDriver};
-use std::{fs::OpenOptions, sync::OnceLock};
-use tokio::sync::mpsc;
+use rongta::{
+    PrintReport, RongtaPrinter, SupportedDriver, UnprintableChar,
+    profile::{PrinterModel, PrinterProfile},
+};
+use std::{
+    collections::HashMap,
+    fmt::Write,
+    fs::OpenOptions,
+    panic::AssertUnwindSafe,
+    path::PathBuf,
+    sync::{
+        OnceLock,
+        atomic::{AtomicBool, Ordering},
+    },
+    time::{Duration, Instant},
+};
+use tokio::sync::{mpsc, oneshot};
 
 const VENDOR_ID: u16 = 0x0FE6;
 const PRODUCT_ID: u16 = 0x811E;
 
-type PrintQueue = mpsc::Sender<PrintTask>;
+/// Jobs that can wait for the printer before new ones are turned away
+const PRINT_QUEUE_CAPACITY: usize = 32;
+
+/// How often spooled jobs are retried while the printer is offline
+const SPOOL_RETRY_INTERVAL: Duration = Duration::from_secs(60);
+
+/// A queued task, the printer it goes to when not the default, and an optional channel for
+/// the caller waiting on its outcome
+type QueuedTask = (
+    PrintTask,
+    Option<SupportedDriver>,
+    Option<oneshot::Sender<anyhow::Result<PrintReport>>>,
+);
+type PrintQueue = mpsc::Sender<QueuedTask>;
 
 static PRINT_QUEUE: OnceLock<PrintQueue> = OnceLock::new();
 
-pub fn init_queue() {
-    let (tx, mut rx) = mpsc::channel::<PrintTask>(32);
+/// Start the print queue worker. With a `spool`, jobs for the default printer that fail
+/// while it can't be reached are written to it and re-queued once the printer is back.
+pub fn init_queue(spool: Option<Spool>) {
+    let (tx, mut rx) = mpsc::channel::<QueuedTask>(PRINT_QUEUE_CAPACITY);
+    let worker_spool = spool.clone();
+    let wake_after = Config::get()
+        .ok()
+        .and_then(|c| c.printer.wake_after_idle_secs)
+        .map(Duration::from_secs);
+    let mut idle = IdleTimers::new(wake_after);
     tokio::spawn(async move {
-        while let Some(task) = rx.recv().await {
+        while let Some((task, route, reply)) = rx.recv().await {
             let lock_file = match acquire_printer_lock() {
                 Ok(f) => f,
                 Err(e) => {
                     log::error!("Could not acquire printer lock, skipping job: {e:#}");
+                    if let Some(reply) = reply {
+                        let _ = reply.send(Err(e));
+                    }
                     continue;
                 }
             };
 
-            let result = match task {
-                PrintTask::BoxTemplate(template) => print_box_template(template),
-                PrintTask::HabitTracker(template) => print_habit_tracker(template),
-                PrintTask::Markdown(template) => print_markdown(template),
-                PrintTask::Text(template) => print_text(template),
-                PrintTask::File(template) => print_file(template),
-            };
+            // Spooled jobs are retried on the default printer only
+            let spooled = worker_spool
+                .as_ref()
+                .filter(|_| route.is_none())
+                .map(|_| task.clone());
+            let driver = route.unwrap_or_else(default_driver);
+            let network = network_key(&driver);
+            if let Some(key) = &network
+                && idle.should_wake(key, Instant::now())
+            {
+                log::info!("Waking {key} after it sat idle");
+                if let Err(e) =
+                    rongta::build_any_printer(driver.clone()).and_then(|mut p| p.wake(no_init()))
+                {
+                    log::warn!("Failed to wake {key}: {e:#}");
+                }
+            }
+            let result = catch_panic(|| print_task(task, &PrintSetup::current(), driver));
+            if let Some(key) = network {
+                idle.printed(key, Instant::now());
+            }
 
             if let Err(e) = lock_file.unlock() {
                 log::error!("Failed to release printer lock: {e:#}");
                 break;
             }
 
-            if let Err(e) = result {
-                log::error!("Print task failed: {e:#}");
+            let result = settle_job(
+                result,
+                worker_spool.as_ref().zip(spooled),
+                printer_reachable,
+            );
+            if let Some(reply) = reply {
+                let _ = reply.send(result);
             }
         }
     });
     PRINT_QUEUE
         .set(tx)
-        .expect("Unable to initialize the PRINT_QUEUE")
+        .expect("Unable to initialize the PRINT_QUEUE");
+    if let Some(spool) = spool {
+        tokio::spawn(retry_spooled(spool));
+    }
+}
+
+/// The outcome to report for a finished job. A failed `spoolable` job, one for the default
+/// printer of a daemon that spools, is written to its spool when the printer isn't
+/// `reachable` and its error carries `Spooled` context.
+fn settle_job(
+    result: anyhow::Result<PrintReport>,
+    spoolable: Option<(&Spool, PrintTask)>,
+    reachable: impl FnOnce() -> bool,
+) -> anyhow::Result<PrintReport> {
+    let e = match result {
+        Ok(report) => return Ok(report),
+        Err(e) => e,
+    };
+    log::error!("Print task failed: {e:#}");
+    let Some((spool, task)) = spoolable.filter(|_| !reachable()) else {
+        return Err(e);
+    };
+    match spool.push(&task) {
+        Ok(path) => {
+            let spooled = Spooled(path);
+            log::warn!("{spooled}");
+            Err(e.context(spooled))
+        }
+        Err(spool_error) => {
+            log::error!("Failed to spool job: {spool_error:#}");
+            Err(e)
+        }
+    }
+}
+
+/// When each network printer last printed, to wake it before a job once it has idled
+struct IdleTimers {
+    /// Idle time after which a printer is woken, never when `None`
+    threshold: Option<Duration>,
+    last_job: HashMap<String, Instant>,
+}
+impl IdleTimers {
+    fn new(threshold: Option<Duration>) -> Self {
+        Self {
+            threshold,
+            last_job: HashMap::new(),
+        }
+    }
+
+    /// Whether the printer at `key` has been idle longer than the threshold at `now`. A
+    /// printer that hasn't printed since startup may have been idle for any time.
+    fn should_wake(&self, key: &str, now: Instant) -> bool {
+        self.threshold.is_some_and(|threshold| {
+            self.last_job
+                .get(key)
+                .is_none_or(|last| now.duration_since(*last) > threshold)
+        })
+    }
+
+    fn printed(&mut self, key: String, now: Instant) {
+        self.last_job.insert(key, now);
+    }
+}
+
+/// Identifies a network printer for `IdleTimers`, `None` for other drivers
+fn network_key(driver: &SupportedDriver) -> Option<String> {
+    match driver {
+        SupportedDriver::Network(host, port) => Some(format!("{host}:{port}")),
+        _ => None,
+    }
+}
+
+/// What a task is printed with besides its own arguments. Tests make their own so their
+/// output doesn't depend on the config, flags or a random box pattern.
+pub struct PrintSetup {
+    /// The builder for a task's `cut` and `cpl`
+    pub builder: fn(bool, u8) -> RongtaPrinter,
+    /// The box pattern for templates with an outline
+    pub pattern: fn() -> anyhow::Result<BoxPattern>,
+    pub timezone: BannerTimezone,
+}
+impl PrintSetup {
+    /// The setup of the config and flags, with a random box pattern
+    pub fn current() -> Self {
+        Self {
+            builder: printer_builder,
+            pattern: get_random_box_pattern,
+            timezone: banner_timezone(),
+        }
+    }
+
+    fn builder(&self, cut: bool, cpl: u8) -> RongtaPrinter {
+        (self.builder)(cut, cpl)
+    }
+}
+
+pub fn print_task(
+    task: PrintTask,
+    setup: &PrintSetup,
+    driver: SupportedDriver,
+) -> anyhow::Result<PrintReport> {
+    match task {
+        PrintTask::BoxTemplate(template) => print_box_template(template, setup, driver),
+        PrintTask::Calendar(template) => print_calendar(template, setup, driver),
+        PrintTask::Checklist(template) => print_checklist(template, setup, driver),
+        PrintTask::HabitTracker(template) => print_habit_tracker(template, setup, driver),
+        PrintTask::Markdown(template) => print_markdown(template, false, false, setup, driver),
+        PrintTask::Text(template) => print_text(template, setup, driver),
+        PrintTask::File(template) => print_file(template, setup, driver),
+        PrintTask::Data(template) => print_data(template, setup, driver),
+        PrintTask::QrCode(template) => print_qr_code(template, setup, driver),
+        PrintTask::Timestamp(template) => print_timestamp(template, setup, driver),
+    }
+}
+
+/// Run a job, turning a panic into an error. A panic would otherwise end the queue worker
+/// and every later job would be lost with it.
+fn catch_panic<T>(job: impl FnOnce() -> anyhow::Result<T>) -> anyhow::Result<T> {
+    std::panic::catch_unwind(AssertUnwindSafe(job)).unwrap_or_else(|panic| {
+        let message = panic
+            .downcast_ref::<&str>()
+            .map(|message| message.to_string())
+            .or_else(|| panic.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "unknown cause".to_string());
+        log::error!("Print task panicked: {message}");
+        Err(anyhow::anyhow!("Print task panicked: {message}"))
+    })
+}
+
+/// Re-queue spooled jobs whenever the printer can be reached again
+async fn retry_spooled(spool: Spool) {
+    let mut interval = tokio::time::interval(SPOOL_RETRY_INTERVAL);
+    loop {
+        interval.tick().await;
+        if spool.is_empty().unwrap_or(true) || !printer_reachable() {
+            continue;
+        }
+        match spool.take_all() {
+            Ok(tasks) => {
+                log::info!("Printer is back, printing {} spooled jobs", tasks.len());
+                for task in tasks {
+                    enqueue_print(task).await;
+                }
+            }
+            Err(e) => log::error!("Failed to drain spool: {e:#}"),
+        }
+    }
+}
+
+/// Whether the printer can be opened right now
+fn printer_reachable() -> bool {
+    rongta::build_any_printer(default_driver()).is_ok()
 }
 
 pub async fn enqueue_print(task: PrintTask) {
+    send_to_queue(task, None, None).await;
+}
+
+/// Queue a task for `printer`, or the default printer when `None`, without waiting for
+/// room. Errors when the queue is full so callers that must stay responsive, like the MQTT
+/// event loop, can turn the job away instead of stalling behind a slow printer. The
+/// returned channel receives the job's outcome once it has printed.
+pub fn try_enqueue_print_to(
+    task: PrintTask,
+    printer: Option<SupportedDriver>,
+) -> anyhow::Result<oneshot::Receiver<anyhow::Result<PrintReport>>> {
+    let (tx, rx) = oneshot::channel();
+    try_queue(
+        PRINT_QUEUE.get().expect("PRINT_QUEUE not initialized"),
+        (task, printer, Some(tx)),
+    )?;
+    Ok(rx)
+}
+
+fn try_queue(queue: &PrintQueue, queued: QueuedTask) -> anyhow::Result<()> {
+    queue.try_send(queued).map_err(|e| match e {
+        mpsc::error::TrySendError::Full(_) => anyhow::anyhow!(
+            "Print queue is full, {} jobs are waiting for the printer",
+            queue.max_capacity()
+        ),
+        mpsc::error::TrySendError::Closed(_) => anyhow::anyhow!("PRINT_QUEUE receiver dropped"),
+    })
+}
+
+/// Queue a task and wait until it has been printed
+pub async fn print_and_wait(task: PrintTask) -> anyhow::Result<PrintReport> {
+    let (tx, rx) = oneshot::channel();
+    send_to_queue(task, None, Some(tx)).await;
+    rx.await
+        .context("PRINT_QUEUE dropped the task before printing it")?
+}
+
+async fn send_to_queue(
+    task: PrintTask,
+    route: Option<SupportedDriver>,
+    reply: Option<oneshot::Sender<anyhow::Result<PrintReport>>>,
+) {
     PRINT_QUEUE
         .get()
         .expect("PRINT_QUEUE not initialized")
-        .send(task)
+        .send((task, route, reply))
         .await
         .expect("PRINT_QUEUE receiver dropped");
 }
 
-fn driver() -> SupportedDriver {
-    SupportedDriver::Usb(VENDOR_ID, PRODUCT_ID)
+/// File the ESC/POS stream is written to instead of the printer, see `set_output`
+static OUTPUT: OnceLock<PathBuf> = OnceLock::new();
+
+/// Write every job to `path` rather than printing it
+pub fn set_output(path: PathBuf) {
+    OUTPUT
+        .set(path)
+        .expect("The output file can only be set once");
+}
+
+/// The attached USB printer, or the `--output` file when given
+fn default_driver() -> SupportedDriver {
+    driver_for(OUTPUT.get())
+}
+
+/// The file at `output` when jobs are written to one, otherwise the USB printer
+fn driver_for(output: Option<&PathBuf>) -> SupportedDriver {
+    match output {
+        Some(path) => SupportedDriver::File(path.clone()),
+        None => SupportedDriver::Usb(VENDOR_ID, PRODUCT_ID),
+    }
+}
+
+pub(crate) fn banner_timezone() -> BannerTimezone {
+    Config::get().map(|c| c.timezone).unwrap_or_default()
+}
+
+fn cutter_offset_lines() -> u8 {
+    Config::get()
+        .map(|c| c.printer.cutter_offset_lines)
+        .unwrap_or_default()
+}
+
+/// Set once from `--debug-hex` before anything is printed
+static DEBUG_HEX: AtomicBool = AtomicBool::new(false);
+
+pub fn set_debug_hex(enabled: bool) {
+    DEBUG_HEX.store(enabled, Ordering::Relaxed);
+}
+
+fn debug_hex() -> bool {
+    DEBUG_HEX.load(Ordering::Relaxed)
+}
+
+/// Set once from `--no-init` before anything is printed
+static NO_INIT: AtomicBool = AtomicBool::new(false);
+
+pub fn set_no_init(enabled: bool) {
+    NO_INIT.store(enabled, Ordering::Relaxed);
+}
+
+/// Whether `--no-init` or the config asks to skip the printer reset
+fn no_init() -> bool {
+    NO_INIT.load(Ordering::Relaxed) || Config::get().is_ok_and(|c| c.printer.no_init)
+}
+
+/// Set once from `--model`, overriding the configured model
+static MODEL: OnceLock<PrinterModel> = OnceLock::new();
+
+pub fn set_model(model: PrinterModel) {
+    MODEL
+        .set(model)
+        .expect("The printer model can only be set once");
+}
+
+/// The profile of the `--model` or configured printer model, `None` for a custom printer
+fn printer_profile() -> Option<PrinterProfile> {
+    MODEL
+        .get()
+        .copied()
+        .or_else(|| Config::get().map(|c| c.printer.model).ok())
+        .and_then(PrinterModel::profile)
+}
+
+fn printer_builder(cut: bool, cpl: u8) -> RongtaPrinter {
+    let builder = RongtaPrinter::new(cut)
+        .with_cpl(cpl)
+        .with_cutter_offset(cutter_offset_lines())
+        .with_debug_hex(debug_hex())
+        .with_no_init(no_init());
+    match printer_profile() {
+        Some(profile) => builder.with_profile(&profile),
+        None => builder,
+    }
 }
 
 fn acquire_printer_lock() -> anyhow::Result<std::fs::File> {
@@ -84,61 +431,710 @@ fn acquire_printer_lock() -> anyhow::Result<std::fs::File> {
     Ok(file)
 }
 
-fn print_markdown(arg: DirectPrintOut) -> anyhow::Result<()> {
-    let mut interpreter = MarkdownInterpreter::new(RongtaPrinter::new(arg.cut));
-    interpreter.print(&arg.content, arg.rows, driver())
+fn print_markdown(
+    arg: DirectPrintOut,
+    split_on_rule: bool,
+    compact: bool,
+    setup: &PrintSetup,
+    driver: SupportedDriver,
+) -> anyhow::Result<PrintReport> {
+    let mut builder = setup.builder(arg.cut, arg.cpl);
+    if compact {
+        builder.compact_preset();
+    }
+    print_markdown_with(builder, &arg.content, arg.rows, split_on_rule, driver)
+}
+
+fn print_markdown_with(
+    builder: RongtaPrinter,
+    content: &str,
+    rows: Option<u32>,
+    split_on_rule: bool,
+    driver: SupportedDriver,
+) -> anyhow::Result<PrintReport> {
+    let mut interpreter = MarkdownInterpreter::new(builder);
+    interpreter.set_split_on_rule(split_on_rule);
+    interpreter.print(content, rows, driver)
 }
 
-fn print_text(arg: DirectPrintOut) -> anyhow::Result<()> {
-    TextInterpreter::print(&arg.content, arg.cut, driver())
+fn print_text(
+    arg: DirectPrintOut,
+    setup: &PrintSetup,
+    driver: SupportedDriver,
+) -> anyhow::Result<PrintReport> {
+    TextInterpreter::print(&arg.content, &setup.builder(arg.cut, arg.cpl), driver)
 }
 
-fn print_box_template(arg: BoxTemplate) -> anyhow::Result<()> {
-    let pattern = get_random_box_pattern()?;
-    let builder = RongtaPrinter::new(arg.cut);
+fn print_box_template(
+    arg: BoxTemplate,
+    setup: &PrintSetup,
+    driver: SupportedDriver,
+) -> anyhow::Result<PrintReport> {
+    let pattern = (setup.pattern)()?;
+    let builder = setup.builder(arg.cut, arg.cpl);
     let mut template = BoxTemplateBuilder::new(builder, pattern);
     template
-        .set_rows(arg.rows.unwrap_or(29))
+        .set_height(arg.height.unwrap_or(29))
         .set_lined(arg.lined)
-        .set_banner(arg.banner);
+        .set_checkboxes(arg.checkboxes)
+        .set_banner(arg.banner)
+        .set_timezone(setup.timezone);
     if let Some(d) = arg.date {
         template.set_date_banner(d);
     }
-    template.print(driver())
+    if !arg.columns.is_empty() {
+        let labels: Vec<&str> = arg.columns.iter().map(String::as_str).collect();
+        template.set_columns(&labels)?;
+    }
+    if !arg.ledger.is_empty() {
+        let labels: Vec<&str> = arg.ledger.iter().map(String::as_str).collect();
+        template.set_ledger(&labels)?;
+    }
+    template.print(driver)
 }
 
-fn print_habit_tracker(arg: HabitTrackerTemplate) -> anyhow::Result<()> {
-    let pattern = get_random_box_pattern()?;
-    let builder = RongtaPrinter::new(arg.cut);
-    let mut template =
-        HabitTrackerTemplateBuilder::new(builder, pattern, arg.habit, arg.start_date, arg.end_date);
-    template.print(driver())
+fn print_calendar(
+    arg: CalendarTemplate,
+    setup: &PrintSetup,
+    driver: SupportedDriver,
+) -> anyhow::Result<PrintReport> {
+    let date = setup.timezone.localize(arg.date).date_naive();
+    let span = match arg.week {
+        true => CalendarSpan::Week(date),
+        false => CalendarSpan::Month {
+            year: date.year(),
+            month: date.month(),
+        },
+    };
+    let builder = setup.builder(arg.cut, arg.cpl);
+    let mut template = CalendarTemplateBuilder::new(builder, (setup.pattern)()?, span);
+    template.set_lined(arg.lined);
+    if let Some(weeks) = arg.weeks_per_page {
+        template.set_weeks_per_page(weeks as usize);
+    }
+    template.print(driver)
 }
 
-fn print_file(arg: KonanFile) -> anyhow::Result<()> {
-    let file_path = printer_files_dir_path()?.join(arg.name);
-    if let Some((prehook_command, profile)) = arg.prehook_command.zip(arg.prehook_command_arg) {
-        prehook_command.run_command(file_path.clone(), &profile)?;
+fn print_checklist(
+    arg: ChecklistTemplate,
+    setup: &PrintSetup,
+    driver: SupportedDriver,
+) -> anyhow::Result<PrintReport> {
+    let items = arg
+        .items
+        .iter()
+        .map(|item| item.parse())
+        .collect::<Result<Vec<ChecklistItem>, _>>()?;
+    let builder = setup.builder(arg.cut, arg.cpl);
+    let mut template = ChecklistTemplateBuilder::new(builder, items);
+    template
+        .set_title(arg.title)
+        .set_numbered(arg.numbered)
+        .set_blank_lines(arg.blank_lines as usize);
+    template.print(driver)
+}
+
+fn print_habit_tracker(
+    arg: HabitTrackerTemplate,
+    setup: &PrintSetup,
+    driver: SupportedDriver,
+) -> anyhow::Result<PrintReport> {
+    let pattern = (setup.pattern)()?;
+    let builder = setup.builder(arg.cut, arg.cpl);
+    let mut template = HabitTrackerTemplateBuilder::new_multi(
+        builder,
+        pattern,
+        arg.habits,
+        arg.start_date,
+        arg.end_date,
+    );
+    template
+        .set_timezone(setup.timezone)
+        .set_mark_weekends(arg.mark_weekends);
+    template.print(driver)
+}
+
+fn qr_code_builder(arg: QrCode, setup: &PrintSetup) -> anyhow::Result<RongtaPrinter> {
+    let mut builder = setup.builder(arg.cut, arg.cpl);
+    let size = arg.size.unwrap_or(rongta::elements::QrCode::DEFAULT_SIZE);
+    match arg.caption {
+        Some(caption) => builder.add_qr_with_caption(&arg.data, &caption, size)?,
+        None => builder.add_qr_code(&arg.data, size)?,
+    }
+    Ok(builder)
+}
+
+fn print_qr_code(
+    arg: QrCode,
+    setup: &PrintSetup,
+    driver: SupportedDriver,
+) -> anyhow::Result<PrintReport> {
+    qr_code_builder(arg, setup)?.print_detailed(None, driver)
+}
+
+fn timestamp_builder(arg: Timestamp, setup: &PrintSetup) -> anyhow::Result<RongtaPrinter> {
+    let format = arg.format.as_deref().unwrap_or(DEFAULT_TIMESTAMP_FORMAT);
+    let mut text = String::new();
+    // Formatting through `write!` reports an invalid format instead of panicking
+    write!(text, "{}", setup.timezone.localize(arg.time).format(format))
+        .map_err(|_| anyhow::anyhow!("Invalid date format '{format}'"))?;
+    let mut builder = setup.builder(arg.cut, arg.cpl);
+    builder.add_autosized_text(&text)?;
+    Ok(builder)
+}
+
+fn print_timestamp(
+    arg: Timestamp,
+    setup: &PrintSetup,
+    driver: SupportedDriver,
+) -> anyhow::Result<PrintReport> {
+    timestamp_builder(arg, setup)?.print_detailed(None, driver)
+}
+
+fn print_file(
+    arg: KonanFile,
+    setup: &PrintSetup,
+    driver: SupportedDriver,
+) -> anyhow::Result<PrintReport> {
+    run_prehook(&arg)?;
+    let (file_path, content) = read_file(&arg)?;
+    if arg.line_numbers {
+        return TextInterpreter::print_with_line_numbers(
+            &content,
+            file_builder(&arg, setup)?,
+            arg.rows,
+            driver,
+        );
     }
-    let content = std::fs::read_to_string(&file_path)
-        .with_context(|| format!("Failed to read pulse file '{}'", file_path.display()))?;
     let file_extension = file_path
         .extension()
         .expect("Supported files are markdown and text");
 
     if file_extension == "md" {
-        print_markdown(DirectPrintOut {
-            cut: arg.cut,
-            content,
-            rows: arg.rows,
-        })
+        print_markdown_with(
+            file_builder(&arg, setup)?,
+            &content,
+            arg.rows,
+            arg.split_on_rule,
+            driver,
+        )
+    } else if file_extension == "txt" && arg.title.is_some() {
+        // Raw text is written straight to the printer, a header needs the builder
+        let mut builder = file_builder(&arg, setup)?;
+        builder.add_content(&content)?;
+        builder.print_detailed(arg.rows, driver)
     } else if file_extension == "txt" {
-        print_text(DirectPrintOut {
-            cut: arg.cut,
-            content,
-            rows: arg.rows,
-        })
+        print_text(
+            DirectPrintOut {
+                cut: arg.cut,
+                cpl: arg.cpl,
+                content,
+                rows: arg.rows,
+                printer: None,
+            },
+            setup,
+            driver,
+        )
     } else {
         bail!("Supported extensions are markdown and text")
     }
 }
+
+/// Render a file as `print_file` would, without printing it, and list the characters
+/// the printer can't print by their line and column in the file. The prehook isn't run,
+/// the file is checked as it stands.
+pub fn validate_file(arg: KonanFile) -> anyhow::Result<Vec<UnprintableChar>> {
+    let (file_path, content) = read_file(&arg)?;
+    validate_content(&arg, &PrintSetup::current(), &file_path, &content)
+}
+
+fn validate_content(
+    arg: &KonanFile,
+    setup: &PrintSetup,
+    file_path: &std::path::Path,
+    content: &str,
+) -> anyhow::Result<Vec<UnprintableChar>> {
+    let found = render_file(arg, setup, file_path, content)?.validate();
+    Ok(locate_in_source(found, content))
+}
+
+/// Move each of `found`, located in the rendered and wrapped lines, to its line and column
+/// in `source`. Rendering keeps the text in order, so each is matched to the next
+/// occurrence of its character. One the markup produced, like an entity, is placed at the
+/// previous match.
+fn locate_in_source(found: Vec<UnprintableChar>, source: &str) -> Vec<UnprintableChar> {
+    let mut remaining = source.lines().enumerate().flat_map(|(line, text)| {
+        text.chars()
+            .enumerate()
+            .map(move |(column, ch)| (line + 1, column + 1, ch))
+    });
+    let mut last = (1, 1);
+    found
+        .into_iter()
+        .map(|unprintable| {
+            let mut search = remaining.clone();
+            if let Some((line, column, _)) = search.find(|(_, _, ch)| *ch == unprintable.ch) {
+                last = (line, column);
+                remaining = search;
+            }
+            UnprintableChar {
+                line: last.0,
+                column: last.1,
+                ..unprintable
+            }
+        })
+        .collect()
+}
+
+fn run_prehook(arg: &KonanFile) -> anyhow::Result<()> {
+    if let Some((prehook_command, profile)) = arg
+        .prehook_command
+        .clone()
+        .zip(arg.prehook_command_arg.clone())
+    {
+        prehook_command.run_command(printer_files_dir_path()?.join(&arg.name), &profile)?;
+    }
+    Ok(())
+}
+
+/// Read the file from the printer files directory
+fn read_file(arg: &KonanFile) -> anyhow::Result<(PathBuf, String)> {
+    let file_path = printer_files_dir_path()?.join(&arg.name);
+    let content = std::fs::read_to_string(&file_path)
+        .with_context(|| format!("Failed to read pulse file '{}'", file_path.display()))?;
+    Ok((file_path, content))
+}
+
+/// The lines `print_file` sends for `content`. Plain text is laid out in the builder here,
+/// where printing writes it straight to the printer.
+fn render_file(
+    arg: &KonanFile,
+    setup: &PrintSetup,
+    file_path: &std::path::Path,
+    content: &str,
+) -> anyhow::Result<RongtaPrinter> {
+    let mut builder = file_builder(arg, setup)?;
+    if arg.line_numbers {
+        for line in line_numbered(content, builder.cpl())? {
+            builder.add_content(&line)?;
+            builder.new_line();
+        }
+        return Ok(builder);
+    }
+    match file_path.extension().and_then(|e| e.to_str()) {
+        Some("md") => {
+            let mut interpreter = MarkdownInterpreter::new(builder);
+            interpreter.set_split_on_rule(arg.split_on_rule);
+            interpreter.render(content)
+        }
+        Some("txt") => {
+            builder.add_content(content)?;
+            Ok(builder)
+        }
+        _ => bail!("Supported extensions are markdown and text"),
+    }
+}
+
+/// The builder a file prints with, compacted and headed with its title when asked for
+fn file_builder(arg: &KonanFile, setup: &PrintSetup) -> anyhow::Result<RongtaPrinter> {
+    let mut builder = setup.builder(arg.cut, arg.cpl);
+    if arg.compact {
+        builder.compact_preset();
+    }
+    if let Some(title) = &arg.title {
+        blueprint::add_title_header(&mut builder, title)?;
+    }
+    Ok(builder)
+}
+
+fn print_data(
+    arg: KonanData,
+    setup: &PrintSetup,
+    driver: SupportedDriver,
+) -> anyhow::Result<PrintReport> {
+    let file_path = printer_files_dir_path()?.join(&arg.name);
+    let content = std::fs::read_to_string(&file_path)
+        .with_context(|| format!("Failed to read data file '{}'", file_path.display()))?;
+    let table = match file_path.extension().and_then(|e| e.to_str()) {
+        Some("json") => Table::from_json(&content),
+        Some("csv") => Table::from_csv(&content),
+        _ => bail!("Supported data files are json and csv"),
+    }
+    .with_context(|| format!("Failed to parse data file '{}'", file_path.display()))?;
+    TableTemplateBuilder::new(setup.builder(arg.cut, arg.cpl), table).print(driver)
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use chrono::FixedOffset;
+
+    /// A setup that doesn't read the config or flags, with a fixed box pattern and UTC
+    pub(crate) fn setup() -> PrintSetup {
+        PrintSetup {
+            builder: |cut, cpl| RongtaPrinter::new(cut).with_cpl(cpl),
+            pattern: || {
+                Ok(BoxPattern {
+                    top: format!(".{}.", "-".repeat(46)),
+                    row: format!("|{}|", " ".repeat(46)),
+                    bottom: format!("'{}'", "-".repeat(46)),
+                })
+            },
+            timezone: BannerTimezone::Fixed(FixedOffset::east_opt(0).unwrap()),
+        }
+    }
+
+    mod catch_panic {
+        use super::*;
+
+        #[test]
+        fn panicking_job_does_not_stop_the_next() {
+            let jobs: Vec<Box<dyn FnOnce() -> anyhow::Result<u32>>> =
+                vec![Box::new(|| panic!("bad job")), Box::new(|| Ok(2))];
+            let results: Vec<_> = jobs.into_iter().map(catch_panic).collect();
+            assert_eq!(
+                results[0].as_ref().unwrap_err().to_string(),
+                "Print task panicked: bad job"
+            );
+            assert_eq!(results[1].as_ref().unwrap(), &2);
+        }
+    }
+
+    mod settle_job {
+        use super::*;
+        use crate::spool::tests::{spool, text_task};
+        use rongta::printer::DumpDriver;
+
+        fn offline_failure(task: &PrintTask) -> anyhow::Result<PrintReport> {
+            print_task(
+                task.clone(),
+                &setup(),
+                SupportedDriver::Dump(DumpDriver::failing_after(0)),
+            )
+        }
+
+        #[test]
+        fn offline_job_is_spooled_then_printed_once_reachable() {
+            let spool = spool("settle-offline");
+            let task = text_task("hello from the spool");
+
+            let err =
+                settle_job(offline_failure(&task), Some((&spool, task)), || false).unwrap_err();
+            assert!(err.downcast_ref::<Spooled>().is_some());
+            assert!(!spool.is_empty().unwrap());
+
+            let online = DumpDriver::new();
+            for task in spool.take_all().unwrap() {
+                print_task(task, &setup(), SupportedDriver::Dump(online.clone())).unwrap();
+            }
+            assert!(spool.is_empty().unwrap());
+            let bytes = online.bytes();
+            assert!(bytes.windows(20).any(|w| w == b"hello from the spool"));
+        }
+
+        #[test]
+        fn reachable_printer_fails_without_spooling() {
+            let spool = spool("settle-reachable");
+            let task = text_task("hello");
+            let err =
+                settle_job(offline_failure(&task), Some((&spool, task)), || true).unwrap_err();
+            assert!(err.downcast_ref::<Spooled>().is_none());
+            assert!(spool.is_empty().unwrap());
+        }
+
+        #[test]
+        fn routed_job_is_not_spooled() {
+            let task = text_task("hello");
+            let err = settle_job(offline_failure(&task), None, || {
+                panic!("reachability is only checked for spoolable jobs")
+            })
+            .unwrap_err();
+            assert!(err.downcast_ref::<Spooled>().is_none());
+        }
+
+        #[test]
+        fn success_is_passed_through() {
+            let spool = spool("settle-success");
+            let report = PrintReport::default();
+            let settled = settle_job(Ok(report), Some((&spool, text_task("hi"))), || {
+                panic!("reachability is only checked after a failure")
+            });
+            assert_eq!(settled.unwrap(), report);
+            assert!(spool.is_empty().unwrap());
+        }
+    }
+
+    mod idle_timers {
+        use super::*;
+
+        #[test]
+        fn wakes_after_the_threshold() {
+            let start = Instant::now();
+            let mut idle = IdleTimers::new(Some(Duration::from_secs(60)));
+            assert!(idle.should_wake("printer:9100", start));
+            idle.printed("printer:9100".to_string(), start);
+            assert!(!idle.should_wake("printer:9100", start + Duration::from_secs(60)));
+            assert!(idle.should_wake("printer:9100", start + Duration::from_secs(61)));
+            assert!(idle.should_wake("other:9100", start));
+        }
+
+        #[test]
+        fn never_wakes_without_a_threshold() {
+            let idle = IdleTimers::new(None);
+            assert!(!idle.should_wake("printer:9100", Instant::now()));
+        }
+
+        #[test]
+        fn only_network_printers_are_timed() {
+            let network = SupportedDriver::Network("printer".to_string(), 9100);
+            assert_eq!(network_key(&network), Some("printer:9100".to_string()));
+            assert_eq!(network_key(&SupportedDriver::Usb(1, 2)), None);
+        }
+    }
+
+    mod validate_content {
+        use super::*;
+
+        fn markdown_file() -> KonanFile {
+            KonanFile {
+                cut: false,
+                cpl: 48,
+                name: "notes.md".to_string(),
+                prehook_command: None,
+                prehook_command_arg: None,
+                rows: None,
+                line_numbers: false,
+                split_on_rule: false,
+                compact: false,
+                title: None,
+            }
+        }
+
+        fn unprintable(content: &str) -> Vec<UnprintableChar> {
+            let file = markdown_file();
+            validate_content(&file, &setup(), std::path::Path::new(&file.name), content).unwrap()
+        }
+
+        #[test]
+        fn reports_each_unprintable_char() {
+            let found = unprintable("# Menu\n\nSoup \u{4e2d}\n");
+            assert_eq!(
+                found,
+                [UnprintableChar {
+                    line: 3,
+                    column: 6,
+                    ch: '\u{4e2d}'
+                }]
+            );
+        }
+
+        #[test]
+        fn reports_the_line_in_the_file_not_the_wrapped_line() {
+            let long = "soup ".repeat(20);
+            let found = unprintable(&format!(
+                "**Menu**\n\n{long}\u{4e2d} \u{4e2d}\n\nbread \u{5b57}\n"
+            ));
+            let locations: Vec<(usize, usize)> = found.iter().map(|u| (u.line, u.column)).collect();
+            assert_eq!(locations, [(3, 101), (3, 103), (5, 7)]);
+        }
+
+        #[test]
+        fn clean_document_reports_none() {
+            assert!(unprintable("# Menu\n\n\u{201c}Soup\u{201d} -- caf\u{e9}\n").is_empty());
+        }
+    }
+
+    mod try_queue {
+        use super::*;
+
+        fn job(text: &str) -> QueuedTask {
+            let task = PrintTask::Text(DirectPrintOut {
+                cut: true,
+                cpl: 48,
+                content: text.to_string(),
+                rows: None,
+                printer: None,
+            });
+            (task, None, None)
+        }
+
+        #[test]
+        fn full_queue_turns_jobs_away_until_one_drains() {
+            let (tx, mut rx) = mpsc::channel::<QueuedTask>(2);
+            try_queue(&tx, job("first")).unwrap();
+            try_queue(&tx, job("second")).unwrap();
+            let full = try_queue(&tx, job("third")).unwrap_err();
+            assert_eq!(
+                full.to_string(),
+                "Print queue is full, 2 jobs are waiting for the printer"
+            );
+
+            // The worker picks up the in-flight job, freeing a slot
+            assert!(rx.try_recv().is_ok());
+            try_queue(&tx, job("third")).unwrap();
+            assert!(try_queue(&tx, job("fourth")).is_err());
+        }
+    }
+
+    mod driver_for {
+        use super::*;
+
+        #[test]
+        fn output_path_is_a_file_driver() {
+            let path = PathBuf::from("job.bin");
+            assert!(matches!(
+                driver_for(Some(&path)),
+                SupportedDriver::File(file) if file == path
+            ));
+            assert!(matches!(
+                driver_for(None),
+                SupportedDriver::Usb(VENDOR_ID, PRODUCT_ID)
+            ));
+        }
+
+        #[test]
+        fn template_is_written_to_the_file() {
+            let path =
+                std::env::temp_dir().join(format!("konan-output-{}.bin", std::process::id()));
+            print_task(
+                PrintTask::BoxTemplate(BoxTemplate {
+                    cut: true,
+                    cpl: 48,
+                    height: Some(4),
+                    lined: false,
+                    checkboxes: false,
+                    banner: None,
+                    date: None,
+                    columns: Vec::new(),
+                    ledger: Vec::new(),
+                    printer: None,
+                }),
+                &setup(),
+                SupportedDriver::File(path.clone()),
+            )
+            .unwrap();
+            let bytes = std::fs::read(&path).unwrap();
+            std::fs::remove_file(&path).unwrap();
+
+            let init = rongta::printer::DumpDriver::new();
+            let mut printer = rongta::printer::AnyPrinter::from_existing(init.clone());
+            printer.begin_job().unwrap();
+            printer.print().unwrap();
+            assert!(!init.bytes().is_empty());
+            assert!(bytes.starts_with(&init.bytes()));
+            assert!(bytes.len() > init.bytes().len());
+        }
+    }
+
+    mod print_calendar {
+        use super::*;
+        use rongta::printer::DumpDriver;
+
+        fn printed(week: bool) -> String {
+            let driver = DumpDriver::new();
+            let task = CalendarTemplate {
+                cut: false,
+                cpl: 48,
+                date: "2026-10-15T12:00:00Z".parse().unwrap(),
+                week,
+                lined: false,
+                weeks_per_page: None,
+            };
+            print_calendar(task, &setup(), SupportedDriver::Dump(driver.clone())).unwrap();
+            String::from_utf8_lossy(&driver.bytes()).into_owned()
+        }
+
+        #[test]
+        fn prints_the_month_of_the_date() {
+            assert!(printed(false).contains("October 2026"));
+        }
+
+        #[test]
+        fn week_starts_on_the_date() {
+            assert!(printed(true).contains("Week of Oct 15, 2026"));
+        }
+    }
+
+    mod qr_code_builder {
+        use super::*;
+        use rongta::elements::Justify;
+
+        fn task(caption: Option<&str>) -> QrCode {
+            QrCode {
+                cut: true,
+                cpl: 48,
+                data: "https://example.com".to_string(),
+                size: None,
+                caption: caption.map(str::to_string),
+            }
+        }
+
+        fn qr_count(builder: &RongtaPrinter) -> usize {
+            builder
+                .lines()
+                .iter()
+                .filter(|line| line.qr_code.is_some())
+                .count()
+        }
+
+        #[test]
+        fn adds_caption_beneath_code() {
+            let builder = qr_code_builder(task(Some("Wifi")), &setup()).unwrap();
+            assert_eq!(qr_count(&builder), 1);
+            let lines = builder.lines();
+            assert_eq!(lines.len(), 3);
+            let caption: String = lines[1].chars.iter().map(|sc| sc.ch).collect();
+            assert_eq!(caption, "Wifi");
+            assert_eq!(lines[1].justify_content, Justify::Center);
+        }
+
+        #[test]
+        fn code_only_without_caption() {
+            let builder = qr_code_builder(task(None), &setup()).unwrap();
+            assert_eq!(qr_count(&builder), 1);
+            assert!(builder.lines().iter().all(|line| line.chars.is_empty()));
+        }
+    }
+
+    mod timestamp_builder {
+        use super::*;
+        use chrono::Utc;
+
+        fn task(format: Option<&str>) -> Timestamp {
+            Timestamp {
+                cut: true,
+                cpl: 48,
+                time: Utc::now(),
+                format: format.map(str::to_string),
+            }
+        }
+
+        fn text(builder: &RongtaPrinter) -> String {
+            builder
+                .lines()
+                .iter()
+                .flat_map(|line| line.chars.iter().map(|sc| sc.ch))
+                .collect()
+        }
+
+        #[test]
+        fn contains_the_formatted_date() {
+            let task = task(None);
+            let expected = task.time.format(DEFAULT_TIMESTAMP_FORMAT).to_string();
+            let builder = timestamp_builder(task, &setup()).unwrap();
+            assert_eq!(text(&builder), expected);
+        }
+
+        #[test]
+        fn uses_the_given_format() {
+            let task = task(Some("%Y-%m-%d"));
+            let expected = task.time.format("%Y-%m-%d").to_string();
+            assert_eq!(text(&timestamp_builder(task, &setup()).unwrap()), expected);
+        }
+
+        #[test]
+        fn rejects_an_invalid_format() {
+            assert!(timestamp_builder(task(Some("%Q")), &setup()).is_err());
+        }
+    }
+}
@@ -0,0 +1,132 @@
+use anyhow::{Context, Result};
+use cli_shared::PrintTask;
+use std::{
+    fmt::{self, Display},
+    path::PathBuf,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// Context on the error of a job that failed while the printer was offline and was
+/// written to the spool at this path. It prints once the printer is back, so callers
+/// should report it as spooled rather than failed.
+#[derive(Debug)]
+pub struct Spooled(pub PathBuf);
+impl Display for Spooled {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Printer offline, job spooled to '{}'", self.0.display())
+    }
+}
+
+/// Directory of print jobs that failed because the printer was offline, kept as JSON until
+/// they can be printed
+#[derive(Debug, Clone)]
+pub struct Spool {
+    dir: PathBuf,
+}
+
+impl Spool {
+    pub fn new(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+
+    /// Write `task` to the spool, named so jobs drain in the order they were spooled
+    pub fn push(&self, task: &PrintTask) -> Result<PathBuf> {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .context("System clock is before the unix epoch")?
+            .as_nanos();
+        let path = self.dir.join(format!("{nanos:039}.json"));
+        let json = serde_json::to_string(task).context("Failed to serialize print task")?;
+        std::fs::write(&path, json)
+            .with_context(|| format!("Failed to write spool file '{}'", path.display()))?;
+        Ok(path)
+    }
+
+    fn files(&self) -> Result<Vec<PathBuf>> {
+        let entries = std::fs::read_dir(&self.dir)
+            .with_context(|| format!("Failed to read spool at '{}'", self.dir.display()))?;
+        let mut files: Vec<PathBuf> = entries
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+            .collect();
+        files.sort();
+        Ok(files)
+    }
+
+    pub fn is_empty(&self) -> Result<bool> {
+        Ok(self.files()?.is_empty())
+    }
+
+    /// Remove and return every spooled task, oldest first. Files that can't be parsed are
+    /// left in place and logged.
+    pub fn take_all(&self) -> Result<Vec<PrintTask>> {
+        let mut tasks = Vec::new();
+        for path in self.files()? {
+            let parsed = std::fs::read_to_string(&path)
+                .map_err(anyhow::Error::from)
+                .and_then(|json| serde_json::from_str(&json).map_err(anyhow::Error::from));
+            match parsed {
+                Ok(task) => {
+                    std::fs::remove_file(&path).with_context(|| {
+                        format!("Failed to remove spool file '{}'", path.display())
+                    })?;
+                    tasks.push(task);
+                }
+                Err(e) => log::error!("Skipping unreadable spool file '{}': {e:#}", path.display()),
+            }
+        }
+        Ok(tasks)
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use cli_shared::tasks::DirectPrintOut;
+
+    pub(crate) fn spool(name: &str) -> Spool {
+        let dir = std::env::temp_dir().join(format!("konan-spool-{}-{name}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        Spool::new(dir)
+    }
+
+    pub(crate) fn text_task(content: &str) -> PrintTask {
+        PrintTask::Text(DirectPrintOut {
+            cut: true,
+            cpl: 48,
+            content: content.to_string(),
+            rows: None,
+            printer: None,
+        })
+    }
+
+    mod take_all {
+        use super::*;
+
+        #[test]
+        fn drains_oldest_first() {
+            let spool = spool("order");
+            spool.push(&text_task("first")).unwrap();
+            spool.push(&text_task("second")).unwrap();
+            let contents: Vec<String> = spool
+                .take_all()
+                .unwrap()
+                .into_iter()
+                .map(|task| match task {
+                    PrintTask::Text(arg) => arg.content,
+                    other => panic!("unexpected task {other:?}"),
+                })
+                .collect();
+            assert_eq!(contents, ["first", "second"]);
+        }
+
+        #[test]
+        fn leaves_unreadable_files() {
+            let spool = spool("corrupt");
+            std::fs::write(spool.dir.join("0.json"), "not json").unwrap();
+            assert!(spool.take_all().unwrap().is_empty());
+            assert!(!spool.is_empty().unwrap());
+        }
+    }
+}
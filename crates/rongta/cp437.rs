@@ -1,5 +1,5 @@
 use anyhow::{Result, bail};
-use std::{collections::HashSet, sync::LazyLock};
+use std::{borrow::Cow, collections::HashSet, sync::LazyLock};
 
 /// Extended CP437 characters (non-ASCII) for O(1) lookup
 static EXTENDED_CP437: LazyLock<HashSet<char>> = LazyLock::new(|| {
@@ -36,6 +36,24 @@ pub fn normalize_char(ch: char) -> Option<char> {
     }
 }
 
+/// Normalize Unicode typographic characters in `text` to their ASCII equivalents,
+/// including one-to-many replacements such as the ellipsis (`…` → `...`).
+/// Borrows `text` when nothing needs replacing.
+pub fn normalize_to_ascii(text: &str) -> Cow<'_, str> {
+    let needs_normalizing = |ch: char| ch == '\u{2026}' || normalize_char(ch).is_some();
+    if !text.chars().any(needs_normalizing) {
+        return Cow::Borrowed(text);
+    }
+    let mut normalized = String::with_capacity(text.len());
+    for ch in text.chars() {
+        match ch {
+            '\u{2026}' => normalized.push_str("..."),
+            _ => normalized.push(normalize_char(ch).unwrap_or(ch)),
+        }
+    }
+    Cow::Owned(normalized)
+}
+
 /// Check if a character is valid in CP437.
 /// Uses a fast path for ASCII characters and HashSet lookup for extended characters.
 fn is_cp437_char(ch: char) -> bool {
@@ -113,6 +131,38 @@ mod tests {
         }
     }
 
+    mod normalize_to_ascii {
+        use super::*;
+
+        #[test]
+        fn expands_ellipsis() {
+            assert_eq!(normalize_to_ascii("Wait\u{2026}"), "Wait...");
+        }
+
+        #[test]
+        fn normalizes_quotes_and_dashes() {
+            assert_eq!(
+                normalize_to_ascii("\u{201C}It\u{2019}s\u{201D} \u{2013} fine \u{2014} really"),
+                "\"It's\" - fine - really"
+            );
+        }
+
+        #[test]
+        fn borrows_plain_ascii() {
+            assert!(matches!(normalize_to_ascii("plain text"), Cow::Borrowed(_)));
+        }
+
+        #[test]
+        fn keeps_cp437_extended() {
+            assert_eq!(normalize_to_ascii("café ░▒▓"), "café ░▒▓");
+        }
+
+        #[test]
+        fn handles_empty_string() {
+            assert_eq!(normalize_to_ascii(""), "");
+        }
+    }
+
     mod cp437_char_only {
         use super::*;
 
@@ -1,6 +1,8 @@
 use crate::{cp437, printer::AnyPrinter};
-use anyhow::Result;
-use escpos::utils::JustifyMode;
+use anyhow::{Result, ensure};
+use escpos::utils::{JustifyMode, QRCodeCorrectionLevel, QRCodeModel, QRCodeOption, UnderlineMode};
+use std::ops::RangeInclusive;
+use unicode_width::UnicodeWidthChar;
 
 pub trait ToPrintCommand {
     fn to_print_command(&self, printer: &mut AnyPrinter) -> Result<()>;
@@ -12,17 +14,39 @@ pub enum TextSize {
     Medium,
     Large,
     ExtraLarge,
+    /// Double width at normal height
+    Wide,
+    /// Double height at normal width
+    Tall,
 }
 impl TextSize {
     /// Returns the visual width of a character with this text size.
-    /// Medium = 1 column, Large = 2 columns, ExtraLarge = 3 columns.
+    /// Medium and Tall = 1 column, Large and Wide = 2 columns, ExtraLarge = 3 columns.
     pub fn char_width(&self) -> usize {
         match self {
-            TextSize::Medium => 1,
-            TextSize::Large => 2,
+            TextSize::Medium | TextSize::Tall => 1,
+            TextSize::Large | TextSize::Wide => 2,
             TextSize::ExtraLarge => 3,
         }
     }
+
+    /// How many normal lines tall a character with this text size is
+    pub fn char_height(&self) -> usize {
+        match self {
+            TextSize::Medium | TextSize::Wide => 1,
+            TextSize::Large | TextSize::Tall => 2,
+            TextSize::ExtraLarge => 3,
+        }
+    }
+
+    /// Returns the largest size that keeps `char_count` characters within `cpl` columns.
+    /// Falls back to Medium when nothing larger fits.
+    pub fn largest_fitting(char_count: usize, cpl: usize) -> TextSize {
+        [TextSize::ExtraLarge, TextSize::Large]
+            .into_iter()
+            .find(|size| char_count * size.char_width() <= cpl)
+            .unwrap_or_default()
+    }
 }
 impl ToPrintCommand for TextSize {
     fn to_print_command(&self, printer: &mut AnyPrinter) -> Result<()> {
@@ -30,11 +54,22 @@ impl ToPrintCommand for TextSize {
             TextSize::Medium => printer.reset_size()?,
             TextSize::Large => printer.size(2, 2)?,
             TextSize::ExtraLarge => printer.size(3, 3)?,
+            TextSize::Wide => printer.size(2, 1)?,
+            TextSize::Tall => printer.size(1, 2)?,
         };
         Ok(())
     }
 }
 
+/// How long each page is when a job is split into pages
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PageLength {
+    /// A fixed number of printed lines
+    Rows(u32),
+    /// Roughly this much paper, estimated from the height of each line
+    Inches(f32),
+}
+
 #[derive(Default, Clone, Copy, Debug, PartialEq)]
 pub enum Justify {
     #[default]
@@ -53,28 +88,210 @@ impl ToPrintCommand for Justify {
     }
 }
 
+/// How a job, or a page of one, ends once its lines are sent
+#[derive(Default, Clone, Copy, Debug, PartialEq)]
+pub enum CutMode {
+    /// Feed the last line past the blade and cut
+    #[default]
+    Full,
+    /// Flush without cutting
+    None,
+}
+impl From<bool> for CutMode {
+    fn from(cut: bool) -> Self {
+        match cut {
+            true => CutMode::Full,
+            false => CutMode::None,
+        }
+    }
+}
+
+/// How pages are separated when printing with `rows` pagination.
+#[derive(Default, Clone, Copy, Debug, PartialEq)]
+pub enum PageSeparator {
+    /// Full cut, for printers with a cutter
+    #[default]
+    Cut,
+    /// Feed the given number of blank lines, for continuous paper without a cutter
+    FormFeed(u8),
+    /// Advance to the next black mark or label start (GS FF)
+    BlackMark,
+}
+impl ToPrintCommand for PageSeparator {
+    /// Emits the separator and sends the finished page to the printer. Every variant ends
+    /// in `print`/`print_cut`, which flush the driver, so each page emerges as soon as it
+    /// is separated rather than when the whole job completes.
+    fn to_print_command(&self, printer: &mut AnyPrinter) -> Result<()> {
+        match self {
+            PageSeparator::Cut => printer.print_cut(),
+            PageSeparator::FormFeed(lines) => {
+                printer.feeds(*lines)?;
+                printer.print()
+            }
+            PageSeparator::BlackMark => {
+                printer.custom(&[0x1D, 0x0C])?;
+                printer.print()
+            }
+        }
+    }
+}
+
+/// Character font. Font B is smaller than the default Font A, so text takes less paper.
+#[derive(Default, Clone, Copy, Debug, PartialEq)]
+pub enum Font {
+    #[default]
+    A,
+    B,
+}
+impl Font {
+    /// Height of a character at normal size in dots
+    pub fn char_height_dots(&self) -> usize {
+        match self {
+            Font::A => 24,
+            Font::B => 17,
+        }
+    }
+}
+impl ToPrintCommand for Font {
+    fn to_print_command(&self, printer: &mut AnyPrinter) -> Result<()> {
+        printer.font(match self {
+            Font::A => escpos::utils::Font::A,
+            Font::B => escpos::utils::Font::B,
+        })
+    }
+}
+
+/// Print direction. Rotated text runs along the paper, so a line can be as long as the page.
+#[derive(Default, Clone, Copy, Debug, PartialEq)]
+pub enum Rotation {
+    #[default]
+    None,
+    /// 90° clockwise (ESC V)
+    Cw90,
+}
+impl ToPrintCommand for Rotation {
+    fn to_print_command(&self, printer: &mut AnyPrinter) -> Result<()> {
+        printer.flip(*self == Rotation::Cw90)
+    }
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Default)]
 pub struct FormatState {
     pub text_size: TextSize,
     pub is_bold: bool,
+    pub is_underlined: bool,
+    /// White on black
+    pub is_reversed: bool,
 }
 impl ToPrintCommand for FormatState {
     fn to_print_command(&self, printer: &mut AnyPrinter) -> Result<()> {
         printer.bold(self.is_bold)?;
+        printer.underline(match self.is_underlined {
+            true => UnderlineMode::Single,
+            false => UnderlineMode::None,
+        })?;
+        printer.reverse(self.is_reversed)?;
         self.text_size.to_print_command(printer)
     }
 }
 
+/// Columns `ch` takes at Medium size: 2 for East Asian wide and full-width characters,
+/// 0 for combining marks and 1 otherwise
+pub fn char_columns(ch: char) -> usize {
+    ch.width().unwrap_or(1)
+}
+
 #[derive(Clone, Debug)]
 pub struct StyledChar {
     pub ch: char,
     pub state: FormatState,
 }
+impl StyledChar {
+    /// Columns the character takes, accounting for both its East Asian width and text size
+    pub fn columns(&self) -> usize {
+        char_columns(self.ch) * self.state.text_size.char_width()
+    }
+
+    /// Whitespace that leaves the paper blank, unlike an underlined or reversed space
+    pub fn is_blank(&self) -> bool {
+        self.ch.is_whitespace() && !self.state.is_underlined && !self.state.is_reversed
+    }
+}
 impl ToPrintCommand for StyledChar {
     fn to_print_command(&self, printer: &mut AnyPrinter) -> Result<()> {
-        // Normalize typographic characters to ASCII equivalents before CP437 validation
-        let normalized_ch = cp437::normalize_char(self.ch).unwrap_or(self.ch);
-        let ascii_content = cp437::cp437_char_only(normalized_ch)?;
-        printer.write(&ascii_content.to_string())
+        printer.write(&printable_char(self.ch)?.to_string())
+    }
+}
+
+/// `ch` as the printer receives it, erroring when the code page can't show it
+pub(crate) fn printable_char(ch: char) -> Result<char> {
+    // Normalize typographic characters to ASCII equivalents before CP437 validation
+    cp437::cp437_char_only(cp437::normalize_char(ch).unwrap_or(ch))
+}
+
+/// Module sizes (dots per QR module) the printer accepts
+pub const QR_SIZE_RANGE: RangeInclusive<u8> = 1..=15;
+
+/// A QR code printed natively by the printer
+#[derive(Clone, Debug, PartialEq)]
+pub struct QrCode {
+    pub data: String,
+    /// Dots per module, within `QR_SIZE_RANGE`
+    pub size: u8,
+}
+impl QrCode {
+    pub const DEFAULT_SIZE: u8 = 6;
+
+    pub fn new(data: &str, size: u8) -> Result<Self> {
+        ensure!(!data.is_empty(), "QR code data must not be empty");
+        ensure!(
+            QR_SIZE_RANGE.contains(&size),
+            "QR code size must be between {} and {}, got {size}",
+            QR_SIZE_RANGE.start(),
+            QR_SIZE_RANGE.end()
+        );
+        Ok(Self {
+            data: data.to_string(),
+            size,
+        })
+    }
+}
+impl ToPrintCommand for QrCode {
+    fn to_print_command(&self, printer: &mut AnyPrinter) -> Result<()> {
+        printer.qrcode_option(
+            &self.data,
+            QRCodeOption::new(QRCodeModel::Model2, self.size, QRCodeCorrectionLevel::M),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::printer::{PrinterCommand, RecordingPrinter};
+
+    mod text_size {
+        use super::*;
+
+        fn size_command(size: TextSize) -> Vec<PrinterCommand> {
+            let recorder = RecordingPrinter::new();
+            size.to_print_command(&mut AnyPrinter::from(recorder.clone()))
+                .unwrap();
+            recorder.commands()
+        }
+
+        #[test]
+        fn wide_and_tall_scale_one_dimension() {
+            assert_eq!(TextSize::Wide.char_width(), 2);
+            assert_eq!(TextSize::Wide.char_height(), 1);
+            assert_eq!(TextSize::Tall.char_width(), 1);
+            assert_eq!(TextSize::Tall.char_height(), 2);
+        }
+
+        #[test]
+        fn wide_and_tall_emit_their_size() {
+            assert_eq!(size_command(TextSize::Wide), [PrinterCommand::Size(2, 1)]);
+            assert_eq!(size_command(TextSize::Tall), [PrinterCommand::Size(1, 2)]);
+        }
     }
 }
@@ -0,0 +1,32 @@
+use std::fmt;
+
+/// Errors callers may want to react to, rather than only log.
+/// They travel inside `anyhow::Error`, so use `downcast_ref::<RongtaError>()` to inspect them.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RongtaError {
+    /// The connection dropped mid-job and could not be re-established.
+    /// `lines_printed` is how many lines reached the printer before the failure.
+    Disconnected { lines_printed: usize },
+    /// The printer reported its cover open at a page boundary, so the job stopped before
+    /// sending more. `lines_printed` is how many lines reached the printer before that.
+    CoverOpen { lines_printed: usize },
+}
+
+impl std::error::Error for RongtaError {}
+
+impl fmt::Display for RongtaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RongtaError::Disconnected { lines_printed } => write!(
+                f,
+                "Printer disconnected mid-job after {} lines were printed",
+                lines_printed
+            ),
+            RongtaError::CoverOpen { lines_printed } => write!(
+                f,
+                "Printer cover is open, stopped after {} lines were printed",
+                lines_printed
+            ),
+        }
+    }
+}
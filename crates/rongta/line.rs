@@ -1,41 +1,104 @@
-use crate::{CPL, elements};
+use crate::elements;
+
+/// Consecutive characters sharing a format state, so the state is sent once for all of them
+pub type StyleRun = (elements::FormatState, String);
 
 #[derive(Default, Debug)]
 pub struct Line {
     pub chars: Vec<elements::StyledChar>,
     pub justify_content: elements::Justify,
+    /// Printed in place of the line's characters
+    pub qr_code: Option<elements::QrCode>,
+    /// Prints nothing, instead the page is cut here
+    pub page_break: bool,
     cached_width: usize,
 }
 impl Line {
     pub fn new(chars: Vec<elements::StyledChar>, justify_content: elements::Justify) -> Self {
-        let cached_width = chars.iter().map(|sc| sc.state.text_size.char_width()).sum();
+        let cached_width = chars.iter().map(elements::StyledChar::columns).sum();
         Self {
             chars,
             justify_content,
+            qr_code: None,
+            page_break: false,
             cached_width,
         }
     }
+
+    /// A centered line holding only a QR code
+    pub fn with_qr_code(qr_code: elements::QrCode) -> Self {
+        Self {
+            justify_content: elements::Justify::Center,
+            qr_code: Some(qr_code),
+            ..Default::default()
+        }
+    }
+    /// Columns the line takes up, accounting for text size
+    pub fn visual_width(&self) -> usize {
+        self.cached_width
+    }
+
+    /// Whether the line prints as empty paper: only blank whitespace and no QR code
+    pub fn is_blank(&self) -> bool {
+        !self.page_break && self.qr_code.is_none() && self.chars.iter().all(|sc| sc.is_blank())
+    }
+
+    /// Whether nothing has been added since the line was started by `new_line`
+    pub fn is_unstarted(&self) -> bool {
+        !self.page_break && self.qr_code.is_none() && self.chars.is_empty()
+    }
+
+    /// A line marking where the page is cut
+    pub fn page_break() -> Self {
+        Self {
+            page_break: true,
+            ..Default::default()
+        }
+    }
+
+    /// The line's characters without trailing blank whitespace, which would otherwise
+    /// shift centered and right-justified lines off their visual position. Underlined and
+    /// reversed spaces print, so they are kept.
+    pub fn trimmed_chars(&self) -> &[elements::StyledChar] {
+        let end = self
+            .chars
+            .iter()
+            .rposition(|sc| !sc.is_blank())
+            .map_or(0, |i| i + 1);
+        &self.chars[..end]
+    }
+
+    /// The line's characters grouped into runs of the same format state
+    pub fn compress(&self) -> Vec<StyleRun> {
+        style_runs(&self.chars)
+    }
+
+    /// `compress` for what printing sends, the characters without trailing whitespace
+    pub fn trimmed_runs(&self) -> Vec<StyleRun> {
+        style_runs(self.trimmed_chars())
+    }
+
     /// Find the character index where we should soft-wrap (at whitespace).
-    /// Returns None if the line fits within CPL or no whitespace is found.
-    fn find_wrap_point(&self) -> Option<usize> {
+    /// Returns None if the line fits within `cpl` or no whitespace is found.
+    fn find_wrap_point(&self, cpl: usize) -> Option<usize> {
         log::trace!(
             "Finding wrap point for {:?}",
             self.chars.iter().map(|sc| sc.ch).collect::<Vec<char>>()
         );
 
-        // Find the last whitespace before we exceed CPL visual width
+        // Find the last whitespace before we exceed the visual width
         let mut width = 0;
         let mut last_whitespace_idx: Option<usize> = None;
 
         for (i, sc) in self.chars.iter().enumerate() {
-            if sc.ch.is_whitespace() && width <= CPL as usize {
+            if sc.ch.is_whitespace() && width <= cpl {
                 last_whitespace_idx = Some(i);
             }
 
-            width += sc.state.text_size.char_width();
+            width += sc.columns();
 
-            // Once we've exceeded CPL, stop looking
-            if width > CPL as usize {
+            // Once we've exceeded the width, stop looking
+            if width > cpl {
                 break;
             }
         }
@@ -44,15 +107,15 @@ impl Line {
     }
 
     /// Add a character to the line, and return a new line if the line is full.
-    /// Uses visual width (accounting for text size) to determine when to wrap.
-    pub fn add_char(&mut self, sch: elements::StyledChar) -> Option<Line> {
-        let char_width = sch.state.text_size.char_width();
+    /// Uses visual width (accounting for text size) to determine when to wrap at `cpl` columns.
+    pub fn add_char(&mut self, sch: elements::StyledChar, cpl: usize) -> Option<Line> {
+        let char_width = sch.columns();
         self.cached_width += char_width;
         self.chars.push(sch);
-        if self.cached_width <= CPL as usize {
+        if self.cached_width <= cpl {
             return None;
         }
-        let remainder = if let Some(wrap_point) = self.find_wrap_point() {
+        let remainder = if let Some(wrap_point) = self.find_wrap_point(cpl) {
             log::trace!(
                 "Wrapping line at {} for {:?}",
                 wrap_point,
@@ -67,7 +130,86 @@ impl Line {
             log::trace!("No whitespace found, hard wrap for {:?}", self.chars.last());
             self.chars.split_off(self.chars.len() - 1)
         };
+        self.cached_width = self.chars.iter().map(elements::StyledChar::columns).sum();
 
-        (!remainder.is_empty()).then_some(Line::new(remainder, self.justify_content))
+        // Always start a new line, even when wrapping at a trailing space leaves nothing over,
+        // otherwise the next word is appended to the full line
+        Some(Line::new(remainder, self.justify_content))
+    }
+}
+
+fn style_runs(chars: &[elements::StyledChar]) -> Vec<StyleRun> {
+    let mut runs: Vec<StyleRun> = Vec::new();
+    for sc in chars {
+        match runs.last_mut() {
+            Some((state, text)) if *state == sc.state => text.push(sc.ch),
+            _ => runs.push((sc.state, sc.ch.to_string())),
+        }
+    }
+    runs
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::RongtaPrinter;
+
+    mod compress {
+        use super::*;
+
+        #[test]
+        fn uniform_line_is_one_run() {
+            let mut builder = RongtaPrinter::new(false);
+            builder.add_content("all the same").unwrap();
+            let runs = builder.lines()[0].compress();
+            assert_eq!(runs.len(), 1);
+            assert_eq!(runs[0].1, "all the same");
+        }
+
+        #[test]
+        fn each_style_change_starts_a_run() {
+            let mut builder = RongtaPrinter::new(false);
+            builder.add_content("plain ").unwrap();
+            builder.set_is_bold(true);
+            builder.add_content("bold").unwrap();
+            builder.set_is_bold(false);
+            builder.add_content(" plain").unwrap();
+            let runs = builder.lines()[0].compress();
+            let texts: Vec<&str> = runs.iter().map(|(_, text)| text.as_str()).collect();
+            assert_eq!(texts, ["plain ", "bold", " plain"]);
+            assert!(runs[1].0.is_bold);
+        }
+    }
+
+    mod trimmed_chars {
+        use super::*;
+
+        fn trimmed(builder: &RongtaPrinter) -> String {
+            builder.lines()[0]
+                .trimmed_chars()
+                .iter()
+                .map(|sc| sc.ch)
+                .collect()
+        }
+
+        #[test]
+        fn drops_trailing_plain_spaces() {
+            let mut builder = RongtaPrinter::new(false);
+            builder.add_content("text   ").unwrap();
+            assert_eq!(trimmed(&builder), "text");
+        }
+
+        #[test]
+        fn keeps_trailing_reversed_and_underlined_spaces() {
+            let mut builder = RongtaPrinter::new(false);
+            builder.add_content("text").unwrap();
+            builder.set_is_reversed(true);
+            builder.add_content("  ").unwrap();
+            builder.set_is_reversed(false);
+            builder.set_is_underlined(true);
+            builder.add_content("  ").unwrap();
+            builder.set_is_underlined(false);
+            builder.add_content("  ").unwrap();
+            assert_eq!(trimmed(&builder), "text    ");
+        }
     }
 }
@@ -1,23 +1,201 @@
+use crate::elements::CutMode;
 use anyhow::Result;
 use escpos::{
-    driver::{ConsoleDriver, NetworkDriver, UsbDriver},
+    driver::{ConsoleDriver, Driver, FileDriver, UsbDriver},
+    errors::PrinterError,
     printer::Printer,
-    utils::{JustifyMode, UnderlineMode},
+    utils::{
+        Font, JustifyMode, QRCodeOption, RealTimeStatusRequest, RealTimeStatusResponse,
+        UnderlineMode,
+    },
 };
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+pub use escpos::utils::DebugMode;
+
+mod recording;
+pub use recording::{PrinterCommand, RecordingPrinter};
+mod tcp;
+pub use tcp::TcpDriver;
+
+/// DLE EOT 2: transmit the offline cause status. Real-time, so it is answered straight away
+/// rather than queued behind buffered print data.
+const OFFLINE_CAUSE_STATUS: [u8; 3] = [0x10, 0x04, 0x02];
 
+/// How long a network status query waits for its reply. Real-time requests are answered
+/// within milliseconds, so a longer wait means the printer doesn't send status.
+const STATUS_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// ESC SP: right-side character spacing, followed by the spacing in dots
+const CHAR_SPACING: [u8; 2] = [0x1B, b' '];
+
+/// Each printer keeps a handle on its driver, since escpos only writes through `Printer`
+/// and status replies have to be read from the driver directly.
+/// Clones share the underlying connection but not the commands buffered since the last flush
+#[derive(Clone)]
 pub enum AnyPrinter {
-    Usb(Printer<UsbDriver>),
-    Network(Printer<NetworkDriver>),
-    Console(Printer<ConsoleDriver>),
+    Usb(Printer<UsbDriver>, UsbDriver),
+    Network(Printer<TcpDriver>, TcpDriver),
+    Console(Printer<ConsoleDriver>, ConsoleDriver),
+    File(Printer<FileDriver>, FileDriver),
+    Dump(Printer<DumpDriver>, DumpDriver),
+    Recording(RecordingPrinter),
+}
+
+impl From<RecordingPrinter> for AnyPrinter {
+    fn from(printer: RecordingPrinter) -> Self {
+        AnyPrinter::Recording(printer)
+    }
+}
+
+macro_rules! impl_from_driver {
+    ($variant:ident, $driver:ty) => {
+        impl From<$driver> for AnyPrinter {
+            fn from(driver: $driver) -> Self {
+                AnyPrinter::$variant(crate::build_printer(driver.clone()), driver)
+            }
+        }
+    };
+}
+impl_from_driver!(Usb, UsbDriver);
+impl_from_driver!(Network, TcpDriver);
+impl_from_driver!(Console, ConsoleDriver);
+impl_from_driver!(File, FileDriver);
+impl_from_driver!(Dump, DumpDriver);
+
+/// Send `request` and read its one byte reply, waiting as long as the driver's read timeout
+fn read_status(driver: &impl Driver, request: &[u8]) -> Result<Option<u8>, PrinterError> {
+    let mut response = [0u8; 1];
+    driver.write(request)?;
+    Ok((driver.read(&mut response)? == 1).then_some(response[0]))
+}
+
+/// Whether an offline cause `reply` says the cover is open, `None` when the printer didn't
+/// give a usable answer
+fn cover_open_from(reply: Result<Option<u8>, String>) -> Option<bool> {
+    match reply {
+        Ok(Some(reply)) => {
+            match RealTimeStatusResponse::parse(RealTimeStatusRequest::OfflineCause, reply) {
+                Ok(status) => status
+                    .get(&RealTimeStatusResponse::CoverClosed)
+                    .map(|closed| !closed),
+                Err(e) => {
+                    log::debug!("Ignoring printer status reply: {e}");
+                    None
+                }
+            }
+        }
+        Ok(None) => None,
+        Err(e) => {
+            log::debug!("Printer status unavailable: {e}");
+            None
+        }
+    }
+}
+
+#[derive(Default)]
+struct DumpState {
+    bytes: Vec<u8>,
+    writes: usize,
+    flushes: usize,
+    fail_after_writes: Option<usize>,
+    status_replies: Vec<u8>,
+}
+
+/// In-memory driver that records every byte sent to it instead of talking to hardware.
+/// Clones share the same buffer, so keep a clone around to inspect what was printed.
+#[derive(Clone, Default)]
+pub struct DumpDriver {
+    state: Arc<Mutex<DumpState>>,
+}
+impl DumpDriver {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A driver whose writes start failing once `writes` writes have succeeded.
+    /// Simulates a connection dropping mid-job.
+    pub fn failing_after(writes: usize) -> Self {
+        let driver = Self::default();
+        driver.lock().fail_after_writes = Some(writes);
+        driver
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, DumpState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Queue `status` as the reply to a status query, like a bidirectional printer would.
+    /// Replies are used in order and the last one repeats. Without any the driver is
+    /// treated as write only.
+    pub fn answer_status(&self, status: u8) {
+        self.lock().status_replies.push(status);
+    }
+
+    /// Let writes succeed again, as if the dropped connection came back
+    pub fn reconnect(&self) {
+        self.lock().fail_after_writes = None;
+    }
+
+    /// Every byte successfully written so far
+    pub fn bytes(&self) -> Vec<u8> {
+        self.lock().bytes.clone()
+    }
+
+    /// Number of times the printer flushed this driver
+    pub fn flush_count(&self) -> usize {
+        self.lock().flushes
+    }
+}
+impl Driver for DumpDriver {
+    fn name(&self) -> String {
+        "dump".to_owned()
+    }
+
+    fn write(&self, data: &[u8]) -> escpos::errors::Result<()> {
+        let mut state = self.lock();
+        if state
+            .fail_after_writes
+            .is_some_and(|limit| state.writes >= limit)
+        {
+            return Err(PrinterError::Io("dump driver disconnected".to_string()));
+        }
+        state.writes += 1;
+        state.bytes.extend_from_slice(data);
+        Ok(())
+    }
+
+    fn read(&self, buf: &mut [u8]) -> escpos::errors::Result<usize> {
+        let mut state = self.lock();
+        let (Some(&status), Some(first)) = (state.status_replies.first(), buf.first_mut()) else {
+            return Ok(0);
+        };
+        if state.status_replies.len() > 1 {
+            state.status_replies.remove(0);
+        }
+        *first = status;
+        Ok(1)
+    }
+
+    fn flush(&self) -> escpos::errors::Result<()> {
+        self.lock().flushes += 1;
+        Ok(())
+    }
 }
 
 macro_rules! delegate_printer_method {
     ($method:ident $(, $arg:ident : $ty:ty)*) => {
         pub fn $method(&mut self $(, $arg: $ty)*) -> Result<()> {
             match self {
-                AnyPrinter::Usb(p) => { p.$method($($arg),*)?; },
-                AnyPrinter::Network(p) => { p.$method($($arg),*)?; },
-                AnyPrinter::Console(p)=>{ p.$method($($arg),*)?; }
+                AnyPrinter::Usb(p, _) => { p.$method($($arg),*)?; },
+                AnyPrinter::Network(p, _) => { p.$method($($arg),*)?; },
+                AnyPrinter::Console(p, _)=>{ p.$method($($arg),*)?; }
+                AnyPrinter::File(p, _)=>{ p.$method($($arg),*)?; }
+                AnyPrinter::Dump(p, _)=>{ p.$method($($arg),*)?; }
+                AnyPrinter::Recording(p)=>{ p.$method($($arg),*)?; }
             }
         Ok(())
         }
@@ -25,13 +203,133 @@ macro_rules! delegate_printer_method {
 }
 
 impl AnyPrinter {
+    /// Wrap an already open driver. The printer can be kept for many jobs, so rapid
+    /// sequential prints share one USB handle or TCP connection.
+    pub fn from_existing<D: Driver>(driver: D) -> Self
+    where
+        Self: From<D>,
+    {
+        driver.into()
+    }
+
+    /// Whether the printer reports its cover open. `None` when the transport is write only
+    /// (the console, a file, or a dump driver without a status) or the printer didn't answer.
+    /// Network printers get `STATUS_TIMEOUT` to answer, USB printers the driver's timeout.
+    pub fn cover_open(&self) -> Option<bool> {
+        let reply = match self {
+            AnyPrinter::Usb(_, driver) => {
+                read_status(driver, &OFFLINE_CAUSE_STATUS).map_err(|e| e.to_string())
+            }
+            AnyPrinter::Network(_, driver) => driver
+                .query(&OFFLINE_CAUSE_STATUS, STATUS_TIMEOUT)
+                .map_err(|e| e.to_string()),
+            AnyPrinter::Console(..) | AnyPrinter::File(..) | AnyPrinter::Recording(_) => {
+                return None;
+            }
+            AnyPrinter::Dump(_, driver) => {
+                if driver.lock().status_replies.is_empty() {
+                    return None;
+                }
+                read_status(driver, &OFFLINE_CAUSE_STATUS).map_err(|e| e.to_string())
+            }
+        };
+        cover_open_from(reply)
+    }
+
+    /// Log every ESC/POS instruction as it is queued, under the `escpos` log target at
+    /// debug level. Recording printers ignore this.
+    pub fn set_debug_mode(&mut self, mode: Option<DebugMode>) -> &mut Self {
+        match self {
+            AnyPrinter::Usb(printer, _) => {
+                printer.debug_mode(mode);
+            }
+            AnyPrinter::Network(printer, _) => {
+                printer.debug_mode(mode);
+            }
+            AnyPrinter::Console(printer, _) => {
+                printer.debug_mode(mode);
+            }
+            AnyPrinter::File(printer, _) => {
+                printer.debug_mode(mode);
+            }
+            AnyPrinter::Dump(printer, _) => {
+                printer.debug_mode(mode);
+            }
+            AnyPrinter::Recording(_) => {}
+        }
+        self
+    }
+
+    pub fn debug_mode(&self) -> Option<DebugMode> {
+        match self {
+            AnyPrinter::Usb(printer, _) => printer.options().get_debug_mode(),
+            AnyPrinter::Network(printer, _) => printer.options().get_debug_mode(),
+            AnyPrinter::Console(printer, _) => printer.options().get_debug_mode(),
+            AnyPrinter::File(printer, _) => printer.options().get_debug_mode(),
+            AnyPrinter::Dump(printer, _) => printer.options().get_debug_mode(),
+            AnyPrinter::Recording(_) => None,
+        }
+    }
+
+    /// Return the printer to its power-on state so styles from a previous job on the same
+    /// connection don't carry over
+    pub fn begin_job(&mut self) -> Result<()> {
+        self.flip(false)?;
+        self.reset()
+    }
+
+    /// Rouse a printer that has dozed off while idle, so it doesn't drop the first bytes of
+    /// the next job. Sends an initialize, which prints nothing, and flushes it. With
+    /// `no_init` a NUL byte is sent instead, so the settings stored on the printer survive.
+    pub fn wake(&mut self, no_init: bool) -> Result<()> {
+        match no_init {
+            true => self.custom(&[0x00])?,
+            false => self.init()?,
+        };
+        self.print()
+    }
+
+    /// End a job or page: with `CutMode::Full` feed `feed_before` lines so the last one
+    /// clears the blade, then cut. Either way the buffer is flushed to the printer.
+    pub fn emit_cut(&mut self, feed_before: u8, mode: CutMode) -> Result<()> {
+        match mode {
+            CutMode::Full => {
+                self.feed_to_cutter(feed_before)?;
+                self.print_cut()
+            }
+            CutMode::None => self.print(),
+        }
+    }
+
+    /// Feed the model's print head to cutter distance so the last line clears the blade
+    pub fn feed_to_cutter(&mut self, lines: u8) -> Result<()> {
+        if lines > 0 {
+            self.feeds(lines)?;
+        }
+        Ok(())
+    }
+
+    /// Leave `dots` of space to the right of every character, 0 for the printer default
+    pub fn char_spacing(&mut self, dots: u8) -> Result<()> {
+        self.custom(&[CHAR_SPACING[0], CHAR_SPACING[1], dots])
+    }
+
     delegate_printer_method!(feed);
+    delegate_printer_method!(font, font: Font);
+    delegate_printer_method!(line_spacing, value: u8);
+    delegate_printer_method!(feeds, lines: u8);
+    delegate_printer_method!(custom, cmd: &[u8]);
     delegate_printer_method!(print);
     delegate_printer_method!(print_cut);
     delegate_printer_method!(write, text: &str);
     delegate_printer_method!(justify, mode: JustifyMode);
     delegate_printer_method!(bold, enabled: bool);
     delegate_printer_method!(underline, mode:UnderlineMode);
+    delegate_printer_method!(reverse, enabled: bool);
+    delegate_printer_method!(flip, enabled: bool);
     delegate_printer_method!(size, width:u8, height:u8);
     delegate_printer_method!(reset_size);
+    delegate_printer_method!(reset);
+    delegate_printer_method!(init);
+    delegate_printer_method!(qrcode_option, data: &str, option: QRCodeOption);
 }
@@ -0,0 +1,210 @@
+use anyhow::Result;
+use escpos::utils::{Font, JustifyMode, QRCodeOption, UnderlineMode};
+use std::sync::{Arc, Mutex};
+
+/// A call made on a `RecordingPrinter`
+#[derive(Debug, Clone, PartialEq)]
+pub enum PrinterCommand {
+    Feed,
+    Feeds(u8),
+    Font(Font),
+    /// Line spacing in dots
+    LineSpacing(u8),
+    Custom(Vec<u8>),
+    /// Flush without cutting
+    Print,
+    /// Flush and cut
+    Cut,
+    /// Text from consecutive writes, joined
+    Write(String),
+    Justify(JustifyMode),
+    Bold(bool),
+    Underline(UnderlineMode),
+    Reverse(bool),
+    Flip(bool),
+    Size(u8, u8),
+    ResetSize,
+    Reset,
+    /// ESC @, initialize
+    Init,
+    /// QR code data
+    QrCode(String),
+}
+
+/// Printer that records the commands it is given instead of encoding them, so tests can
+/// assert the order elements emit commands in. Clones share the same record, so keep a
+/// clone around to inspect what was printed. Commands are recorded as they are called,
+/// including those of a page that later fails and is never flushed.
+#[derive(Clone, Default)]
+pub struct RecordingPrinter {
+    commands: Arc<Mutex<Vec<PrinterCommand>>>,
+}
+
+impl RecordingPrinter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Every command recorded so far
+    pub fn commands(&self) -> Vec<PrinterCommand> {
+        self.lock().clone()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Vec<PrinterCommand>> {
+        self.commands.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn record(&mut self, command: PrinterCommand) -> Result<()> {
+        let mut commands = self.lock();
+        match (commands.last_mut(), command) {
+            (Some(PrinterCommand::Write(text)), PrinterCommand::Write(more)) => {
+                text.push_str(&more)
+            }
+            (_, command) => commands.push(command),
+        }
+        Ok(())
+    }
+
+    pub fn feed(&mut self) -> Result<()> {
+        self.record(PrinterCommand::Feed)
+    }
+
+    pub fn feeds(&mut self, lines: u8) -> Result<()> {
+        self.record(PrinterCommand::Feeds(lines))
+    }
+
+    pub fn font(&mut self, font: Font) -> Result<()> {
+        self.record(PrinterCommand::Font(font))
+    }
+
+    pub fn line_spacing(&mut self, value: u8) -> Result<()> {
+        self.record(PrinterCommand::LineSpacing(value))
+    }
+
+    pub fn custom(&mut self, cmd: &[u8]) -> Result<()> {
+        self.record(PrinterCommand::Custom(cmd.to_vec()))
+    }
+
+    pub fn print(&mut self) -> Result<()> {
+        self.record(PrinterCommand::Print)
+    }
+
+    pub fn print_cut(&mut self) -> Result<()> {
+        self.record(PrinterCommand::Cut)
+    }
+
+    pub fn write(&mut self, text: &str) -> Result<()> {
+        self.record(PrinterCommand::Write(text.to_string()))
+    }
+
+    pub fn justify(&mut self, mode: JustifyMode) -> Result<()> {
+        self.record(PrinterCommand::Justify(mode))
+    }
+
+    pub fn bold(&mut self, enabled: bool) -> Result<()> {
+        self.record(PrinterCommand::Bold(enabled))
+    }
+
+    pub fn underline(&mut self, mode: UnderlineMode) -> Result<()> {
+        self.record(PrinterCommand::Underline(mode))
+    }
+
+    pub fn reverse(&mut self, enabled: bool) -> Result<()> {
+        self.record(PrinterCommand::Reverse(enabled))
+    }
+
+    pub fn flip(&mut self, enabled: bool) -> Result<()> {
+        self.record(PrinterCommand::Flip(enabled))
+    }
+
+    pub fn size(&mut self, width: u8, height: u8) -> Result<()> {
+        self.record(PrinterCommand::Size(width, height))
+    }
+
+    pub fn reset_size(&mut self) -> Result<()> {
+        self.record(PrinterCommand::ResetSize)
+    }
+
+    pub fn reset(&mut self) -> Result<()> {
+        self.record(PrinterCommand::Reset)
+    }
+
+    pub fn init(&mut self) -> Result<()> {
+        self.record(PrinterCommand::Init)
+    }
+
+    pub fn qrcode_option(&mut self, data: &str, _option: QRCodeOption) -> Result<()> {
+        self.record(PrinterCommand::QrCode(data.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{RongtaPrinter, elements::TextSize, printer::AnyPrinter};
+    use PrinterCommand::*;
+
+    fn recorded(builder: &RongtaPrinter) -> Vec<PrinterCommand> {
+        let recorder = RecordingPrinter::new();
+        builder
+            .print_to(&mut AnyPrinter::from(recorder.clone()), None)
+            .unwrap();
+        recorder.commands()
+    }
+
+    mod command_order {
+        use super::*;
+
+        #[test]
+        fn bold_underlined_word() {
+            let mut builder = RongtaPrinter::new(true);
+            builder.set_is_bold(true);
+            builder.set_is_underlined(true);
+            builder.add_content("hi").unwrap();
+            assert_eq!(
+                recorded(&builder),
+                [
+                    Flip(false),
+                    Reset,
+                    Bold(true),
+                    Underline(UnderlineMode::Single),
+                    Reverse(false),
+                    ResetSize,
+                    Write("hi".to_string()),
+                    Feed,
+                    Cut,
+                ]
+            );
+        }
+
+        #[test]
+        fn mixed_size_line() {
+            let mut builder = RongtaPrinter::new(false);
+            builder.set_justify_content(crate::elements::Justify::Center);
+            builder.set_text_size(TextSize::Large);
+            builder.add_content("Big").unwrap();
+            builder.set_text_size(TextSize::Medium);
+            builder.add_content("small").unwrap();
+            assert_eq!(
+                recorded(&builder),
+                [
+                    Flip(false),
+                    Reset,
+                    Justify(JustifyMode::CENTER),
+                    Bold(false),
+                    Underline(UnderlineMode::None),
+                    Reverse(false),
+                    Size(2, 2),
+                    Write("Big".to_string()),
+                    Bold(false),
+                    Underline(UnderlineMode::None),
+                    Reverse(false),
+                    ResetSize,
+                    Write("small".to_string()),
+                    Feed,
+                    Print,
+                ]
+            );
+        }
+    }
+}
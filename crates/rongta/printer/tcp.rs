@@ -0,0 +1,145 @@
+use escpos::{driver::Driver, errors::PrinterError};
+use std::{
+    io::{ErrorKind, Read, Write},
+    net::TcpStream,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+/// Timeout of reads and writes other than status queries, escpos' own default
+const IO_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Driver for network printers. Unlike escpos' `NetworkDriver` it can answer a status query
+/// with its own short timeout, after clearing replies that arrived too late for an
+/// earlier query.
+/// Clones share the same connection.
+#[derive(Clone)]
+pub struct TcpDriver {
+    host: String,
+    port: u16,
+    stream: Arc<Mutex<TcpStream>>,
+}
+
+impl TcpDriver {
+    pub fn open(host: &str, port: u16) -> std::io::Result<Self> {
+        let stream = TcpStream::connect((host, port))?;
+        stream.set_read_timeout(Some(IO_TIMEOUT))?;
+        stream.set_write_timeout(Some(IO_TIMEOUT))?;
+        Ok(Self {
+            host: host.to_string(),
+            port,
+            stream: Arc::new(Mutex::new(stream)),
+        })
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, TcpStream> {
+        self.stream.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Send `request` and wait up to `timeout` for its one byte reply, `None` when none came
+    pub fn query(&self, request: &[u8], timeout: Duration) -> std::io::Result<Option<u8>> {
+        let mut stream = self.lock();
+        // A reply that missed its query's timeout would otherwise answer this one
+        stream.set_nonblocking(true)?;
+        let mut stale = [0u8; 64];
+        let drained = loop {
+            match stream.read(&mut stale) {
+                Ok(0) => break Ok(()),
+                Ok(read) => log::debug!("Discarding {read} stale status bytes"),
+                Err(e) if e.kind() == ErrorKind::WouldBlock => break Ok(()),
+                Err(e) => break Err(e),
+            }
+        };
+        stream.set_nonblocking(false)?;
+        drained?;
+
+        stream.write_all(request)?;
+        stream.set_read_timeout(Some(timeout))?;
+        let mut reply = [0u8; 1];
+        let read = stream.read(&mut reply);
+        stream.set_read_timeout(Some(IO_TIMEOUT))?;
+        match read {
+            Ok(1) => Ok(Some(reply[0])),
+            Ok(_) => Ok(None),
+            Err(e) if matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+impl Driver for TcpDriver {
+    fn name(&self) -> String {
+        format!("network ({}:{})", self.host, self.port)
+    }
+
+    fn write(&self, data: &[u8]) -> escpos::errors::Result<()> {
+        self.lock()
+            .write_all(data)
+            .map_err(|e| PrinterError::Io(e.to_string()))
+    }
+
+    fn read(&self, buf: &mut [u8]) -> escpos::errors::Result<usize> {
+        self.lock()
+            .read(buf)
+            .map_err(|e| PrinterError::Io(e.to_string()))
+    }
+
+    fn flush(&self) -> escpos::errors::Result<()> {
+        self.lock()
+            .flush()
+            .map_err(|e| PrinterError::Io(e.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::TcpListener;
+
+    mod query {
+        use super::*;
+
+        /// A driver connected to a local server that runs `serve` on its end
+        fn connected(serve: impl FnOnce(TcpStream) + Send + 'static) -> TcpDriver {
+            let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+            let port = listener.local_addr().unwrap().port();
+            std::thread::spawn(move || serve(listener.accept().unwrap().0));
+            TcpDriver::open("127.0.0.1", port).unwrap()
+        }
+
+        #[test]
+        fn discards_a_late_reply() {
+            let (sent, stale_sent) = std::sync::mpsc::channel();
+            let driver = connected(move |mut printer| {
+                printer.write_all(&[0xAA]).unwrap();
+                sent.send(()).unwrap();
+                let mut request = [0u8; 3];
+                printer.read_exact(&mut request).unwrap();
+                printer.write_all(&[0x12]).unwrap();
+                // Hold the connection open until the driver has read the reply
+                let _ = printer.read(&mut request);
+            });
+            stale_sent.recv().unwrap();
+            std::thread::sleep(Duration::from_millis(50));
+            let reply = driver
+                .query(&[0x10, 0x04, 0x02], Duration::from_secs(1))
+                .unwrap();
+            assert_eq!(reply, Some(0x12));
+        }
+
+        #[test]
+        fn silent_printer_times_out_quickly() {
+            let driver = connected(|mut printer| {
+                let mut request = [0u8; 3];
+                let _ = printer.read_exact(&mut request);
+                let _ = printer.read(&mut request);
+            });
+            let started = std::time::Instant::now();
+            let reply = driver
+                .query(&[0x10, 0x04, 0x02], Duration::from_millis(100))
+                .unwrap();
+            assert_eq!(reply, None);
+            assert!(started.elapsed() < IO_TIMEOUT);
+        }
+    }
+}
@@ -0,0 +1,100 @@
+use escpos::utils::PageCode;
+use std::{fmt, str::FromStr};
+
+/// What a printer model can do, used in place of configuring each setting by hand
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PrinterProfile {
+    /// Font A characters per line at the full paper width
+    pub cpl: u8,
+    /// Font B characters per line at the full paper width
+    pub font_b_cpl: u8,
+    /// Character code tables the firmware can switch to. Jobs are refused when it lacks
+    /// `CODE_PAGE`, the one they are encoded in.
+    pub code_pages: &'static [PageCode],
+    /// Whether the printer has an auto-cutter, without one cuts are skipped
+    pub has_cutter: bool,
+}
+
+/// Printer models with a known profile. `Custom` leaves every setting as configured.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PrinterModel {
+    #[default]
+    Custom,
+    RongtaRp80,
+    RongtaRp58,
+    EpsonTmT20,
+}
+
+impl PrinterModel {
+    /// The model's profile, `None` for `Custom`
+    pub fn profile(self) -> Option<PrinterProfile> {
+        match self {
+            PrinterModel::Custom => None,
+            PrinterModel::RongtaRp80 => Some(PrinterProfile {
+                cpl: 48,
+                font_b_cpl: 64,
+                code_pages: &[
+                    PageCode::PC437,
+                    PageCode::PC850,
+                    PageCode::PC858,
+                    PageCode::WPC1252,
+                ],
+                has_cutter: true,
+            }),
+            PrinterModel::RongtaRp58 => Some(PrinterProfile {
+                cpl: 32,
+                font_b_cpl: 42,
+                code_pages: &[PageCode::PC437, PageCode::PC850],
+                has_cutter: false,
+            }),
+            PrinterModel::EpsonTmT20 => Some(PrinterProfile {
+                cpl: 48,
+                font_b_cpl: 64,
+                code_pages: &[
+                    PageCode::PC437,
+                    PageCode::Katakana,
+                    PageCode::PC850,
+                    PageCode::PC858,
+                    PageCode::WPC1252,
+                ],
+                has_cutter: true,
+            }),
+        }
+    }
+}
+
+impl fmt::Display for PrinterModel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            PrinterModel::Custom => "custom",
+            PrinterModel::RongtaRp80 => "rongta-rp80",
+            PrinterModel::RongtaRp58 => "rongta-rp58",
+            PrinterModel::EpsonTmT20 => "epson-tm-t20",
+        };
+        f.write_str(name)
+    }
+}
+
+impl FromStr for PrinterModel {
+    type Err = anyhow::Error;
+
+    /// Accepts the model as printed on the printer, e.g. `Rongta RP80` or `tm-t20`, ignoring
+    /// case, spaces and dashes
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let name: String = value
+            .chars()
+            .filter(char::is_ascii_alphanumeric)
+            .map(|ch| ch.to_ascii_lowercase())
+            .collect();
+        match name.as_str() {
+            "custom" => Ok(PrinterModel::Custom),
+            "rongtarp80" | "rp80" => Ok(PrinterModel::RongtaRp80),
+            "rongtarp58" | "rp58" => Ok(PrinterModel::RongtaRp58),
+            "epsontmt20" | "tmt20" => Ok(PrinterModel::EpsonTmT20),
+            _ => Err(anyhow::anyhow!(
+                "Unknown printer model '{value}', expected rongta-rp80, rongta-rp58, \
+                 epson-tm-t20 or custom"
+            )),
+        }
+    }
+}
@@ -0,0 +1,52 @@
+use std::fmt;
+
+/// Summary of a finished print job
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct PrintReport {
+    /// Lines sent to the printer, including blank lines
+    pub lines_printed: usize,
+    /// Bytes of text sent to the printer, excluding ESC/POS control sequences
+    pub bytes_sent: usize,
+    /// Pages sent to the printer. Unpaginated jobs are a single page.
+    pub pages: usize,
+    /// Whether the last page was cut
+    pub cut: bool,
+}
+impl PrintReport {
+    /// Record a page that reached the printer, ending at line index `lines_printed`
+    pub(crate) fn page_printed(&mut self, lines_printed: usize, bytes_sent: usize) {
+        self.lines_printed = lines_printed;
+        self.bytes_sent += bytes_sent;
+        self.pages += 1;
+    }
+}
+
+/// A character the printer's code page can't show, see `RongtaPrinter::validate`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct UnprintableChar {
+    /// 1-based line of the rendered document, after wrapping
+    pub line: usize,
+    /// 1-based character position within the line
+    pub column: usize,
+    pub ch: char,
+}
+
+impl fmt::Display for UnprintableChar {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "line {}, column {}: '{}' (U+{:04X})",
+            self.line, self.column, self.ch, self.ch as u32
+        )
+    }
+}
+
+impl fmt::Display for PrintReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Printed {} lines across {} pages",
+            self.lines_printed, self.pages
+        )
+    }
+}
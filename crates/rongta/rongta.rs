@@ -1,25 +1,88 @@
-use crate::elements::{FormatState, Justify, TextSize};
+use crate::elements::{Font, FormatState, Justify, PageLength, PageSeparator, Rotation, TextSize};
 use anyhow::{Context, Result};
 use elements::ToPrintCommand;
 use escpos::{
-    driver::{ConsoleDriver, Driver, NetworkDriver, UsbDriver},
+    driver::{ConsoleDriver, Driver, FileDriver, UsbDriver},
     printer::Printer,
     printer_options::PrinterOptions,
-    utils::Protocol,
+    utils::{DebugMode, PageCode, Protocol},
 };
+use std::path::PathBuf;
 
 mod cp437;
 pub mod elements;
-mod line;
+mod error;
+pub mod line;
 pub mod printer;
+pub mod profile;
+mod report;
 
-pub const CPL: u8 = 48; // characters per line
+pub use error::RongtaError;
+pub use report::{PrintReport, UnprintableChar};
+
+pub const CPL: u8 = 48; // default characters per line
+
+/// Line spacing in dots used by `compact_preset`, just above the height of Font B
+pub const COMPACT_LINE_SPACING: u8 = 18;
+
+/// Code page every job is encoded and validated in, selected when the printer is opened
+pub const CODE_PAGE: PageCode = PageCode::PC437;
+
+/// Width of a Font A character in dots, one `cpl` column
+const CHAR_DOTS: usize = 12;
+
+/// Print head resolution of 203 dpi thermal printers
+const DOTS_PER_INCH: f32 = 203.0;
+
+/// The printer's own line spacing in dots, 3.75mm, used when none is set
+const DEFAULT_LINE_SPACING: u8 = 30;
+
+/// Modules across a QR code of the size most URLs need, for estimating its height
+const QR_MODULES: usize = 33;
 
-#[derive(Default)]
 pub struct RongtaPrinter {
     lines: Vec<line::Line>,
     cut: bool,
+    cpl: u8,
     format_state: FormatState,
+    page_separator: PageSeparator,
+    rotation: Rotation,
+    cutter_offset_lines: u8,
+    strict_width: bool,
+    debug_mode: Option<DebugMode>,
+    font: Font,
+    /// Dots between lines, the printer default when `None`
+    line_spacing: Option<u8>,
+    /// Dots of space after every character
+    char_spacing: u8,
+    /// Consecutive blank lines kept before the rest are dropped, unlimited when `None`
+    max_blank_lines: Option<usize>,
+    /// Skip `begin_job`, keeping settings made on the printer itself
+    no_init: bool,
+    /// The printer model's profile, for the width of Font B lines
+    profile: Option<profile::PrinterProfile>,
+}
+
+impl Default for RongtaPrinter {
+    fn default() -> Self {
+        Self {
+            lines: Vec::default(),
+            cut: false,
+            cpl: CPL,
+            format_state: FormatState::default(),
+            page_separator: PageSeparator::default(),
+            rotation: Rotation::default(),
+            cutter_offset_lines: 0,
+            strict_width: false,
+            debug_mode: None,
+            font: Font::default(),
+            line_spacing: None,
+            char_spacing: 0,
+            max_blank_lines: None,
+            no_init: false,
+            profile: None,
+        }
+    }
 }
 
 impl RongtaPrinter {
@@ -30,23 +93,121 @@ impl RongtaPrinter {
         }
     }
 
+    /// A builder holding `lines`, each wrapped at the default `CPL` and keeping its
+    /// justification on every wrapped line. Content added afterwards starts a new line.
+    pub fn from_lines(lines: Vec<(String, Justify)>) -> Result<Self> {
+        let mut builder = Self::default();
+        for (text, justify) in lines {
+            builder.set_justify_content(justify);
+            builder.add_content(&text)?;
+            builder.new_line();
+        }
+        Ok(builder)
+    }
+
+    /// Wrap lines at `cpl` characters instead of the default `CPL`, e.g. 32 for 58mm paper.
+    /// Never wider than the paper of a profile set before.
+    pub fn with_cpl(mut self, cpl: u8) -> Self {
+        self.cpl = self.profile.map_or(cpl, |profile| cpl.min(profile.cpl));
+        self
+    }
+
+    /// Fit the job to what the printer model can do: lines no wider than its paper and no
+    /// cuts without a cutter. A narrower `cpl` set before is kept. Font B lines wrap at the
+    /// profile's Font B width, scaled down with a narrower `cpl`.
+    pub fn with_profile(mut self, profile: &profile::PrinterProfile) -> Self {
+        self.cpl = self.cpl.min(profile.cpl);
+        self.cut &= profile.has_cutter;
+        self.profile = Some(*profile);
+        self
+    }
+
+    /// Don't reset the printer at the start of the job. For printers set up through their
+    /// own utility, e.g. with a persisted code page, that the reset would undo.
+    pub fn with_no_init(mut self, no_init: bool) -> Self {
+        self.no_init = no_init;
+        self
+    }
+
+    /// Cut at the end of the job, or not, instead of what was given to `new`. Never cuts
+    /// when a profile set before has no cutter.
+    pub fn with_cut(mut self, cut: bool) -> Self {
+        self.cut = cut && self.profile.is_none_or(|profile| profile.has_cutter);
+        self
+    }
+
+    /// Feed `lines` extra lines before every cut so the last printed line clears the blade.
+    /// This depends on the printer model (the distance from print head to cutter), not the content.
+    pub fn with_cutter_offset(mut self, lines: u8) -> Self {
+        self.cutter_offset_lines = lines;
+        self
+    }
+
+    /// Refuse to print lines wider than `cpl` instead of only logging a warning. Lines added
+    /// through `add_content` always wrap, so a wide line points at a rendering bug.
+    pub fn with_strict_width(mut self, strict: bool) -> Self {
+        self.strict_width = strict;
+        self
+    }
+
+    /// Log the ESC/POS bytes of every instruction in hex when printing through
+    /// `print_detailed`, to capture the exact byte stream for a bug report
+    pub fn with_debug_hex(mut self, enabled: bool) -> Self {
+        self.debug_mode = enabled.then_some(DebugMode::Hex);
+        self
+    }
+
+    /// Characters per line this builder wraps at, fewer than the configured `cpl` when
+    /// `set_char_spacing` widens every character and more in Font B with a profile
+    pub fn cpl(&self) -> u8 {
+        let paper_dots = self.cpl as usize * CHAR_DOTS;
+        // The active font's width as `dots / chars`, Font B's from the profile's paper width
+        let (font_dots, font_chars) = match (self.font, self.profile) {
+            (Font::B, Some(profile)) => (
+                profile.cpl as usize * CHAR_DOTS,
+                profile.font_b_cpl as usize,
+            ),
+            _ => (CHAR_DOTS, 1),
+        };
+        let cpl = paper_dots * font_chars / (font_dots + self.char_spacing as usize * font_chars);
+        cpl as u8
+    }
+
     /// Add content to the current line. The content is formatted according to the current formatting state.
     /// This is a more efficient way to add content that needs the same formatting.
     /// Highly recommended to call `new_line()` after adding content to the current line.
+    ///
+    /// Line endings (`\n`, `\r\n` or a lone `\r`) start a new line with the same
+    /// justification rather than being printed.
     pub fn add_content(&mut self, content: &str) -> Result<()> {
         if self.lines.is_empty() {
             self.lines.push(line::Line::default());
         }
-        for char in content.chars() {
+        let cpl = self.cpl() as usize;
+        let content = cp437::normalize_to_ascii(content);
+        let mut chars = content.chars().peekable();
+        while let Some(char) = chars.next() {
+            if char == '\r' || char == '\n' {
+                if char == '\r' {
+                    chars.next_if_eq(&'\n');
+                }
+                let justify = self.lines.last().map(|l| l.justify_content);
+                self.lines
+                    .push(line::Line::new(Vec::new(), justify.unwrap_or_default()));
+                continue;
+            }
             let new_line = {
                 let current_line = self
                     .lines
                     .last_mut()
                     .expect("New line should have been added");
-                current_line.add_char(elements::StyledChar {
-                    ch: char,
-                    state: self.format_state,
-                })
+                current_line.add_char(
+                    elements::StyledChar {
+                        ch: char,
+                        state: self.format_state,
+                    },
+                    cpl,
+                )
             };
 
             if let Some(new_line) = new_line {
@@ -69,6 +230,169 @@ impl RongtaPrinter {
         }
     }
 
+    /// Add centered content using the largest `TextSize` that keeps it within the line width.
+    /// At 48 CPL ExtraLarge fits up to 16 characters, Large up to 24, anything longer is Medium.
+    /// The chosen size stays active for the next characters, like `set_text_size`.
+    pub fn add_autosized_text(&mut self, text: &str) -> Result<()> {
+        let size = TextSize::largest_fitting(text.chars().count(), self.cpl() as usize);
+        self.set_justify_content(Justify::Center);
+        self.set_text_size(size);
+        self.add_content(text)
+    }
+
+    /// Drop the current line when nothing has been added to it yet
+    fn drop_empty_line(&mut self) {
+        if self
+            .lines
+            .last()
+            .is_some_and(|line| line.chars.is_empty() && line.qr_code.is_none() && !line.page_break)
+        {
+            self.lines.pop();
+        }
+    }
+
+    /// Add a centered QR code on its own line. Content added afterwards starts on the next line.
+    pub fn add_qr_code(&mut self, data: &str, size: u8) -> Result<()> {
+        let qr_code = elements::QrCode::new(data, size)?;
+        self.drop_empty_line();
+        self.lines.push(line::Line::with_qr_code(qr_code));
+        self.new_line();
+        Ok(())
+    }
+
+    /// Cut the paper after the content so far, whatever the `rows` pagination. Content added
+    /// afterwards starts a new page. Breaks with nothing printed before them are skipped.
+    pub fn page_break(&mut self) {
+        self.drop_empty_line();
+        self.lines.push(line::Line::page_break());
+        self.new_line();
+    }
+
+    /// Paper `line` takes up in dots, estimated from its tallest character and the line
+    /// spacing. Characters taller than the spacing push the next line down.
+    pub fn line_height_dots(&self, line: &line::Line) -> usize {
+        let spacing = self.line_spacing.unwrap_or(DEFAULT_LINE_SPACING) as usize;
+        if let Some(qr_code) = &line.qr_code {
+            return qr_code.size as usize * QR_MODULES + spacing;
+        }
+        let tallest = line
+            .chars
+            .iter()
+            .map(|sc| sc.state.text_size.char_height())
+            .max()
+            .unwrap_or(1);
+        spacing.max(self.font.char_height_dots() * tallest)
+    }
+
+    /// Paper the printed lines take up in inches, not counting the feed before a cut
+    pub fn estimated_length_inches(&self) -> f32 {
+        let dots: usize = self.lines[..self.content_len()]
+            .iter()
+            .filter(|line| !line.page_break)
+            .map(|line| self.line_height_dots(line))
+            .sum();
+        dots as f32 / DOTS_PER_INCH
+    }
+
+    /// Insert page breaks so every page is `length` long, each page ending with a cut.
+    /// Pages by `Inches` end before the line that would overrun them, so they come out a
+    /// little short rather than long. Unlike the `rows` of `print`, the last page isn't
+    /// padded, and breaks already in the content start a new count.
+    pub fn paginate(&mut self, length: PageLength) {
+        let end = self.content_len();
+        let tail = self.lines.split_off(end);
+        let lines = std::mem::take(&mut self.lines);
+        let heights: Vec<usize> = lines
+            .iter()
+            .map(|line| self.line_height_dots(line))
+            .collect();
+        let mut page_rows = 0;
+        let mut page_dots = 0;
+        for (line, height) in lines.into_iter().zip(heights) {
+            if line.page_break {
+                page_rows = 0;
+                page_dots = 0;
+                self.lines.push(line);
+                continue;
+            }
+            let full = match length {
+                PageLength::Rows(rows) => page_rows >= rows,
+                PageLength::Inches(inches) => (page_dots + height) as f32 > inches * DOTS_PER_INCH,
+            };
+            if full && page_rows > 0 {
+                self.lines.push(line::Line::page_break());
+                page_rows = 0;
+                page_dots = 0;
+            }
+            page_rows += 1;
+            page_dots += height;
+            self.lines.push(line);
+        }
+        self.lines.extend(tail);
+    }
+
+    /// Add a centered QR code followed by a centered caption, wrapped at `cpl`. Content added
+    /// afterwards starts on the next line.
+    pub fn add_qr_with_caption(&mut self, data: &str, caption: &str, size: u8) -> Result<()> {
+        self.add_qr_code(data, size)?;
+        self.set_justify_content(Justify::Center);
+        self.add_content(caption)?;
+        self.new_line();
+        Ok(())
+    }
+
+    /// Set how pages are separated when printing with `rows` pagination. Defaults to a cut.
+    pub fn set_page_separator(&mut self, separator: PageSeparator) {
+        self.page_separator = separator;
+    }
+
+    /// Print the whole job rotated. Rotation is switched on at the start of each page and
+    /// off before each page separator. Lines still wrap at `cpl`, so rotated content is
+    /// limited to the paper width rather than its length.
+    pub fn set_rotation(&mut self, rotation: Rotation) {
+        self.rotation = rotation;
+    }
+
+    /// Print the whole job in `font`. Lines wrap at `cpl`, or at the profile's Font B
+    /// width in Font B when `with_profile` set one. Set it before adding content.
+    pub fn set_font(&mut self, font: Font) {
+        self.font = font;
+    }
+
+    /// Set the distance between lines in dots, or restore the printer default with `None`
+    pub fn set_line_spacing(&mut self, dots: Option<u8>) {
+        self.line_spacing = dots;
+    }
+
+    /// Space characters `dots` apart for emphasis or a looser look, sent once at the start of
+    /// the job. Each character takes more of the paper, so lines wrap sooner: at 48 CPL a
+    /// spacing of 3 dots leaves room for 38 characters.
+    pub fn set_char_spacing(&mut self, dots: u8) {
+        self.char_spacing = dots;
+    }
+
+    /// Print at most `max` blank lines in a row, dropping the rest
+    pub fn set_max_blank_lines(&mut self, max: Option<usize>) {
+        self.max_blank_lines = max;
+    }
+
+    /// Save paper on long receipts: Font B, tight line spacing and no more than one blank
+    /// line in a row
+    pub fn compact_preset(&mut self) -> &mut Self {
+        self.set_font(Font::B);
+        self.set_line_spacing(Some(COMPACT_LINE_SPACING));
+        self.set_max_blank_lines(Some(1));
+        self
+    }
+
+    /// Switch rotation on (`Rotation::Cw90`) or off (`Rotation::None`), if the job is rotated
+    fn rotate(&self, printer: &mut printer::AnyPrinter, rotation: Rotation) -> Result<()> {
+        if self.rotation != Rotation::None {
+            rotation.to_print_command(printer)?;
+        }
+        Ok(())
+    }
+
     /// Set the text size of the next characters
     pub fn set_text_size(&mut self, size: elements::TextSize) {
         self.format_state.text_size = size;
@@ -79,6 +403,114 @@ impl RongtaPrinter {
         self.format_state.is_bold = bold;
     }
 
+    /// Set the underline state for the next characters
+    pub fn set_is_underlined(&mut self, underlined: bool) {
+        self.format_state.is_underlined = underlined;
+    }
+
+    /// Set reverse video (white on black) for the next characters
+    pub fn set_is_reversed(&mut self, reversed: bool) {
+        self.format_state.is_reversed = reversed;
+    }
+
+    /// The formatting that will be applied to the next characters
+    pub fn format_state(&self) -> FormatState {
+        self.format_state
+    }
+
+    /// Replace the formatting for the next characters, e.g. to restore a saved `format_state`
+    pub fn set_format_state(&mut self, state: FormatState) {
+        self.format_state = state;
+    }
+
+    /// The lines rendered so far
+    pub fn lines(&self) -> &[line::Line] {
+        &self.lines
+    }
+
+    /// The lines built so far, for composing them into another builder
+    pub fn into_lines(self) -> Vec<line::Line> {
+        self.lines
+    }
+
+    /// Add a finished line as is, without wrapping or applying the current format state.
+    /// The next `add_content` starts a new line after it.
+    pub fn push_line(&mut self, line: line::Line) {
+        if self.lines.last().is_some_and(line::Line::is_unstarted) {
+            self.lines.pop();
+        }
+        self.lines.push(line);
+        self.new_line();
+    }
+
+    /// Add the lines of `other` after this builder's, each keeping its own justification
+    /// and styles. Trailing blank lines of `other` are dropped.
+    pub fn append(&mut self, mut other: RongtaPrinter) {
+        other.trim_trailing_blank_lines();
+        for line in other.into_lines() {
+            self.push_line(line);
+        }
+    }
+
+    /// Insert a finished line before the line at `index`, or after the last line when
+    /// `index` is the number of lines. Useful for headers added once the body is built.
+    pub fn insert_line_at(&mut self, index: usize, line: line::Line) -> Result<()> {
+        anyhow::ensure!(
+            index <= self.lines.len(),
+            "Cannot insert a line at {index}, there are {} lines",
+            self.lines.len()
+        );
+        self.lines.insert(index, line);
+        Ok(())
+    }
+
+    /// Remove and return the line at `index`
+    pub fn remove_line(&mut self, index: usize) -> Result<line::Line> {
+        anyhow::ensure!(
+            index < self.lines.len(),
+            "Cannot remove line {index}, there are {} lines",
+            self.lines.len()
+        );
+        Ok(self.lines.remove(index))
+    }
+
+    /// Every character the printer would reject after typographic normalization, without
+    /// sending anything. Printing stops at the first of them, so this finds them all up front.
+    pub fn validate(&self) -> Vec<UnprintableChar> {
+        self.lines
+            .iter()
+            .enumerate()
+            .flat_map(|(index, line)| {
+                line.chars
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, sc)| elements::printable_char(sc.ch).is_err())
+                    .map(move |(column, sc)| UnprintableChar {
+                        line: index + 1,
+                        column: column + 1,
+                        ch: sc.ch,
+                    })
+            })
+            .collect()
+    }
+
+    /// Number of lines up to and including the last one with content. Empty lines after it
+    /// would only feed paper before the cut. Spaces count as content since reversed or
+    /// underlined ones print. Intentional feeds aren't lines, `cutter_offset_lines` and the
+    /// padding of `rows` pages are sent at the cut, so they still print.
+    fn content_len(&self) -> usize {
+        self.lines
+            .iter()
+            .rposition(|line| !line.is_unstarted())
+            .map_or(0, |last| last + 1)
+    }
+
+    /// Drop the blank lines at the end of the document. Printing already skips them, this
+    /// is for callers that add lines after inspecting `lines`.
+    pub fn trim_trailing_blank_lines(&mut self) {
+        self.lines.truncate(self.content_len());
+    }
+
     /// Reset all styles for the next characters
     /// If you want to reset the justification you should explicitly set or call `new_line`
     pub fn reset_styles(&mut self) {
@@ -90,51 +522,268 @@ impl RongtaPrinter {
         &self,
         printer: &mut printer::AnyPrinter,
         rows: Option<u32>,
+    ) -> anyhow::Result<()> {
+        self.print_to_detailed(printer, rows).map(|_| ())
+    }
+
+    /// Same as `print_to`, reporting what was printed
+    pub fn print_to_detailed(
+        &self,
+        printer: &mut printer::AnyPrinter,
+        rows: Option<u32>,
+    ) -> anyhow::Result<PrintReport> {
+        let mut report = PrintReport::default();
+        self.print_lines(printer, rows, &mut report)?;
+        Ok(report)
+    }
+
+    /// Send `text` as is, without wrapping or styling, then end the job the way the builder
+    /// is configured: the reset unless `no_init`, and `cut` after `cutter_offset_lines`.
+    /// The builder's lines are ignored.
+    pub fn print_raw_to(
+        &self,
+        printer: &mut printer::AnyPrinter,
+        text: &str,
+    ) -> anyhow::Result<PrintReport> {
+        self.check_code_page()?;
+        if !self.no_init {
+            printer.begin_job()?;
+        }
+        printer.write(text)?;
+        printer.emit_cut(self.cutter_offset_lines, self.cut.into())?;
+        Ok(PrintReport {
+            lines_printed: text.lines().count(),
+            bytes_sent: text.len(),
+            pages: 1,
+            cut: self.cut,
+        })
+    }
+
+    /// Print everything and end the job with a flush and a cut, whatever `cut` was set to,
+    /// unless the printer profile has no cutter.
+    ///
+    /// The builder isn't tied to a printer and does nothing when dropped: lines only reach
+    /// the printer through `print*` or `finalize`, so a builder abandoned on an early error
+    /// leaves nothing half-sent.
+    pub fn finalize(self, printer: &mut printer::AnyPrinter) -> anyhow::Result<PrintReport> {
+        self.with_cut(true).print_to_detailed(printer, None)
+    }
+
+    /// Print the lines after `report.lines_printed`, recording each page in `report` once it
+    /// has been flushed to the printer. Lines only leave the printer's buffer when a page is
+    /// cut (or the job ends), so on error `report.lines_printed` is a safe place to resume from.
+    ///
+    /// On error the unfinished page is dropped from `printer`'s buffer, otherwise a printer
+    /// kept for the next job would send it, partial line and all, ahead of that job.
+    fn print_lines(
+        &self,
+        printer: &mut printer::AnyPrinter,
+        rows: Option<u32>,
+        report: &mut PrintReport,
+    ) -> anyhow::Result<()> {
+        self.check_code_page()?;
+        self.check_line_widths()?;
+        let mut page_start = printer.clone();
+        let result = self.print_pages(printer, rows, report, &mut page_start);
+        if result.is_err() {
+            *printer = page_start;
+        }
+        result
+    }
+
+    /// Refuse a job for a printer whose profile can't select `CODE_PAGE`, it would print
+    /// the wrong glyphs for anything past ASCII
+    fn check_code_page(&self) -> Result<()> {
+        if let Some(profile) = self.profile {
+            anyhow::ensure!(
+                profile.code_pages.contains(&CODE_PAGE),
+                "The printer profile doesn't support {CODE_PAGE:?}, the code page jobs are \
+                 encoded in"
+            );
+        }
+        Ok(())
+    }
+
+    /// Warn about, or in strict mode reject, lines the printer would wrap on its own
+    fn check_line_widths(&self) -> Result<()> {
+        let cpl = self.cpl() as usize;
+        for (index, line) in self.lines.iter().enumerate() {
+            if line.visual_width() <= cpl {
+                continue;
+            }
+            let text: String = line.chars.iter().map(|sc| sc.ch).collect();
+            let message = format!(
+                "Line {} is {} columns wide, over the {cpl} column limit: {text:?}",
+                index + 1,
+                line.visual_width()
+            );
+            anyhow::ensure!(!self.strict_width, message);
+            log::warn!("{message}");
+        }
+        Ok(())
+    }
+
+    /// `page_start` is updated to a copy of `printer` after every flushed page
+    fn print_pages(
+        &self,
+        printer: &mut printer::AnyPrinter,
+        rows: Option<u32>,
+        report: &mut PrintReport,
+        page_start: &mut printer::AnyPrinter,
     ) -> anyhow::Result<()> {
         let mut last_justify_content = Justify::default();
         let mut last_format_state = FormatState::default();
-        if let Some(rows_per_page) = rows {
-            let mut line_count = 0;
-            for line in &self.lines {
-                print_line(
-                    line,
-                    printer,
-                    &mut last_justify_content,
-                    &mut last_format_state,
-                )?;
-                line_count += 1;
-                if line_count >= rows_per_page {
-                    printer.print_cut()?;
+        let mut page_bytes = 0;
+        // lines printed on the current page
+        let mut line_count = 0;
+        let mut pages_printed = 0;
+        let start = report.lines_printed;
+        // blank lines in a row, for `max_blank_lines`
+        let mut blank_run = 0;
+        // whether the printer still answers status queries this job
+        let mut query_status = true;
+        if !self.no_init {
+            printer.begin_job()?;
+        }
+        if self.font != Font::A {
+            self.font.to_print_command(printer)?;
+        }
+        if let Some(dots) = self.line_spacing {
+            printer.line_spacing(dots)?;
+        }
+        if self.char_spacing > 0 {
+            printer.char_spacing(self.char_spacing)?;
+        }
+        let end = self.content_len();
+        for (index, line) in self.lines[..end].iter().enumerate().skip(start) {
+            blank_run = if line.is_blank() { blank_run + 1 } else { 0 };
+            if self.max_blank_lines.is_some_and(|max| blank_run > max) {
+                continue;
+            }
+            if line.page_break {
+                if line_count > 0 {
+                    self.rotate(printer, Rotation::None)?;
+                    printer.emit_cut(self.cutter_offset_lines, self.cut.into())?;
+                    report.page_printed(index + 1, page_bytes);
+                    report.cut |= self.cut;
+                    *page_start = printer.clone();
+                    page_bytes = 0;
                     line_count = 0;
+                    pages_printed += 1;
                 }
+                continue;
+            }
+            if line_count == 0 {
+                ensure_cover_closed(printer, report, &mut query_status)?;
+                self.rotate(printer, self.rotation)?;
             }
+            page_bytes += print_line(
+                line,
+                printer,
+                &mut last_justify_content,
+                &mut last_format_state,
+            )?;
+            line_count += 1;
+            if rows.is_some_and(|rows_per_page| line_count >= rows_per_page) {
+                self.rotate(printer, Rotation::None)?;
+                self.separate_page(printer)?;
+                report.page_printed(index + 1, page_bytes);
+                *page_start = printer.clone();
+                page_bytes = 0;
+                line_count = 0;
+                pages_printed += 1;
+            }
+        }
+        if let Some(rows_per_page) = rows {
             if line_count > 0 {
                 while line_count < rows_per_page {
                     printer.feed()?;
                     line_count += 1;
                 }
-                printer.print_cut()?;
+                self.rotate(printer, Rotation::None)?;
+                self.separate_page(printer)?;
+                report.page_printed(end, page_bytes);
             }
-        } else {
-            for line in &self.lines {
-                print_line(
-                    line,
-                    printer,
-                    &mut last_justify_content,
-                    &mut last_format_state,
-                )?;
-            }
-            match self.cut {
-                true => printer.print_cut()?,
-                false => printer.print()?,
-            };
+            report.cut |= self.cut && self.page_separator == PageSeparator::Cut;
+        } else if line_count > 0 || pages_printed == 0 {
+            if line_count == 0 {
+                ensure_cover_closed(printer, report, &mut query_status)?;
+                self.rotate(printer, self.rotation)?;
+            }
+            self.rotate(printer, Rotation::None)?;
+            printer.emit_cut(self.cutter_offset_lines, self.cut.into())?;
+            report.page_printed(end, page_bytes);
+            report.cut |= self.cut;
         }
         Ok(())
     }
 
+    /// End a `rows` page. A `Cut` separator cuts like the end of the job, so without `cut`
+    /// it only flushes.
+    fn separate_page(&self, printer: &mut printer::AnyPrinter) -> Result<()> {
+        match self.page_separator {
+            PageSeparator::Cut => printer.emit_cut(self.cutter_offset_lines, self.cut.into()),
+            separator => separator.to_print_command(printer),
+        }
+    }
+
     pub fn print(&self, rows: Option<u32>, driver: SupportedDriver) -> Result<()> {
+        self.print_detailed(rows, driver).map(|_| ())
+    }
+
+    /// Print through the given driver. If a network connection drops mid-job, reconnect once
+    /// and resume from the last fully printed page. When that also fails the error is a
+    /// `RongtaError::Disconnected` carrying how many lines made it out.
+    pub fn print_detailed(
+        &self,
+        rows: Option<u32>,
+        driver: SupportedDriver,
+    ) -> Result<PrintReport> {
+        let mut printer = self.open_printer(driver.clone())?;
+        let mut report = PrintReport::default();
+        match self.print_lines(&mut printer, rows, &mut report) {
+            Ok(()) => Ok(report),
+            Err(e)
+                if matches!(driver, SupportedDriver::Network(..))
+                    && e.downcast_ref::<RongtaError>().is_none() =>
+            {
+                log::warn!(
+                    "Print interrupted after {} lines, reconnecting: {e:#}",
+                    report.lines_printed
+                );
+                self.resume_print(|| self.open_printer(driver), rows, report)
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    /// `print_raw_to` through the given driver
+    pub fn print_raw(&self, text: &str, driver: SupportedDriver) -> Result<PrintReport> {
+        let mut printer = self.open_printer(driver)?;
+        self.print_raw_to(&mut printer, text)
+    }
+
+    fn open_printer(&self, driver: SupportedDriver) -> Result<printer::AnyPrinter> {
         let mut printer = build_any_printer(driver)?;
-        self.print_to(&mut printer, rows)
+        printer.set_debug_mode(self.debug_mode);
+        Ok(printer)
+    }
+
+    fn resume_print(
+        &self,
+        reconnect: impl FnOnce() -> Result<printer::AnyPrinter>,
+        rows: Option<u32>,
+        mut report: PrintReport,
+    ) -> Result<PrintReport> {
+        match reconnect().and_then(|mut printer| self.print_lines(&mut printer, rows, &mut report))
+        {
+            Ok(()) => Ok(report),
+            Err(e) => {
+                let lines_printed = report.lines_printed;
+                log::error!("Failed to resume print after {lines_printed} lines: {e:#}");
+                Err(RongtaError::Disconnected { lines_printed }.into())
+            }
+        }
     }
 }
 
@@ -143,57 +792,112 @@ pub enum SupportedDriver {
     Console,
     Usb(u16, u16),
     Network(String, u16),
+    /// Append the ESC/POS stream to a file, created when missing, so every job of a run is
+    /// kept. For printers driven by something else, e.g. a CUPS raw queue with `lp -o raw`
+    File(PathBuf),
+    /// Record the output in memory, see `printer::DumpDriver`
+    Dump(printer::DumpDriver),
 }
 
 pub fn build_any_printer(driver: SupportedDriver) -> Result<printer::AnyPrinter> {
     match driver {
         SupportedDriver::Console => {
             let driver = ConsoleDriver::open(true);
-            Ok(printer::AnyPrinter::Console(build_printer(driver)?))
+            Ok(printer::AnyPrinter::from_existing(driver))
         }
         SupportedDriver::Usb(vendor_id, product_id) => {
-            let driver = UsbDriver::open(vendor_id, product_id, None, None)
-                .inspect_err(|_| {
-                    log::error!("Attempted to connect to {}:{}", vendor_id, product_id)
-                })
-                .with_context(|| "Failed to open usb driver")?;
-            Ok(printer::AnyPrinter::Usb(build_printer(driver)?))
+            let driver = UsbDriver::open(vendor_id, product_id, None, None).map_err(|e| {
+                log::error!("Attempted to connect to {}:{}", vendor_id, product_id);
+                let context = describe_usb_open_error(&e.to_string(), vendor_id, product_id);
+                anyhow::Error::new(e).context(context)
+            })?;
+            Ok(printer::AnyPrinter::from_existing(driver))
         }
         SupportedDriver::Network(host, port) => {
-            let driver = NetworkDriver::open(&host, port, None)
+            let driver = printer::TcpDriver::open(&host, port)
                 .inspect_err(|_| log::error!("Attempted to connect to {}:{}", host, port))
                 .with_context(|| "Failed to open network driver")?;
-            Ok(printer::AnyPrinter::Network(build_printer(driver)?))
+            Ok(printer::AnyPrinter::from_existing(driver))
         }
+        SupportedDriver::File(path) => {
+            // FileDriver appends to an existing file only
+            std::fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(&path)
+                .with_context(|| format!("Failed to create output file '{}'", path.display()))?;
+            let driver = FileDriver::open(&path).with_context(|| "Failed to open file driver")?;
+            Ok(printer::AnyPrinter::from_existing(driver))
+        }
+        SupportedDriver::Dump(driver) => Ok(printer::AnyPrinter::from_existing(driver)),
+    }
+}
+
+/// Explain a `UsbDriver::open` failure. escpos flattens rusb errors into strings, so the
+/// cause is recovered from the message.
+fn describe_usb_open_error(message: &str, vendor_id: u16, product_id: u16) -> String {
+    let device = format!("{vendor_id:04x}:{product_id:04x}");
+    if message.contains("Access denied") {
+        format!(
+            "Permission denied opening USB printer {device}. Add a udev rule such as \
+             SUBSYSTEM==\"usb\", ATTRS{{idVendor}}==\"{vendor_id:04x}\", \
+             ATTRS{{idProduct}}==\"{product_id:04x}\", MODE=\"0666\" \
+             or add your user to the dialout group, then replug the printer"
+        )
+    } else if message.contains("not found") {
+        format!("USB printer {device} not found, check that it is plugged in and powered on")
+    } else if message.contains("Resource busy") {
+        format!("USB printer {device} is busy, another process may be using it")
+    } else {
+        "Failed to open usb driver".to_string()
     }
 }
 
-fn build_printer<D>(driver: D) -> Result<Printer<D>>
+fn build_printer<D>(driver: D) -> Printer<D>
 where
     D: Driver,
 {
-    let mut printer = Printer::new(
-        driver,
-        Protocol::default(),
-        Some(PrinterOptions::new(
-            Some(escpos::utils::PageCode::PC437),
-            None,
-            // Some(DebugMode::Dec), // set to None to disable debug
-            CPL,
-        )),
-    );
-    printer.flip(false)?;
-    printer.reset()?;
-
-    Ok(printer)
+    Printer::new(driver, Protocol::default(), Some(printer_options()))
 }
 
+/// Options every printer opens with. Hex debugging is set per job, see `with_debug_hex`.
+fn printer_options() -> PrinterOptions {
+    PrinterOptions::new(Some(CODE_PAGE), None, CPL)
+}
+
+/// Stop before the next page when a bidirectional printer reports its cover open. Output
+/// sent while it is open is buffered by the printer and comes out garbled once it closes.
+/// `query` is cleared when the printer leaves a query unanswered, so a printer that doesn't
+/// send status is waited on once per job rather than at every page.
+fn ensure_cover_closed(
+    printer: &printer::AnyPrinter,
+    report: &PrintReport,
+    query: &mut bool,
+) -> Result<()> {
+    if !*query {
+        return Ok(());
+    }
+    match printer.cover_open() {
+        Some(true) => {
+            let lines_printed = report.lines_printed;
+            log::warn!("Printer cover is open, stopping after {lines_printed} lines");
+            Err(RongtaError::CoverOpen { lines_printed }.into())
+        }
+        Some(false) => Ok(()),
+        None => {
+            *query = false;
+            Ok(())
+        }
+    }
+}
+
+/// Print a single line, returning how many characters were written
 fn print_line(
     line: &line::Line,
     printer: &mut printer::AnyPrinter,
     last_justify_content: &mut Justify,
     last_format_state: &mut FormatState,
-) -> anyhow::Result<()> {
+) -> anyhow::Result<usize> {
     if *last_justify_content != line.justify_content {
         line.justify_content.to_print_command(printer)?;
         *last_justify_content = line.justify_content;
@@ -207,12 +911,1542 @@ fn print_line(
         default.to_print_command(printer)?;
         *last_format_state = default;
     }
-    for styled_char in &line.chars {
-        if *last_format_state != styled_char.state {
-            styled_char.state.to_print_command(printer)?;
-            *last_format_state = styled_char.state;
+    if let Some(qr_code) = &line.qr_code {
+        qr_code.to_print_command(printer)?;
+        printer.feed()?;
+        return Ok(0);
+    }
+    let mut chars_printed = 0;
+    for (state, text) in line.trimmed_runs() {
+        if *last_format_state != state {
+            state.to_print_command(printer)?;
+            *last_format_state = state;
+        }
+        let text = text
+            .chars()
+            .map(elements::printable_char)
+            .collect::<Result<String>>()?;
+        printer.write(&text)?;
+        chars_printed += text.chars().count();
+    }
+    printer.feed()?;
+    Ok(chars_printed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use printer::DumpDriver;
+
+    fn dump_printer(driver: &DumpDriver) -> printer::AnyPrinter {
+        printer::AnyPrinter::from_existing(driver.clone())
+    }
+
+    fn printer_with_lines(count: usize) -> RongtaPrinter {
+        let mut printer = RongtaPrinter::new(true);
+        for i in 0..count {
+            printer.add_content(&format!("line {i}")).unwrap();
+            printer.new_line();
+        }
+        printer
+    }
+
+    fn contains(haystack: &[u8], needle: &str) -> bool {
+        haystack
+            .windows(needle.len())
+            .any(|window| window == needle.as_bytes())
+    }
+
+    mod add_autosized_text {
+        use super::*;
+
+        fn first_char_size(printer: &RongtaPrinter) -> TextSize {
+            printer.lines[0].chars[0].state.text_size
+        }
+
+        #[test]
+        fn short_text_uses_extra_large() {
+            let mut printer = RongtaPrinter::new(false);
+            printer.add_autosized_text("Ten chars!").unwrap();
+            assert_eq!(first_char_size(&printer), TextSize::ExtraLarge);
+            assert_eq!(printer.lines[0].justify_content, Justify::Center);
+        }
+
+        #[test]
+        fn medium_length_text_uses_large() {
+            let mut printer = RongtaPrinter::new(false);
+            printer
+                .add_autosized_text("Twenty characters long")
+                .unwrap();
+            assert_eq!(first_char_size(&printer), TextSize::Large);
+        }
+
+        #[test]
+        fn long_text_uses_medium() {
+            let mut printer = RongtaPrinter::new(false);
+            printer
+                .add_autosized_text("This banner is thirty chars!!!")
+                .unwrap();
+            assert_eq!(first_char_size(&printer), TextSize::Medium);
+            assert_eq!(printer.lines.len(), 1);
+        }
+    }
+
+    mod resume_print {
+        use super::*;
+
+        #[test]
+        fn resumes_from_last_printed_page_after_reconnect() {
+            let builder = printer_with_lines(4);
+            // flip + reset + two lines (one write per style run plus a feed) + cut
+            let dropped = DumpDriver::failing_after(7);
+            let mut report = PrintReport::default();
+            let result = builder.print_lines(&mut dump_printer(&dropped), Some(2), &mut report);
+            assert!(result.is_err());
+            assert_eq!(report.lines_printed, 2);
+            assert!(contains(&dropped.bytes(), "line 1"));
+            assert!(!contains(&dropped.bytes(), "line 2"));
+
+            let reconnected = DumpDriver::new();
+            builder
+                .resume_print(|| Ok(dump_printer(&reconnected)), Some(2), report)
+                .unwrap();
+            let resumed = reconnected.bytes();
+            assert!(!contains(&resumed, "line 1"));
+            assert!(contains(&resumed, "line 2"));
+            assert!(contains(&resumed, "line 3"));
+        }
+
+        #[test]
+        fn reports_lines_printed_when_reconnect_fails() {
+            let builder = printer_with_lines(4);
+            let err = builder
+                .resume_print(
+                    || Ok(dump_printer(&DumpDriver::failing_after(0))),
+                    Some(2),
+                    PrintReport {
+                        lines_printed: 2,
+                        ..Default::default()
+                    },
+                )
+                .unwrap_err();
+            assert_eq!(
+                err.downcast_ref::<RongtaError>(),
+                Some(&RongtaError::Disconnected { lines_printed: 2 })
+            );
+        }
+    }
+
+    mod page_separator {
+        use super::*;
+
+        fn printed_with(separator: PageSeparator) -> Vec<u8> {
+            let mut builder = printer_with_lines(2);
+            builder.set_page_separator(separator);
+            let driver = DumpDriver::new();
+            builder
+                .print_to(&mut dump_printer(&driver), Some(2))
+                .unwrap();
+            driver.bytes()
+        }
+
+        #[test]
+        fn cut_emits_full_cut() {
+            assert!(printed_with(PageSeparator::Cut).ends_with(&[0x1D, b'V', b'A', 0]));
+        }
+
+        #[test]
+        fn form_feed_emits_feeds_without_cut() {
+            let bytes = printed_with(PageSeparator::FormFeed(6));
+            assert!(bytes.ends_with(&[0x1B, b'd', 6]));
+            assert!(!contains(&bytes, "\x1DVA"));
+        }
+
+        #[test]
+        fn black_mark_emits_gs_ff_without_cut() {
+            let bytes = printed_with(PageSeparator::BlackMark);
+            assert!(bytes.ends_with(&[0x1D, 0x0C]));
+            assert!(!contains(&bytes, "\x1DVA"));
+        }
+
+        #[test]
+        fn flushes_once_per_page() {
+            for separator in [
+                PageSeparator::Cut,
+                PageSeparator::FormFeed(6),
+                PageSeparator::BlackMark,
+            ] {
+                // 4 lines make 2 pages of 2 rows, the trailing empty line is dropped
+                let mut builder = printer_with_lines(4);
+                builder.set_page_separator(separator);
+                let driver = DumpDriver::new();
+                let report = builder
+                    .print_to_detailed(&mut dump_printer(&driver), Some(2))
+                    .unwrap();
+                assert_eq!(report.pages, 2);
+                assert_eq!(driver.flush_count(), 2, "{separator:?}");
+            }
+        }
+    }
+
+    mod emit_cut {
+        use super::*;
+
+        fn printed(cut: bool, rows: Option<u32>) -> Vec<u8> {
+            let builder = printer_with_lines(2).with_cut(cut);
+            let driver = DumpDriver::new();
+            builder
+                .with_cutter_offset(2)
+                .print_to(&mut dump_printer(&driver), rows)
+                .unwrap();
+            driver.bytes()
+        }
+
+        #[test]
+        fn paginated_and_single_page_jobs_end_alike() {
+            for cut in [true, false] {
+                assert_eq!(printed(cut, Some(2)), printed(cut, None), "cut: {cut}");
+            }
+            assert!(printed(true, Some(2)).ends_with(&[0x1B, b'd', 2, 0x1D, b'V', b'A', 0]));
+        }
+
+        #[test]
+        fn paginated_jobs_respect_cut() {
+            assert!(!contains(&printed(false, Some(1)), "\x1DVA"));
+
+            let mut builder = printer_with_lines(1).with_cut(false);
+            builder.page_break();
+            builder.add_content("next").unwrap();
+            let driver = DumpDriver::new();
+            let report = builder
+                .print_to_detailed(&mut dump_printer(&driver), None)
+                .unwrap();
+            assert!(!contains(&driver.bytes(), "\x1DVA"));
+            assert!(!report.cut);
+            assert_eq!(driver.flush_count(), 2);
+        }
+    }
+
+    mod with_cpl {
+        use super::*;
+
+        #[test]
+        fn wraps_at_the_configured_width() {
+            let mut printer = RongtaPrinter::new(false).with_cpl(32);
+            printer
+                .add_content("this sentence is long enough to need wrapping on narrow paper")
+                .unwrap();
+            assert!(printer.lines.len() > 1);
+            assert!(printer.lines.iter().all(|line| line.chars.len() <= 32));
+            assert!(printer.lines[0].chars.len() > 24);
+        }
+
+        #[test]
+        fn defaults_to_cpl() {
+            assert_eq!(RongtaPrinter::new(false).cpl(), CPL);
+        }
+    }
+
+    mod trailing_whitespace {
+        use super::*;
+
+        fn printed_centered(content: &str) -> Vec<u8> {
+            let mut builder = RongtaPrinter::new(false);
+            builder.set_justify_content(Justify::Center);
+            builder.add_content(content).unwrap();
+            let driver = DumpDriver::new();
+            builder.print_to(&mut dump_printer(&driver), None).unwrap();
+            driver.bytes()
+        }
+
+        #[test]
+        fn centered_line_prints_as_if_trimmed() {
+            assert_eq!(
+                printed_centered("centered   "),
+                printed_centered("centered")
+            );
+        }
+
+        #[test]
+        fn leading_whitespace_is_kept() {
+            assert_ne!(printed_centered("  indented"), printed_centered("indented"));
+        }
+    }
+
+    mod print_report {
+        use super::*;
+
+        #[test]
+        fn counts_unpaginated_job() {
+            let builder = printer_with_lines(2);
+            let driver = DumpDriver::new();
+            let report = builder
+                .print_to_detailed(&mut dump_printer(&driver), None)
+                .unwrap();
+            assert_eq!(
+                report,
+                PrintReport {
+                    lines_printed: 2,
+                    bytes_sent: 12,
+                    pages: 1,
+                    cut: true,
+                }
+            );
+        }
+
+        #[test]
+        fn counts_pages() {
+            let builder = printer_with_lines(4);
+            let driver = DumpDriver::new();
+            let report = builder
+                .print_to_detailed(&mut dump_printer(&driver), Some(2))
+                .unwrap();
+            assert_eq!(report.lines_printed, 4);
+            assert_eq!(report.bytes_sent, 24);
+            assert_eq!(report.pages, 2);
+            assert_eq!(report.to_string(), "Printed 4 lines across 2 pages");
+        }
+    }
+
+    mod add_qr_code {
+        use super::*;
+
+        #[test]
+        fn places_code_on_its_own_line() {
+            let mut builder = RongtaPrinter::default();
+            builder.add_content("Scan me").unwrap();
+            builder.add_qr_code("https://example.com", 6).unwrap();
+            builder.add_content("below").unwrap();
+            let lines = builder.lines();
+            assert_eq!(lines.len(), 3);
+            assert!(lines[0].qr_code.is_none());
+            assert_eq!(
+                lines[1].qr_code,
+                Some(elements::QrCode::new("https://example.com", 6).unwrap())
+            );
+            assert_eq!(lines[1].justify_content, Justify::Center);
+            assert_eq!(lines[2].chars.len(), 5);
+        }
+
+        #[test]
+        fn rejects_size_out_of_range() {
+            let mut builder = RongtaPrinter::default();
+            assert!(builder.add_qr_code("data", 0).is_err());
+            assert!(builder.add_qr_code("data", 16).is_err());
+            assert!(builder.lines().is_empty());
+        }
+
+        #[test]
+        fn prints_qr_symbol() {
+            let mut builder = RongtaPrinter::default();
+            builder.add_qr_code("konan", 4).unwrap();
+            let driver = DumpDriver::new();
+            builder.print_to(&mut dump_printer(&driver), None).unwrap();
+            let bytes = driver.bytes();
+            assert!(contains(&bytes, "konan"));
+            // GS ( k: print the stored symbol
+            assert!(
+                bytes
+                    .windows(8)
+                    .any(|w| w == [0x1D, 0x28, 0x6B, 3, 0, 49, 81, 48])
+            );
+        }
+    }
+
+    mod add_qr_with_caption {
+        use super::*;
+
+        #[test]
+        fn centers_caption_below_code() {
+            let mut builder = RongtaPrinter::default().with_cpl(16);
+            builder
+                .add_qr_with_caption("https://example.com/ticket", "Admit one, row F seat 12", 6)
+                .unwrap();
+            builder.add_content("after").unwrap();
+            let lines = builder.lines();
+            assert_eq!(lines.iter().filter(|l| l.qr_code.is_some()).count(), 1);
+            assert!(lines[0].qr_code.is_some());
+            let captions = &lines[1..lines.len() - 1];
+            assert!(!captions.is_empty());
+            assert!(
+                captions
+                    .iter()
+                    .all(|l| l.justify_content == Justify::Center)
+            );
+            let caption: Vec<String> = captions
+                .iter()
+                .map(|l| l.trimmed_chars().iter().map(|sc| sc.ch).collect())
+                .collect();
+            assert_eq!(caption.join(" "), "Admit one, row F seat 12");
+            assert_eq!(lines.last().unwrap().justify_content, Justify::default());
+        }
+
+        #[test]
+        fn rejects_invalid_code_without_caption() {
+            let mut builder = RongtaPrinter::default();
+            assert!(builder.add_qr_with_caption("", "caption", 6).is_err());
+            assert!(builder.lines().is_empty());
+        }
+    }
+
+    mod describe_usb_open_error {
+        use super::*;
+
+        #[test]
+        fn permission_denied_suggests_udev_rule() {
+            let error = escpos::errors::PrinterError::Io(
+                "Access denied (insufficient permissions)".to_string(),
+            );
+            let message = describe_usb_open_error(&error.to_string(), 0x0FE6, 0x811E);
+            assert!(message.starts_with("Permission denied opening USB printer 0fe6:811e"));
+            assert!(message.contains("ATTRS{idVendor}==\"0fe6\""));
+            assert!(message.contains("dialout"));
+        }
+
+        #[test]
+        fn missing_device_is_distinct() {
+            let message = describe_usb_open_error("IO error: USB device not found", 0x0FE6, 0x811E);
+            assert_eq!(
+                message,
+                "USB printer 0fe6:811e not found, check that it is plugged in and powered on"
+            );
+        }
+
+        #[test]
+        fn unknown_errors_stay_generic() {
+            let message = describe_usb_open_error("IO error: Pipe error", 1, 2);
+            assert_eq!(message, "Failed to open usb driver");
+        }
+    }
+
+    mod add_content {
+        use super::*;
+
+        #[test]
+        fn wraps_when_a_space_lands_exactly_at_the_width() {
+            let mut builder = RongtaPrinter::default().with_cpl(12);
+            builder.add_content("this line is too long").unwrap();
+            let lines: Vec<String> = builder
+                .lines()
+                .iter()
+                .map(|line| line.chars.iter().map(|sc| sc.ch).collect())
+                .collect();
+            assert_eq!(lines, ["this line is", "too long"]);
+        }
+
+        #[test]
+        fn expands_ellipsis_before_printing() {
+            let mut builder = RongtaPrinter::default();
+            builder
+                .add_content("Wait\u{2026} what\u{2019}s next")
+                .unwrap();
+            let text: String = builder.lines()[0].chars.iter().map(|sc| sc.ch).collect();
+            assert_eq!(text, "Wait... what's next");
+
+            let driver = DumpDriver::new();
+            builder.print_to(&mut dump_printer(&driver), None).unwrap();
+            assert!(contains(&driver.bytes(), "Wait... what's next"));
+        }
+
+        #[test]
+        fn encodes_accented_letters_as_cp437() {
+            let mut builder = RongtaPrinter::default();
+            builder.add_content("café").unwrap();
+            let driver = DumpDriver::new();
+            builder.print_to(&mut dump_printer(&driver), None).unwrap();
+            // 'é' is 0x82 in code page 437
+            let encoded = [b'c', b'a', b'f', 0x82];
+            assert!(driver.bytes().windows(4).any(|window| window == encoded));
+        }
+
+        fn texts(builder: &RongtaPrinter) -> Vec<String> {
+            builder
+                .lines()
+                .iter()
+                .map(|line| line.chars.iter().map(|sc| sc.ch).collect())
+                .collect()
+        }
+
+        #[test]
+        fn mixed_line_endings_break_lines() {
+            let mut builder = RongtaPrinter::default();
+            builder.add_content("one\r\ntwo\rthree\nfour").unwrap();
+            assert_eq!(texts(&builder), ["one", "two", "three", "four"]);
+            assert!(builder.lines().iter().all(|line| line.visual_width() <= 5));
+        }
+
+        #[test]
+        fn blank_lines_are_kept() {
+            let mut builder = RongtaPrinter::default();
+            builder.add_content("a\r\n\r\nb\n\nc").unwrap();
+            assert_eq!(texts(&builder), ["a", "", "b", "", "c"]);
+        }
+
+        #[test]
+        fn new_lines_keep_the_justification() {
+            let mut builder = RongtaPrinter::default();
+            builder.set_justify_content(Justify::Center);
+            builder.add_content("title\r\nsubtitle").unwrap();
+            assert_eq!(builder.lines()[1].justify_content, Justify::Center);
+        }
+    }
+
+    mod page_break {
+        use super::*;
+        use printer::{PrinterCommand, RecordingPrinter};
+
+        fn two_sections() -> RongtaPrinter {
+            let mut builder = RongtaPrinter::new(true);
+            for line in ["a1", "a2"] {
+                builder.add_content(line).unwrap();
+                builder.new_line();
+            }
+            builder.page_break();
+            for line in ["b1", "b2"] {
+                builder.add_content(line).unwrap();
+                builder.new_line();
+            }
+            builder
+        }
+
+        #[test]
+        fn cuts_between_sections_whatever_the_rows() {
+            // Rows of 3 also checks the break restarts the row count, or "b1" would end a page
+            for rows in [None, Some(3), Some(50)] {
+                let recorder = RecordingPrinter::new();
+                let report = two_sections()
+                    .print_to_detailed(&mut printer::AnyPrinter::from(recorder.clone()), rows)
+                    .unwrap();
+                let commands = recorder.commands();
+                let cuts: Vec<usize> = commands
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, command)| **command == PrinterCommand::Cut)
+                    .map(|(index, _)| index)
+                    .collect();
+                assert_eq!(cuts.len(), 2, "rows {rows:?}");
+                assert_eq!(report.pages, 2, "rows {rows:?}");
+                let first_page = &commands[..cuts[0]];
+                assert!(first_page.contains(&PrinterCommand::Write("a2".to_string())));
+                assert!(!first_page.contains(&PrinterCommand::Write("b1".to_string())));
+            }
+        }
+
+        #[test]
+        fn leading_break_is_skipped() {
+            let mut builder = RongtaPrinter::new(true);
+            builder.page_break();
+            builder.add_content("only").unwrap();
+            let recorder = RecordingPrinter::new();
+            builder
+                .print_to(&mut printer::AnyPrinter::from(recorder.clone()), None)
+                .unwrap();
+            let cuts = recorder
+                .commands()
+                .iter()
+                .filter(|command| **command == PrinterCommand::Cut)
+                .count();
+            assert_eq!(cuts, 1);
+        }
+    }
+
+    mod compact_preset {
+        use super::*;
+        use printer::{PrinterCommand, RecordingPrinter};
+
+        #[test]
+        fn sets_font_spacing_and_blank_line_cap() {
+            let mut builder = RongtaPrinter::default();
+            builder.compact_preset();
+            assert_eq!(builder.font, Font::B);
+            assert_eq!(builder.line_spacing, Some(COMPACT_LINE_SPACING));
+            assert_eq!(builder.max_blank_lines, Some(1));
+        }
+
+        #[test]
+        fn emits_settings_and_collapses_blank_lines() {
+            let mut builder = RongtaPrinter::new(false);
+            builder.compact_preset();
+            builder.add_content("a").unwrap();
+            for _ in 0..3 {
+                builder.new_line();
+            }
+            builder.add_content("b").unwrap();
+
+            let recorder = RecordingPrinter::new();
+            builder
+                .print_to(&mut printer::AnyPrinter::from(recorder.clone()), None)
+                .unwrap();
+            let commands = recorder.commands();
+            assert_eq!(
+                commands[2..4],
+                [
+                    PrinterCommand::Font(escpos::utils::Font::B),
+                    PrinterCommand::LineSpacing(COMPACT_LINE_SPACING)
+                ]
+            );
+            let feeds = commands
+                .iter()
+                .filter(|command| **command == PrinterCommand::Feed)
+                .count();
+            // "a", one blank line and "b"
+            assert_eq!(feeds, 3);
+        }
+    }
+
+    mod with_debug_hex {
+        use super::*;
+
+        #[test]
+        fn opens_printers_in_hex_debug_mode() {
+            let builder = RongtaPrinter::default().with_debug_hex(true);
+            let printer = builder
+                .open_printer(SupportedDriver::Dump(DumpDriver::new()))
+                .unwrap();
+            assert_eq!(printer.debug_mode(), Some(DebugMode::Hex));
+        }
+
+        #[test]
+        fn off_by_default() {
+            assert_eq!(printer_options().get_debug_mode(), None);
+            let printer = RongtaPrinter::default()
+                .open_printer(SupportedDriver::Dump(DumpDriver::new()))
+                .unwrap();
+            assert_eq!(printer.debug_mode(), None);
+        }
+    }
+
+    mod set_rotation {
+        use super::*;
+
+        const ROTATE_ON: [u8; 3] = [0x1B, b'V', 1];
+        const ROTATE_OFF: [u8; 3] = [0x1B, b'V', 0];
+
+        fn position(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+            haystack.windows(needle.len()).rposition(|w| w == needle)
+        }
+
+        #[test]
+        fn cw90_rotates_and_resets_before_cut() {
+            let mut builder = printer_with_lines(2);
+            builder.set_rotation(Rotation::Cw90);
+            let driver = DumpDriver::new();
+            builder.print_to(&mut dump_printer(&driver), None).unwrap();
+            let bytes = driver.bytes();
+            let on = position(&bytes, &ROTATE_ON).unwrap();
+            let off = position(&bytes, &ROTATE_OFF).unwrap();
+            let text = position(&bytes, b"line 1").unwrap();
+            assert!(on < text && text < off);
+            assert!(bytes[off..].ends_with(&[0x1D, b'V', b'A', 0]));
+        }
+
+        #[test]
+        fn resets_on_every_page() {
+            let mut builder = printer_with_lines(3);
+            builder.set_rotation(Rotation::Cw90);
+            let driver = DumpDriver::new();
+            builder
+                .print_to(&mut dump_printer(&driver), Some(2))
+                .unwrap();
+            let bytes = driver.bytes();
+            let count = |needle: &[u8]| bytes.windows(3).filter(|w| *w == needle).count();
+            assert_eq!(count(&ROTATE_ON), 2);
+            // begin_job also switches rotation off once
+            assert_eq!(count(&ROTATE_OFF), 3);
+        }
+
+        #[test]
+        fn unrotated_jobs_only_reset_on_init() {
+            let builder = printer_with_lines(2);
+            let driver = DumpDriver::new();
+            builder.print_to(&mut dump_printer(&driver), None).unwrap();
+            let bytes = driver.bytes();
+            assert_eq!(bytes.windows(3).filter(|w| *w == ROTATE_OFF).count(), 1);
+            assert_eq!(position(&bytes, &ROTATE_ON), None);
+        }
+    }
+
+    mod from_existing {
+        use super::*;
+
+        #[test]
+        fn prints_several_jobs_through_one_printer() {
+            let driver = DumpDriver::new();
+            let mut printer = printer::AnyPrinter::from_existing(driver.clone());
+
+            let mut first = RongtaPrinter::new(true);
+            first.set_justify_content(Justify::Center);
+            first.set_is_bold(true);
+            first.add_content("first").unwrap();
+            let mut second = RongtaPrinter::new(true);
+            second.add_content("second").unwrap();
+
+            assert_eq!(
+                first.print_to_detailed(&mut printer, None).unwrap().pages,
+                1
+            );
+            let after_first = driver.bytes().len();
+            assert_eq!(
+                second.print_to_detailed(&mut printer, None).unwrap().pages,
+                1
+            );
+
+            assert_eq!(driver.flush_count(), 2);
+            let bytes = driver.bytes();
+            // the second job starts from a reset printer rather than the first job's styles
+            assert!(bytes[after_first..].starts_with(&[0x1B, b'V', 0, 0x1B, b'?', b'\n', 0]));
+            assert!(contains(&bytes[after_first..], "second"));
+        }
+    }
+
+    mod print_lines {
+        use super::*;
+
+        #[test]
+        fn failed_page_is_not_sent_with_the_next_job() {
+            let builder = printer_with_lines(4);
+            // the first page takes 7 writes, fail partway into the second
+            let driver = DumpDriver::failing_after(8);
+            let mut printer = dump_printer(&driver);
+            assert!(builder.print_to(&mut printer, Some(2)).is_err());
+            let before_next_job = driver.bytes().len();
+
+            driver.reconnect();
+            let mut next = RongtaPrinter::new(false);
+            next.add_content("next").unwrap();
+            next.print_to(&mut printer, None).unwrap();
+            let sent = &driver.bytes()[before_next_job..];
+            assert!(sent.starts_with(&[0x1B, b'V', 0, 0x1B, b'?', b'\n', 0]));
+            assert!(contains(sent, "next"));
+            assert!(!contains(sent, "line"));
+        }
+    }
+
+    mod finalize {
+        use super::*;
+
+        #[test]
+        fn cuts_even_without_cut() {
+            let mut builder = RongtaPrinter::new(false);
+            builder.add_content("last words").unwrap();
+            let driver = DumpDriver::new();
+            let report = builder.finalize(&mut dump_printer(&driver)).unwrap();
+            assert!(report.cut);
+            assert_eq!(driver.flush_count(), 1);
+            assert!(driver.bytes().ends_with(&[0x1D, b'V', b'A', 0]));
+        }
+
+        #[test]
+        fn never_cuts_without_a_cutter() {
+            let rp58 = profile::PrinterModel::RongtaRp58.profile().unwrap();
+            let mut builder = RongtaPrinter::new(false).with_profile(&rp58);
+            builder.add_content("last words").unwrap();
+            let driver = DumpDriver::new();
+            let report = builder.finalize(&mut dump_printer(&driver)).unwrap();
+            assert!(!report.cut);
+            assert_eq!(driver.flush_count(), 1);
+            assert!(!driver.bytes().windows(2).any(|w| w == [0x1D, b'V']));
+        }
+    }
+
+    mod cover_open {
+        use super::*;
+
+        /// Offline cause replies, `0xx1xx10` with bit 2 set while the cover is open
+        const COVER_CLOSED: u8 = 0b0001_0010;
+        const COVER_OPEN: u8 = 0b0001_0110;
+
+        #[test]
+        fn stops_at_the_page_boundary() {
+            let builder = printer_with_lines(4);
+            let driver = DumpDriver::new();
+            driver.answer_status(COVER_CLOSED);
+            driver.answer_status(COVER_OPEN);
+            let mut report = PrintReport::default();
+            let err = builder
+                .print_lines(&mut dump_printer(&driver), Some(2), &mut report)
+                .unwrap_err();
+            assert_eq!(
+                err.downcast_ref::<RongtaError>(),
+                Some(&RongtaError::CoverOpen { lines_printed: 2 })
+            );
+            assert_eq!(report.pages, 1);
+            let bytes = driver.bytes();
+            assert!(contains(&bytes, "line 1"));
+            assert!(!contains(&bytes, "line 2"));
+            assert!(!contains(&bytes, "line 3"));
+        }
+
+        #[test]
+        fn prints_while_closed() {
+            let builder = printer_with_lines(4);
+            let driver = DumpDriver::new();
+            driver.answer_status(COVER_CLOSED);
+            builder
+                .print_to(&mut dump_printer(&driver), Some(2))
+                .unwrap();
+            assert!(contains(&driver.bytes(), "line 3"));
+        }
+
+        #[test]
+        fn unanswered_query_is_not_repeated() {
+            let builder = printer_with_lines(4);
+            let driver = DumpDriver::new();
+            // Not an offline cause reply, then an open cover that is never asked about
+            driver.answer_status(0xFF);
+            driver.answer_status(COVER_OPEN);
+            builder
+                .print_to(&mut dump_printer(&driver), Some(2))
+                .unwrap();
+            let bytes = driver.bytes();
+            let queries = bytes
+                .windows(3)
+                .filter(|w| *w == [0x10, 0x04, 0x02])
+                .count();
+            assert_eq!(queries, 1);
+            assert!(contains(&bytes, "line 3"));
+        }
+
+        #[test]
+        fn write_only_printers_are_not_queried() {
+            let driver = DumpDriver::new();
+            assert_eq!(dump_printer(&driver).cover_open(), None);
+            assert!(driver.bytes().is_empty());
+        }
+    }
+
+    mod with_strict_width {
+        use super::*;
+
+        fn with_overwide_line(strict: bool) -> RongtaPrinter {
+            let mut builder = RongtaPrinter::new(false)
+                .with_cpl(8)
+                .with_strict_width(strict);
+            builder.add_content("fits").unwrap();
+            let chars = "too wide to fit"
+                .chars()
+                .map(|ch| elements::StyledChar {
+                    ch,
+                    state: FormatState::default(),
+                })
+                .collect();
+            builder.lines.push(line::Line::new(chars, Justify::Left));
+            builder
+        }
+
+        #[test]
+        fn rejects_overwide_line() {
+            let driver = DumpDriver::new();
+            let err = with_overwide_line(true)
+                .print_to(&mut dump_printer(&driver), None)
+                .unwrap_err();
+            assert_eq!(
+                err.to_string(),
+                "Line 2 is 15 columns wide, over the 8 column limit: \"too wide to fit\""
+            );
+            assert!(driver.bytes().is_empty());
+        }
+
+        #[test]
+        fn only_warns_by_default() {
+            let driver = DumpDriver::new();
+            with_overwide_line(false)
+                .print_to(&mut dump_printer(&driver), None)
+                .unwrap();
+            assert!(contains(&driver.bytes(), "too wide to fit"));
+        }
+
+        #[test]
+        fn wrapped_content_passes() {
+            let mut builder = RongtaPrinter::new(false)
+                .with_cpl(8)
+                .with_strict_width(true);
+            builder.add_content("wrapped by add content").unwrap();
+            builder
+                .print_to(&mut dump_printer(&DumpDriver::new()), None)
+                .unwrap();
+        }
+    }
+
+    mod with_cutter_offset {
+        use super::*;
+
+        const FEEDS_THEN_CUT: [u8; 7] = [0x1B, b'd', 3, 0x1D, b'V', b'A', 0];
+
+        fn occurrences(haystack: &[u8], needle: &[u8]) -> usize {
+            haystack
+                .windows(needle.len())
+                .filter(|w| *w == needle)
+                .count()
+        }
+
+        #[test]
+        fn feeds_before_every_page_cut() {
+            let builder = printer_with_lines(4).with_cutter_offset(3);
+            let driver = DumpDriver::new();
+            let report = builder
+                .print_to_detailed(&mut dump_printer(&driver), Some(2))
+                .unwrap();
+            let bytes = driver.bytes();
+            assert!(report.pages >= 2);
+            assert_eq!(occurrences(&bytes, &FEEDS_THEN_CUT), report.pages);
+            assert_eq!(occurrences(&bytes, &[0x1D, b'V', b'A', 0]), report.pages);
+        }
+
+        #[test]
+        fn feeds_before_the_final_cut() {
+            let mut builder = RongtaPrinter::new(true).with_cutter_offset(3);
+            builder.add_content("hello").unwrap();
+            let driver = DumpDriver::new();
+            builder.print_to(&mut dump_printer(&driver), None).unwrap();
+            assert!(driver.bytes().ends_with(&FEEDS_THEN_CUT));
+        }
+
+        #[test]
+        fn trimmed_blank_lines_keep_the_cutter_feed() {
+            let mut builder = RongtaPrinter::new(true).with_cutter_offset(3);
+            builder.add_content("hello").unwrap();
+            for _ in 0..3 {
+                builder.new_line();
+            }
+            let driver = DumpDriver::new();
+            let report = builder
+                .print_to_detailed(&mut dump_printer(&driver), None)
+                .unwrap();
+            assert_eq!(report.lines_printed, 1);
+            assert!(driver.bytes().ends_with(&FEEDS_THEN_CUT));
+        }
+
+        #[test]
+        fn no_feeds_without_a_cut() {
+            let mut builder = RongtaPrinter::new(false).with_cutter_offset(3);
+            builder.add_content("hello").unwrap();
+            let driver = DumpDriver::new();
+            builder.print_to(&mut dump_printer(&driver), None).unwrap();
+            assert!(!contains(&driver.bytes(), "\x1Bd\x03"));
+        }
+
+        #[test]
+        fn form_feed_pages_are_not_offset() {
+            let mut builder = printer_with_lines(2).with_cutter_offset(3);
+            builder.set_page_separator(PageSeparator::FormFeed(6));
+            let driver = DumpDriver::new();
+            builder
+                .print_to(&mut dump_printer(&driver), Some(2))
+                .unwrap();
+            assert!(!contains(&driver.bytes(), "\x1Bd\x03"));
+        }
+    }
+
+    mod char_columns {
+        use super::*;
+        use elements::{StyledChar, char_columns};
+
+        fn styled(ch: char, text_size: TextSize) -> StyledChar {
+            StyledChar {
+                ch,
+                state: FormatState {
+                    text_size,
+                    ..Default::default()
+                },
+            }
+        }
+
+        #[test]
+        fn full_width_counts_as_two() {
+            assert_eq!(char_columns('漢'), 2);
+            assert_eq!(char_columns('Ａ'), 2);
+            assert_eq!(char_columns('a'), 1);
+        }
+
+        #[test]
+        fn scales_with_text_size() {
+            assert_eq!(styled('漢', TextSize::Medium).columns(), 2);
+            assert_eq!(styled('漢', TextSize::Large).columns(), 4);
+        }
+
+        #[test]
+        fn wide_characters_wrap_early() {
+            let mut printer = RongtaPrinter::new(false).with_cpl(6);
+            printer.add_content("漢字漢字").unwrap();
+            assert_eq!(printer.lines.len(), 2);
+            assert_eq!(printer.lines[0].visual_width(), 6);
+            assert_eq!(printer.lines[1].visual_width(), 2);
+        }
+    }
+
+    mod trim_trailing_blank_lines {
+        use super::*;
+
+        fn ending_with_blank_lines() -> RongtaPrinter {
+            let mut builder = RongtaPrinter::new(true);
+            builder.add_content("last words").unwrap();
+            builder.new_line();
+            builder.new_line();
+            builder.new_line();
+            builder
+        }
+
+        #[test]
+        fn trailing_blank_lines_are_not_printed() {
+            let driver = DumpDriver::new();
+            ending_with_blank_lines()
+                .print_to(&mut dump_printer(&driver), None)
+                .unwrap();
+            let bytes = driver.bytes();
+            let text = bytes
+                .windows(b"last words".len())
+                .position(|w| w == b"last words")
+                .unwrap();
+            // The text's own feed, then straight on to the cut
+            assert_eq!(
+                &bytes[text + b"last words".len()..],
+                &[0x1B, b'd', 1, 0x1D, b'V', b'A', 0]
+            );
+        }
+
+        #[test]
+        fn removes_only_the_trailing_lines() {
+            let mut builder = RongtaPrinter::new(true);
+            builder.new_line();
+            builder.new_line();
+            builder.add_content("kept").unwrap();
+            builder.new_line();
+            builder.new_line();
+            builder.trim_trailing_blank_lines();
+            assert_eq!(builder.lines().len(), 2);
+            assert!(builder.lines()[0].is_blank());
+        }
+
+        #[test]
+        fn keeps_trailing_reversed_spaces() {
+            let mut builder = RongtaPrinter::new(true);
+            builder.add_content("title").unwrap();
+            builder.new_line();
+            builder.set_is_reversed(true);
+            builder.add_content("      ").unwrap();
+            builder.new_line();
+            builder.trim_trailing_blank_lines();
+            assert_eq!(builder.lines().len(), 2);
+            assert!(builder.lines()[1].chars.iter().all(|sc| sc.ch == ' '));
+        }
+
+        #[test]
+        fn prints_trailing_reversed_spaces() {
+            let mut builder = RongtaPrinter::new(true);
+            builder.add_content("title").unwrap();
+            builder.new_line();
+            builder.set_is_reversed(true);
+            builder.add_content("      ").unwrap();
+            builder.new_line();
+            builder.set_max_blank_lines(Some(0));
+            assert!(!builder.lines()[1].is_blank());
+            let driver = DumpDriver::new();
+            builder.print_to(&mut dump_printer(&driver), None).unwrap();
+            let bytes = driver.bytes();
+            // Reverse on, Medium size, then the spaces
+            let reversed_spaces = [&[0x1D, b'B', 1, 0x1D, b'!', 0][..], b"      "].concat();
+            assert!(
+                bytes
+                    .windows(reversed_spaces.len())
+                    .any(|w| w == reversed_spaces)
+            );
+        }
+    }
+
+    mod append {
+        use super::*;
+
+        #[test]
+        fn lines_keep_their_justification_and_styles() {
+            let mut body = RongtaPrinter::new(false);
+            body.set_justify_content(Justify::Center);
+            body.set_is_bold(true);
+            body.add_content("centered").unwrap();
+            body.new_line();
+            body.new_line();
+
+            let mut builder = RongtaPrinter::new(false);
+            builder.add_content("before").unwrap();
+            builder.new_line();
+            builder.append(body);
+            builder.add_content("after").unwrap();
+
+            let texts: Vec<String> = builder
+                .lines()
+                .iter()
+                .map(|line| line.chars.iter().map(|sc| sc.ch).collect())
+                .collect();
+            assert_eq!(texts, ["before", "centered", "after"]);
+            assert_eq!(builder.lines()[1].justify_content, Justify::Center);
+            assert!(builder.lines()[1].chars.iter().all(|sc| sc.state.is_bold));
+            assert!(!builder.lines()[2].chars[0].state.is_bold);
+        }
+    }
+
+    mod validate {
+        use super::*;
+
+        #[test]
+        fn reports_the_location_of_unprintable_chars() {
+            let mut builder = RongtaPrinter::new(false);
+            builder
+                .add_content("Caf\u{e9} \u{201c}menu\u{201d}\nsoup \u{4e2d}")
+                .unwrap();
+            assert_eq!(
+                builder.validate(),
+                [UnprintableChar {
+                    line: 2,
+                    column: 6,
+                    ch: '\u{4e2d}'
+                }]
+            );
+            assert_eq!(
+                builder.validate()[0].to_string(),
+                "line 2, column 6: '\u{4e2d}' (U+4E2D)"
+            );
+        }
+
+        #[test]
+        fn clean_document_has_no_problems() {
+            let builder = printer_with_lines(3);
+            assert!(builder.validate().is_empty());
+        }
+    }
+
+    mod from_lines {
+        use super::*;
+
+        #[test]
+        fn entries_wrap_with_their_justification() {
+            let long = "word ".repeat(12);
+            let builder = RongtaPrinter::from_lines(vec![
+                (long.trim_end().to_string(), Justify::Center),
+                ("total".to_string(), Justify::Right),
+            ])
+            .unwrap();
+
+            let lines: Vec<(String, Justify)> = builder
+                .lines()
+                .iter()
+                .filter(|line| !line.is_unstarted())
+                .map(|line| {
+                    (
+                        line.chars.iter().map(|sc| sc.ch).collect(),
+                        line.justify_content,
+                    )
+                })
+                .collect();
+            assert_eq!(lines.len(), 3);
+            assert!(
+                lines[..2]
+                    .iter()
+                    .all(|(_, justify)| *justify == Justify::Center)
+            );
+            assert!(lines[0].0.chars().count() <= CPL as usize);
+            assert_eq!(lines[2], ("total".to_string(), Justify::Right));
+        }
+    }
+
+    mod insert_line_at {
+        use super::*;
+
+        fn texts(builder: &RongtaPrinter) -> Vec<String> {
+            builder
+                .lines()
+                .iter()
+                .map(|line| line.chars.iter().map(|sc| sc.ch).collect())
+                .collect()
+        }
+
+        fn header() -> line::Line {
+            RongtaPrinter::from_lines(vec![("header".to_string(), Justify::Left)])
+                .unwrap()
+                .into_lines()
+                .remove(0)
+        }
+
+        #[test]
+        fn inserts_at_the_top() {
+            let mut builder = RongtaPrinter::new(false);
+            builder.add_content("body").unwrap();
+            builder.insert_line_at(0, header()).unwrap();
+            assert_eq!(texts(&builder), ["header", "body"]);
+        }
+
+        #[test]
+        fn inserts_at_the_end() {
+            let mut builder = RongtaPrinter::new(false);
+            builder.add_content("body").unwrap();
+            let len = builder.lines().len();
+            builder.insert_line_at(len, header()).unwrap();
+            assert_eq!(texts(&builder), ["body", "header"]);
+        }
+
+        #[test]
+        fn out_of_range_index_errors() {
+            let mut builder = RongtaPrinter::new(false);
+            builder.add_content("body").unwrap();
+            assert!(builder.insert_line_at(5, header()).is_err());
+            assert!(builder.remove_line(1).is_err());
+            assert_eq!(texts(&builder), ["body"]);
+        }
+
+        #[test]
+        fn remove_line_returns_it() {
+            let mut builder = RongtaPrinter::new(false);
+            builder.add_content("body").unwrap();
+            builder.insert_line_at(0, header()).unwrap();
+            let removed = builder.remove_line(0).unwrap();
+            assert_eq!(
+                removed.chars.iter().map(|sc| sc.ch).collect::<String>(),
+                "header"
+            );
+            assert_eq!(texts(&builder), ["body"]);
+        }
+    }
+
+    mod with_profile {
+        use super::*;
+        use profile::PrinterModel;
+
+        #[test]
+        fn profile_sets_cpl_and_cut_support() {
+            let rp58 = "Rongta RP58".parse::<PrinterModel>().unwrap();
+            let builder = RongtaPrinter::new(true).with_profile(&rp58.profile().unwrap());
+            assert_eq!(builder.cpl(), 32);
+            assert!(!builder.cut);
+
+            let tm_t20 = "tm-t20".parse::<PrinterModel>().unwrap();
+            let builder = RongtaPrinter::new(true).with_profile(&tm_t20.profile().unwrap());
+            assert_eq!(builder.cpl(), 48);
+            assert!(builder.cut);
+        }
+
+        #[test]
+        fn later_cpl_and_cut_stay_within_the_profile() {
+            let rp58 = PrinterModel::RongtaRp58.profile().unwrap();
+            let builder = RongtaPrinter::new(false)
+                .with_profile(&rp58)
+                .with_cpl(48)
+                .with_cut(true);
+            assert_eq!(builder.cpl(), 32);
+            assert!(!builder.cut);
+            assert_eq!(builder.with_cpl(24).cpl(), 24);
+        }
+
+        #[test]
+        fn known_profiles_support_the_job_code_page() {
+            for model in [
+                PrinterModel::RongtaRp80,
+                PrinterModel::RongtaRp58,
+                PrinterModel::EpsonTmT20,
+            ] {
+                assert!(model.profile().unwrap().code_pages.contains(&CODE_PAGE));
+            }
+        }
+
+        #[test]
+        fn profile_without_the_code_page_is_refused() {
+            let profile = profile::PrinterProfile {
+                code_pages: &[PageCode::PC850],
+                ..PrinterModel::RongtaRp80.profile().unwrap()
+            };
+            let builder = printer_with_lines(1).with_profile(&profile);
+            let recording = printer::RecordingPrinter::new();
+            let err = builder
+                .print_to(&mut recording.clone().into(), None)
+                .unwrap_err();
+            assert!(err.to_string().contains("PC437"));
+            assert!(recording.commands().is_empty());
+        }
+
+        #[test]
+        fn narrower_cpl_is_kept() {
+            let rp80 = PrinterModel::RongtaRp80.profile().unwrap();
+            let builder = RongtaPrinter::new(true).with_cpl(32).with_profile(&rp80);
+            assert_eq!(builder.cpl(), 32);
+        }
+
+        #[test]
+        fn font_b_wraps_at_the_profile_width() {
+            let rp80 = PrinterModel::RongtaRp80.profile().unwrap();
+            let mut builder = RongtaPrinter::new(true).with_profile(&rp80);
+            builder.compact_preset();
+            assert_eq!(builder.cpl(), 64);
+
+            let rp58 = PrinterModel::RongtaRp58.profile().unwrap();
+            let mut builder = RongtaPrinter::new(true).with_profile(&rp58);
+            builder.set_font(Font::B);
+            assert_eq!(builder.cpl(), 42);
+            builder.add_content(&"x".repeat(43)).unwrap();
+            assert_eq!(builder.lines().len(), 2);
+        }
+
+        #[test]
+        fn font_b_scales_with_a_narrower_cpl() {
+            let rp80 = PrinterModel::RongtaRp80.profile().unwrap();
+            let mut builder = RongtaPrinter::new(true).with_cpl(24).with_profile(&rp80);
+            builder.set_font(Font::B);
+            assert_eq!(builder.cpl(), 32);
+        }
+
+        #[test]
+        fn font_b_without_a_profile_keeps_cpl() {
+            let mut builder = RongtaPrinter::new(true);
+            builder.set_font(Font::B);
+            assert_eq!(builder.cpl(), CPL);
+        }
+
+        #[test]
+        fn custom_has_no_profile() {
+            assert_eq!("custom".parse::<PrinterModel>().unwrap().profile(), None);
+            assert!("TM-U220".parse::<PrinterModel>().is_err());
+        }
+    }
+
+    mod with_no_init {
+        use super::*;
+        use printer::{PrinterCommand, RecordingPrinter};
+
+        fn commands(builder: RongtaPrinter) -> Vec<PrinterCommand> {
+            let recording = RecordingPrinter::new();
+            builder
+                .print_to(&mut recording.clone().into(), None)
+                .unwrap();
+            recording.commands()
+        }
+
+        #[test]
+        fn skips_the_reset() {
+            let commands = commands(printer_with_lines(2).with_no_init(true));
+            assert!(!commands.contains(&PrinterCommand::Reset));
+            assert!(!commands.contains(&PrinterCommand::Flip(false)));
+            assert!(commands.contains(&PrinterCommand::Cut));
+        }
+
+        #[test]
+        fn resets_by_default() {
+            let commands = commands(printer_with_lines(2));
+            assert_eq!(
+                commands[..2],
+                [PrinterCommand::Flip(false), PrinterCommand::Reset]
+            );
+        }
+    }
+
+    mod build_any_printer {
+        use super::*;
+
+        #[test]
+        fn file_keeps_every_job() {
+            let path =
+                std::env::temp_dir().join(format!("konan-file-driver-{}.bin", std::process::id()));
+            let _ = std::fs::remove_file(&path);
+            let builder = RongtaPrinter::new(false).with_no_init(true);
+            for text in ["first job", "second job"] {
+                builder
+                    .print_raw(text, SupportedDriver::File(path.clone()))
+                    .unwrap();
+            }
+            let bytes = std::fs::read(&path).unwrap();
+            std::fs::remove_file(&path).unwrap();
+            let position = |text: &[u8]| bytes.windows(text.len()).position(|w| w == text);
+            assert!(position(b"first job").is_some());
+            assert!(position(b"first job") < position(b"second job"));
+        }
+    }
+
+    mod print_raw_to {
+        use super::*;
+        use printer::{PrinterCommand, RecordingPrinter};
+
+        fn commands(builder: RongtaPrinter, text: &str) -> (Vec<PrinterCommand>, PrintReport) {
+            let recording = RecordingPrinter::new();
+            let report = builder
+                .print_raw_to(&mut recording.clone().into(), text)
+                .unwrap();
+            (recording.commands(), report)
+        }
+
+        #[test]
+        fn sends_text_as_is() {
+            let (commands, report) = commands(RongtaPrinter::new(true), "a  b\n  c\n");
+            assert!(commands.contains(&PrinterCommand::Write("a  b\n  c\n".to_string())));
+            assert_eq!(commands.last(), Some(&PrinterCommand::Cut));
+            assert_eq!(report.lines_printed, 2);
+            assert_eq!(report.bytes_sent, 9);
+        }
+
+        #[test]
+        fn feeds_the_cutter_offset_before_the_cut() {
+            let builder = RongtaPrinter::new(true).with_cutter_offset(3);
+            let (commands, _) = commands(builder, "text");
+            assert_eq!(
+                commands[commands.len() - 2..],
+                [PrinterCommand::Feeds(3), PrinterCommand::Cut]
+            );
+        }
+
+        #[test]
+        fn no_init_skips_the_reset() {
+            let builder = RongtaPrinter::new(false).with_no_init(true);
+            let (commands, report) = commands(builder, "text");
+            assert!(!commands.contains(&PrinterCommand::Reset));
+            assert_eq!(commands.last(), Some(&PrinterCommand::Print));
+            assert!(!report.cut);
+        }
+    }
+
+    mod wake {
+        use super::*;
+        use printer::{PrinterCommand, RecordingPrinter};
+
+        #[test]
+        fn sends_only_an_initialize() {
+            let driver = DumpDriver::new();
+            dump_printer(&driver).wake(false).unwrap();
+            // ESC @, then escpos selects the configured code page again (ESC t 0 for PC437)
+            assert_eq!(driver.bytes(), [0x1B, b'@', 0x1B, b't', 0]);
+
+            let recording = RecordingPrinter::new();
+            printer::AnyPrinter::from(recording.clone())
+                .wake(false)
+                .unwrap();
+            assert_eq!(
+                recording.commands(),
+                [PrinterCommand::Init, PrinterCommand::Print]
+            );
+        }
+
+        #[test]
+        fn no_init_keeps_the_printer_settings() {
+            let driver = DumpDriver::new();
+            dump_printer(&driver).wake(true).unwrap();
+            assert_eq!(driver.bytes(), [0x00]);
+            assert!(!driver.bytes().windows(2).any(|w| w == [0x1B, b'@']));
+        }
+    }
+
+    mod paginate {
+        use super::*;
+        use printer::{PrinterCommand, RecordingPrinter};
+
+        fn cuts(builder: &RongtaPrinter) -> usize {
+            let recording = RecordingPrinter::new();
+            let report = builder
+                .print_to_detailed(&mut recording.clone().into(), None)
+                .unwrap();
+            let cuts = recording
+                .commands()
+                .iter()
+                .filter(|c| **c == PrinterCommand::Cut)
+                .count();
+            assert_eq!(report.pages, cuts);
+            cuts
+        }
+
+        fn add_lines(builder: &mut RongtaPrinter, size: TextSize, count: usize) {
+            builder.set_text_size(size);
+            for index in 0..count {
+                builder.add_content(&format!("line {index}")).unwrap();
+                builder.new_line();
+            }
+        }
+
+        #[test]
+        fn inches_account_for_taller_lines() {
+            // 72 + 10 * 30 + 48 + 5 * 30 dots, about 2.8 inches
+            let mut builder = RongtaPrinter::new(true);
+            add_lines(&mut builder, TextSize::ExtraLarge, 1);
+            add_lines(&mut builder, TextSize::Medium, 10);
+            add_lines(&mut builder, TextSize::Large, 1);
+            add_lines(&mut builder, TextSize::Medium, 5);
+            assert!((builder.estimated_length_inches() - 570.0 / 203.0).abs() < 0.01);
+
+            builder.paginate(PageLength::Inches(1.0));
+            assert_eq!(cuts(&builder), 3);
+            // The heading and four lines fill the first inch
+            assert!(builder.lines()[5].page_break);
+        }
+
+        #[test]
+        fn rows_break_after_every_count() {
+            let mut builder = printer_with_lines(5);
+            builder.paginate(PageLength::Rows(2));
+            assert_eq!(cuts(&builder), 3);
+        }
+
+        #[test]
+        fn trailing_blank_lines_get_no_page() {
+            let mut builder = printer_with_lines(2);
+            builder.new_line();
+            builder.new_line();
+            builder.paginate(PageLength::Rows(2));
+            assert_eq!(cuts(&builder), 1);
+        }
+    }
+
+    mod set_char_spacing {
+        use super::*;
+        use printer::{PrinterCommand, RecordingPrinter};
+
+        #[test]
+        fn sent_once_at_job_start() {
+            let mut builder = printer_with_lines(2);
+            builder.set_char_spacing(3);
+            let recording = RecordingPrinter::new();
+            builder
+                .print_to(&mut recording.clone().into(), None)
+                .unwrap();
+            let spacing = PrinterCommand::Custom(vec![0x1B, b' ', 3]);
+            let commands = recording.commands();
+            assert_eq!(commands.iter().filter(|c| **c == spacing).count(), 1);
+            let first_write = commands
+                .iter()
+                .position(|c| matches!(c, PrinterCommand::Write(_)))
+                .unwrap();
+            assert!(commands[..first_write].contains(&spacing));
+        }
+
+        #[test]
+        fn wider_spacing_wraps_sooner() {
+            let content = "a sentence of forty five characters in length";
+            let mut plain = RongtaPrinter::new(false);
+            plain.add_content(content).unwrap();
+            let mut spaced = RongtaPrinter::new(false);
+            spaced.set_char_spacing(3);
+            spaced.add_content(content).unwrap();
+            assert_eq!(spaced.cpl(), 38);
+            assert_eq!(plain.lines().len(), 1);
+            assert_eq!(spaced.lines().len(), 2);
+            assert!(spaced.lines()[0].visual_width() <= 38);
+        }
+
+        #[test]
+        fn font_b_spacing_uses_the_font_b_width() {
+            let rp80 = profile::PrinterModel::RongtaRp80.profile().unwrap();
+            let mut builder = RongtaPrinter::new(true).with_profile(&rp80);
+            builder.set_font(Font::B);
+            builder.set_char_spacing(3);
+            // 576 dots over 9 dot characters with 3 dots of spacing each
+            assert_eq!(builder.cpl(), 48);
+            builder.add_content(&"x".repeat(49)).unwrap();
+            assert_eq!(builder.lines().len(), 2);
+        }
+
+        #[test]
+        fn no_command_by_default() {
+            let recording = RecordingPrinter::new();
+            printer_with_lines(1)
+                .print_to(&mut recording.clone().into(), None)
+                .unwrap();
+            assert!(
+                !recording
+                    .commands()
+                    .iter()
+                    .any(|c| matches!(c, PrinterCommand::Custom(cmd) if cmd.starts_with(b"\x1B ")))
+            );
         }
-        styled_char.to_print_command(printer)?;
     }
-    printer.feed()
 }